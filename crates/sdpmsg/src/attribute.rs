@@ -0,0 +1,436 @@
+use crate::{
+    common::{number, parse_all, space, token, tokens},
+    errorparse::SdpParseError,
+    session::Connection,
+};
+use alloc::vec::Vec;
+use core::fmt;
+use nom::{
+    bytes::complete::{tag, take_while1},
+    combinator::{opt, rest},
+    multi::many0,
+    sequence::{pair, preceded},
+};
+
+/// Mapping of RTP payload type to encoding, `a=rtpmap:`.
+/// [rfc8866 6.6](https://tools.ietf.org/html/rfc8866#section-6.6)
+#[derive(Clone, Debug, PartialEq)]
+pub struct RtpMap<'a> {
+    pub payload_type: u8,
+    pub encoding_name: &'a str,
+    pub clock_rate: u32,
+    /// Number of audio channels
+    pub encoding_params: Option<&'a str>,
+}
+
+impl<'a> RtpMap<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, RtpMap<'a>, SdpParseError<'a>> {
+        let (input, payload_type) = number(input)?;
+        let (input, encoding_name) = preceded(space, take_while1(|c| c != '/'))(input)?;
+        let (input, clock_rate) = preceded(tag("/"), number)(input)?;
+        let (input, encoding_params) = opt(preceded(tag("/"), take_while1(|c| c != ' ')))(input)?;
+        Ok((
+            input,
+            RtpMap {
+                payload_type,
+                encoding_name,
+                clock_rate,
+                encoding_params,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for RtpMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}",
+            self.payload_type, self.encoding_name, self.clock_rate
+        )?;
+        if let Some(encoding_params) = self.encoding_params {
+            write!(f, "/{}", encoding_params)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format specific parameters, `a=fmtp:`.
+/// [rfc8866 6.15](https://tools.ietf.org/html/rfc8866#section-6.15)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fmtp<'a> {
+    pub format: &'a str,
+    /// Ex: `profile-level-id=42e01f;packetization-mode=1`
+    pub params: &'a str,
+}
+
+impl<'a> Fmtp<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Fmtp<'a>, SdpParseError<'a>> {
+        let (input, format) = token(input)?;
+        let (input, params) = preceded(space, rest)(input)?;
+        Ok((input, Fmtp { format, params }))
+    }
+}
+
+/// Direction of media stream.
+/// [rfc8866 6.7](https://tools.ietf.org/html/rfc8866#section-6.7)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    SendRecv,
+    SendOnly,
+    RecvOnly,
+    Inactive,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::SendRecv => "sendrecv",
+            Direction::SendOnly => "sendonly",
+            Direction::RecvOnly => "recvonly",
+            Direction::Inactive => "inactive",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Direction> {
+        match s {
+            "sendrecv" => Some(Direction::SendRecv),
+            "sendonly" => Some(Direction::SendOnly),
+            "recvonly" => Some(Direction::RecvOnly),
+            "inactive" => Some(Direction::Inactive),
+            _ => None,
+        }
+    }
+
+    /// Direction of answer to this offer
+    pub fn reverse(&self) -> Direction {
+        match self {
+            Direction::SendOnly => Direction::RecvOnly,
+            Direction::RecvOnly => Direction::SendOnly,
+            direction => *direction,
+        }
+    }
+}
+
+/// Port and address of RTCP if it is not the next port of RTP, `a=rtcp:`.
+/// [rfc3605](https://tools.ietf.org/html/rfc3605)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rtcp<'a> {
+    pub port: u16,
+    pub connection: Option<Connection<'a>>,
+}
+
+impl<'a> Rtcp<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Rtcp<'a>, SdpParseError<'a>> {
+        let (input, port) = number(input)?;
+        let (input, connection) = opt(preceded(space, Connection::parse))(input)?;
+        Ok((input, Rtcp { port, connection }))
+    }
+}
+
+impl fmt::Display for Rtcp<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.port)?;
+        if let Some(connection) = &self.connection {
+            write!(f, " {}", connection)?;
+        }
+        Ok(())
+    }
+}
+
+/// ICE candidate, `a=candidate:`.
+/// [rfc8839 5.1](https://tools.ietf.org/html/rfc8839#section-5.1)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate<'a> {
+    pub foundation: &'a str,
+    /// 1 for RTP, 2 for RTCP
+    pub component: u32,
+    /// `UDP`
+    pub transport: &'a str,
+    pub priority: u32,
+    pub address: &'a str,
+    pub port: u16,
+    /// `host`, `srflx`, `prflx` or `relay`
+    pub typ: &'a str,
+    /// `raddr`
+    pub related_address: Option<&'a str>,
+    /// `rport`
+    pub related_port: Option<u16>,
+    /// Other name and value pairs
+    pub extensions: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Candidate<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Candidate<'a>, SdpParseError<'a>> {
+        let (input, foundation) = token(input)?;
+        let (input, component) = preceded(space, number)(input)?;
+        let (input, transport) = preceded(space, token)(input)?;
+        let (input, priority) = preceded(space, number)(input)?;
+        let (input, address) = preceded(space, token)(input)?;
+        let (input, port) = preceded(space, number)(input)?;
+        let (input, typ) = preceded(tag(" typ "), token)(input)?;
+        let (input, related_address) = opt(preceded(tag(" raddr "), token))(input)?;
+        let (input, related_port) = opt(preceded(tag(" rport "), number))(input)?;
+        let (input, extensions) =
+            many0(pair(preceded(space, token), preceded(space, token)))(input)?;
+        Ok((
+            input,
+            Candidate {
+                foundation,
+                component,
+                transport,
+                priority,
+                address,
+                port,
+                typ,
+                related_address,
+                related_port,
+                extensions,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Candidate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} typ {}",
+            self.foundation,
+            self.component,
+            self.transport,
+            self.priority,
+            self.address,
+            self.port,
+            self.typ
+        )?;
+        if let Some(related_address) = self.related_address {
+            write!(f, " raddr {}", related_address)?;
+        }
+        if let Some(related_port) = self.related_port {
+            write!(f, " rport {}", related_port)?;
+        }
+        for (name, value) in &self.extensions {
+            write!(f, " {} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// SRTP key, `a=crypto:`.
+/// [rfc4568 9.1](https://tools.ietf.org/html/rfc4568#section-9.1)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crypto<'a> {
+    pub tag: u32,
+    /// Ex: `AES_CM_128_HMAC_SHA1_80`
+    pub suite: &'a str,
+    /// Ex: `inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|2^20|1:32`
+    pub key_params: &'a str,
+    pub session_params: Vec<&'a str>,
+}
+
+impl<'a> Crypto<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Crypto<'a>, SdpParseError<'a>> {
+        let (input, tag) = number(input)?;
+        let (input, suite) = preceded(space, token)(input)?;
+        let (input, key_params) = preceded(space, token)(input)?;
+        let (input, session_params) = opt(preceded(space, tokens))(input)?;
+        Ok((
+            input,
+            Crypto {
+                tag,
+                suite,
+                key_params,
+                session_params: session_params.unwrap_or_default(),
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Crypto<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.tag, self.suite, self.key_params)?;
+        for param in &self.session_params {
+            write!(f, " {}", param)?;
+        }
+        Ok(())
+    }
+}
+
+/// Certificate fingerprint of DTLS, `a=fingerprint:`.
+/// [rfc8122 5](https://tools.ietf.org/html/rfc8122#section-5)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fingerprint<'a> {
+    /// Ex: `sha-256`
+    pub hash_function: &'a str,
+    /// Uppercase hex bytes separated by colons
+    pub fingerprint: &'a str,
+}
+
+impl<'a> Fingerprint<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Fingerprint<'a>, SdpParseError<'a>> {
+        let (input, hash_function) = token(input)?;
+        let (input, fingerprint) = preceded(space, token)(input)?;
+        Ok((
+            input,
+            Fingerprint {
+                hash_function,
+                fingerprint,
+            },
+        ))
+    }
+}
+
+/// Grouping of media descriptions by `mid`, `a=group:`.
+/// [rfc5888 5](https://tools.ietf.org/html/rfc5888#section-5)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group<'a> {
+    /// Ex: `BUNDLE`, `LS`
+    pub semantics: &'a str,
+    pub mids: Vec<&'a str>,
+}
+
+impl<'a> Group<'a> {
+    fn parse(input: &'a str) -> nom::IResult<&'a str, Group<'a>, SdpParseError<'a>> {
+        let (input, semantics) = token(input)?;
+        let (input, mids) = many0(preceded(space, token))(input)?;
+        Ok((input, Group { semantics, mids }))
+    }
+}
+
+impl fmt::Display for Group<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.semantics)?;
+        for mid in &self.mids {
+            write!(f, " {}", mid)?;
+        }
+        Ok(())
+    }
+}
+
+/// `a=` line. Known attributes with invalid value are kept as `Other`.
+/// [rfc8866 5.13](https://tools.ietf.org/html/rfc8866#section-5.13)
+/// ```rust
+/// use sdpmsg::{SdpAttribute, SdpRtpMap};
+///
+/// let rtpmap = SdpAttribute::parse("rtpmap:96 opus/48000/2");
+/// assert_eq!(
+///     rtpmap,
+///     SdpAttribute::RtpMap(SdpRtpMap {
+///         payload_type: 96,
+///         encoding_name: "opus",
+///         clock_rate: 48000,
+///         encoding_params: Some("2"),
+///     })
+/// );
+/// assert_eq!(rtpmap.to_string(), "rtpmap:96 opus/48000/2");
+///
+/// // Clock rate is absent
+/// assert_eq!(
+///     SdpAttribute::parse("rtpmap:31 LPC"),
+///     SdpAttribute::Other { name: "rtpmap", value: Some("31 LPC") }
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Attribute<'a> {
+    RtpMap(RtpMap<'a>),
+    Fmtp(Fmtp<'a>),
+    /// `sendrecv`, `sendonly`, `recvonly` or `inactive`
+    Direction(Direction),
+    Rtcp(Rtcp<'a>),
+    /// Packet time in milliseconds, `a=ptime:`
+    Ptime(u32),
+    Candidate(Candidate<'a>),
+    Crypto(Crypto<'a>),
+    Fingerprint(Fingerprint<'a>),
+    /// Identification tag of media description, `a=mid:`
+    /// [rfc5888 4](https://tools.ietf.org/html/rfc5888#section-4)
+    Mid(&'a str),
+    Group(Group<'a>),
+    /// Unknown attribute, `value` is `None` for property attribute
+    Other {
+        name: &'a str,
+        value: Option<&'a str>,
+    },
+}
+
+impl<'a> Attribute<'a> {
+    /// Parses value of `a=` line
+    pub fn parse(input: &'a str) -> Attribute<'a> {
+        let (name, value) = match input.find(':') {
+            Some(pos) => (&input[..pos], Some(&input[pos + 1..])),
+            None => (input, None),
+        };
+        let typed = match value {
+            None => Direction::from_str(name).map(Attribute::Direction),
+            Some(value) => match name {
+                "rtpmap" => parse_all(value, RtpMap::parse).ok().map(Attribute::RtpMap),
+                "fmtp" => parse_all(value, Fmtp::parse).ok().map(Attribute::Fmtp),
+                "rtcp" => parse_all(value, Rtcp::parse).ok().map(Attribute::Rtcp),
+                "ptime" => parse_all(value, number).ok().map(Attribute::Ptime),
+                "candidate" => parse_all(value, Candidate::parse)
+                    .ok()
+                    .map(Attribute::Candidate),
+                "crypto" => parse_all(value, Crypto::parse).ok().map(Attribute::Crypto),
+                "fingerprint" => parse_all(value, Fingerprint::parse)
+                    .ok()
+                    .map(Attribute::Fingerprint),
+                "mid" => parse_all(value, token).ok().map(Attribute::Mid),
+                "group" => parse_all(value, Group::parse).ok().map(Attribute::Group),
+                _ => None,
+            },
+        };
+        typed.unwrap_or(Attribute::Other { name, value })
+    }
+
+    pub fn name(&self) -> &'a str {
+        match self {
+            Attribute::RtpMap(_) => "rtpmap",
+            Attribute::Fmtp(_) => "fmtp",
+            Attribute::Direction(direction) => direction.as_str(),
+            Attribute::Rtcp(_) => "rtcp",
+            Attribute::Ptime(_) => "ptime",
+            Attribute::Candidate(_) => "candidate",
+            Attribute::Crypto(_) => "crypto",
+            Attribute::Fingerprint(_) => "fingerprint",
+            Attribute::Mid(_) => "mid",
+            Attribute::Group(_) => "group",
+            Attribute::Other { name, .. } => name,
+        }
+    }
+
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Attribute::Direction(direction) => Some(*direction),
+            _ => None,
+        }
+    }
+}
+
+/// Value of `a=` line: `<name>[:<value>]`
+impl fmt::Display for Attribute<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())?;
+        match self {
+            Attribute::RtpMap(rtpmap) => write!(f, ":{}", rtpmap),
+            Attribute::Fmtp(fmtp) => write!(f, ":{} {}", fmtp.format, fmtp.params),
+            Attribute::Direction(_) => Ok(()),
+            Attribute::Rtcp(rtcp) => write!(f, ":{}", rtcp),
+            Attribute::Ptime(ptime) => write!(f, ":{}", ptime),
+            Attribute::Candidate(candidate) => write!(f, ":{}", candidate),
+            Attribute::Crypto(crypto) => write!(f, ":{}", crypto),
+            Attribute::Fingerprint(fingerprint) => write!(
+                f,
+                ":{} {}",
+                fingerprint.hash_function, fingerprint.fingerprint
+            ),
+            Attribute::Mid(mid) => write!(f, ":{}", mid),
+            Attribute::Group(group) => write!(f, ":{}", group),
+            Attribute::Other { value, .. } => match value {
+                Some(value) => write!(f, ":{}", value),
+                None => Ok(()),
+            },
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    attribute::Attribute,
+    media::Media,
+    session::{Bandwidth, Connection, Origin, SessionDescription, Timing},
+};
+use alloc::vec::Vec;
+
+/// Error of building session description
+#[derive(Debug, PartialEq)]
+pub enum BuildError {
+    /// Session and one of media descriptions have no connection data
+    MissingConnection,
+    /// Media description has no formats
+    MissingFormats,
+}
+
+/// Builder of session description.
+/// Session name is `-` and timing is `0 0` if they are not set.
+/// ```rust
+/// use sdpmsg::*;
+///
+/// let audio = SdpMediaBuilder::new("audio", 49170, "RTP/AVP")
+///     .format("0")
+///     .format("101")
+///     .attribute(SdpAttribute::RtpMap(SdpRtpMap {
+///         payload_type: 101,
+///         encoding_name: "telephone-event",
+///         clock_rate: 8000,
+///         encoding_params: None,
+///     }))
+///     .attribute(SdpAttribute::Direction(SdpDirection::SendRecv));
+/// let session = SdpSessionBuilder::new(SdpOrigin::new(2890844526, 2890844526, "10.47.16.5"))
+///     .connection(SdpConnection::new("10.47.16.5"))
+///     .media(audio.build().unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     session.to_string(),
+///     "v=0\r\n\
+///      o=- 2890844526 2890844526 IN IP4 10.47.16.5\r\n\
+///      s=-\r\n\
+///      c=IN IP4 10.47.16.5\r\n\
+///      t=0 0\r\n\
+///      m=audio 49170 RTP/AVP 0 101\r\n\
+///      a=rtpmap:101 telephone-event/8000\r\n\
+///      a=sendrecv\r\n"
+/// );
+/// ```
+pub struct SessionBuilder<'a> {
+    session: SessionDescription<'a>,
+}
+
+impl<'a> SessionBuilder<'a> {
+    pub fn new(origin: Origin<'a>) -> SessionBuilder<'a> {
+        SessionBuilder {
+            session: SessionDescription {
+                version: 0,
+                origin,
+                session_name: "-",
+                information: None,
+                uri: None,
+                emails: Vec::new(),
+                phones: Vec::new(),
+                connection: None,
+                bandwidths: Vec::new(),
+                timings: Vec::new(),
+                time_zones: None,
+                key: None,
+                attributes: Vec::new(),
+                media: Vec::new(),
+            },
+        }
+    }
+
+    pub fn session_name(mut self, session_name: &'a str) -> SessionBuilder<'a> {
+        self.session.session_name = session_name;
+        self
+    }
+
+    pub fn information(mut self, information: &'a str) -> SessionBuilder<'a> {
+        self.session.information = Some(information);
+        self
+    }
+
+    pub fn uri(mut self, uri: &'a str) -> SessionBuilder<'a> {
+        self.session.uri = Some(uri);
+        self
+    }
+
+    pub fn email(mut self, email: &'a str) -> SessionBuilder<'a> {
+        self.session.emails.push(email);
+        self
+    }
+
+    pub fn phone(mut self, phone: &'a str) -> SessionBuilder<'a> {
+        self.session.phones.push(phone);
+        self
+    }
+
+    pub fn connection(mut self, connection: Connection<'a>) -> SessionBuilder<'a> {
+        self.session.connection = Some(connection);
+        self
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth<'a>) -> SessionBuilder<'a> {
+        self.session.bandwidths.push(bandwidth);
+        self
+    }
+
+    pub fn timing(mut self, timing: Timing<'a>) -> SessionBuilder<'a> {
+        self.session.timings.push(timing);
+        self
+    }
+
+    pub fn attribute(mut self, attribute: Attribute<'a>) -> SessionBuilder<'a> {
+        self.session.attributes.push(attribute);
+        self
+    }
+
+    pub fn media(mut self, media: Media<'a>) -> SessionBuilder<'a> {
+        self.session.media.push(media);
+        self
+    }
+
+    pub fn build(mut self) -> Result<SessionDescription<'a>, BuildError> {
+        if self.session.connection.is_none()
+            && self.session.media.iter().any(|m| m.connections.is_empty())
+        {
+            return Err(BuildError::MissingConnection);
+        }
+        if self.session.timings.is_empty() {
+            self.session.timings.push(Timing::new(0, 0));
+        }
+        Ok(self.session)
+    }
+}
+
+/// Builder of media description
+pub struct MediaBuilder<'a> {
+    media: Media<'a>,
+}
+
+impl<'a> MediaBuilder<'a> {
+    pub fn new(media: &'a str, port: u16, proto: &'a str) -> MediaBuilder<'a> {
+        MediaBuilder {
+            media: Media {
+                media,
+                port,
+                num_ports: None,
+                proto,
+                formats: Vec::new(),
+                title: None,
+                connections: Vec::new(),
+                bandwidths: Vec::new(),
+                key: None,
+                attributes: Vec::new(),
+            },
+        }
+    }
+
+    pub fn num_ports(mut self, num_ports: u16) -> MediaBuilder<'a> {
+        self.media.num_ports = Some(num_ports);
+        self
+    }
+
+    pub fn format(mut self, format: &'a str) -> MediaBuilder<'a> {
+        self.media.formats.push(format);
+        self
+    }
+
+    pub fn title(mut self, title: &'a str) -> MediaBuilder<'a> {
+        self.media.title = Some(title);
+        self
+    }
+
+    pub fn connection(mut self, connection: Connection<'a>) -> MediaBuilder<'a> {
+        self.media.connections.push(connection);
+        self
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth<'a>) -> MediaBuilder<'a> {
+        self.media.bandwidths.push(bandwidth);
+        self
+    }
+
+    pub fn attribute(mut self, attribute: Attribute<'a>) -> MediaBuilder<'a> {
+        self.media.attributes.push(attribute);
+        self
+    }
+
+    pub fn build(self) -> Result<Media<'a>, BuildError> {
+        if self.media.formats.is_empty() {
+            return Err(BuildError::MissingFormats);
+        }
+        Ok(self.media)
+    }
+}
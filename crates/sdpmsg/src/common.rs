@@ -0,0 +1,53 @@
+use crate::errorparse::{ErrorKind, SdpParseError};
+use alloc::vec::Vec;
+use core::str::FromStr;
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::digit1,
+    multi::separated_list1,
+};
+
+/// Characters up to space
+pub(crate) fn token(input: &str) -> nom::IResult<&str, &str, SdpParseError<'_>> {
+    take_while1(|c| c != ' ')(input)
+}
+
+pub(crate) fn space(input: &str) -> nom::IResult<&str, &str, SdpParseError<'_>> {
+    tag(" ")(input)
+}
+
+/// Decimal number without sign
+pub(crate) fn number<T: FromStr>(input: &str) -> nom::IResult<&str, T, SdpParseError<'_>> {
+    let (rest, digits) = digit1(input)?;
+    match digits.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => sdp_parse_error!(Value, "number is too large"),
+    }
+}
+
+/// Tokens separated by one space
+pub(crate) fn tokens(input: &str) -> nom::IResult<&str, Vec<&str>, SdpParseError<'_>> {
+    separated_list1(space, token)(input)
+}
+
+/// Applies `parser` to the whole `input`
+pub(crate) fn parse_all<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, T, SdpParseError<'a>>,
+) -> Result<T, nom::Err<SdpParseError<'a>>> {
+    let (rest, value) = parser(input)?;
+    if !rest.is_empty() {
+        return sdp_parse_error!(Value, "unexpected characters at the end of value");
+    }
+    Ok(value)
+}
+
+/// Nom errors become `ErrorKind::Value`
+pub(crate) fn value_error(e: nom::Err<SdpParseError<'_>>) -> nom::Err<SdpParseError<'_>> {
+    e.map(|mut e| {
+        if let ErrorKind::Nom(_) = e.kind {
+            e.kind = ErrorKind::Value;
+        }
+        e
+    })
+}
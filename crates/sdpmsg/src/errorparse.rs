@@ -0,0 +1,155 @@
+use core::fmt;
+use nom::error::ParseError;
+
+/// Kind of parse error
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ErrorKind {
+    /// Line is not `<type>=<value>`
+    Line,
+    /// Type of line is unknown, or the line is out of its place, or repeated
+    UnexpectedLine,
+    /// Mandatory line `SdpParseError::line_type` is absent
+    MissingLine,
+    /// Value of line `SdpParseError::line_type` is invalid
+    Value,
+    /// Line is not valid UTF-8
+    Encoding,
+    /// Error of nom parser out of known context
+    Nom(nom::error::ErrorKind),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Line => f.write_str("bad line"),
+            ErrorKind::UnexpectedLine => f.write_str("unexpected line"),
+            ErrorKind::MissingLine => f.write_str("missing line"),
+            ErrorKind::Value => f.write_str("bad value"),
+            ErrorKind::Encoding => f.write_str("bad encoding"),
+            ErrorKind::Nom(kind) => write!(f, "parser error {:?}", kind),
+        }
+    }
+}
+
+/// Parse error with position of the line.
+/// Position is relative to the input of `SessionDescription::parse`.
+/// ```rust
+/// use sdpmsg::{SdpParseErrorKind, SdpSession};
+///
+/// let sdp = "v=0\r\n\
+///            o=- 20518 0 IN IP4 203.0.113.1\r\n\
+///            s=-\r\n\
+///            t=0 0\r\n\
+///            m=audio port RTP/AVP 0\r\n";
+/// match SdpSession::parse(sdp.as_bytes()) {
+///     Err(nom::Err::Error(e)) => {
+///         assert_eq!(e.kind, SdpParseErrorKind::Value);
+///         assert_eq!(e.line_type, Some('m'));
+///         assert_eq!(e.line, Some(5));
+///         assert_eq!(e.offset, Some(49));
+///     }
+///     _ => panic!(),
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SdpParseError<'a> {
+    pub kind: ErrorKind,
+    pub message: Option<&'a str>,
+    /// Byte offset of the line from the beginning of input
+    pub offset: Option<usize>,
+    /// Line number, starts from 1
+    pub line: Option<usize>,
+    /// Type of line, e.g. `'m'` for media description
+    pub line_type: Option<char>,
+}
+
+impl<'a> SdpParseError<'a> {
+    pub fn new(kind: ErrorKind, message: Option<&'a str>) -> SdpParseError<'a> {
+        SdpParseError {
+            kind,
+            message,
+            offset: None,
+            line: None,
+            line_type: None,
+        }
+    }
+
+    pub(crate) fn line_type(mut self, line_type: char) -> Self {
+        self.line_type = Some(line_type);
+        self
+    }
+
+    /// Sets position of the line that contains error
+    pub(crate) fn at(mut self, line: usize, offset: usize) -> Self {
+        self.line = Some(line);
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Copy of error without borrowed message
+    pub fn into_owned(self) -> SdpParseError<'static> {
+        SdpParseError {
+            kind: self.kind,
+            message: None,
+            offset: self.offset,
+            line: self.line,
+            line_type: self.line_type,
+        }
+    }
+}
+
+/// Returns parse error with kind `ErrorKind::$kind`
+macro_rules! sdp_parse_error {
+    ($kind:ident) => {
+        Err(nom::Err::Error($crate::errorparse::SdpParseError::new(
+            $crate::errorparse::ErrorKind::$kind,
+            None,
+        )))
+    };
+
+    ($kind:ident, $message:expr) => {
+        Err(nom::Err::Error($crate::errorparse::SdpParseError::new(
+            $crate::errorparse::ErrorKind::$kind,
+            Some($message),
+        )))
+    };
+}
+
+impl fmt::Display for SdpParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(line_type) = self.line_type {
+            write!(f, " of {}=", line_type)?;
+        }
+        if let (Some(line), Some(offset)) = (self.line, self.offset) {
+            write!(f, " at line {} (offset {})", line, offset)?;
+        }
+        if let Some(message) = self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SdpParseError<'_> {}
+
+impl<'a> ParseError<&'a str> for SdpParseError<'a> {
+    fn from_error_kind(_input: &'a str, kind: nom::error::ErrorKind) -> Self {
+        SdpParseError::new(ErrorKind::Nom(kind), None)
+    }
+
+    fn append(_input: &'a str, _kind: nom::error::ErrorKind, other: SdpParseError<'a>) -> Self {
+        other
+    }
+}
+
+impl<'a> ParseError<&'a [u8]> for SdpParseError<'a> {
+    fn from_error_kind(_input: &'a [u8], kind: nom::error::ErrorKind) -> Self {
+        SdpParseError::new(ErrorKind::Nom(kind), None)
+    }
+
+    fn append(_input: &'a [u8], _kind: nom::error::ErrorKind, other: SdpParseError<'a>) -> Self {
+        other
+    }
+}
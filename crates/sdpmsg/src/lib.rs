@@ -0,0 +1,95 @@
+#![no_std]
+
+//! # Introduction
+//!
+//! Library for parsing/constructing SDP session descriptions
+//! ([rfc8866](https://tools.ietf.org/html/rfc8866)), the usual body of SIP messages
+//! with `Content-Type: application/sdp`.
+//!
+//! Values are borrowed from the parsed input.
+//!
+//! ## Example
+//! ```rust
+//! use sdpmsg::*;
+//!
+//! let offer = "v=0\r\n\
+//! o=- 4858251974351650128 2 IN IP4 127.0.0.1\r\n\
+//! s=-\r\n\
+//! t=0 0\r\n\
+//! a=group:BUNDLE 0\r\n\
+//! m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+//! c=IN IP4 0.0.0.0\r\n\
+//! a=rtcp:9 IN IP4 0.0.0.0\r\n\
+//! a=candidate:1467250027 1 udp 2122260223 192.168.0.196 46243 typ host generation 0\r\n\
+//! a=fingerprint:sha-256 49:66:12:17:0D:1C:91:AE:57:4C:C6:36:DD:D5:97:D2:7D:62:C9:9A:7F:B9:A3:F4:70:03:E7:43:91:73:23:5E\r\n\
+//! a=mid:0\r\n\
+//! a=sendrecv\r\n\
+//! a=rtpmap:111 opus/48000/2\r\n\
+//! a=fmtp:111 minptime=10;useinbandfec=1\r\n\
+//! a=ptime:20\r\n";
+//!
+//! let (rest, session) = SdpSession::parse(offer.as_bytes()).unwrap();
+//! assert!(rest.is_empty());
+//! assert_eq!(
+//!     session.attribute("group"),
+//!     Some(&SdpAttribute::Group(SdpGroup { semantics: "BUNDLE", mids: vec!["0"] }))
+//! );
+//!
+//! let audio = &session.media[0];
+//! assert_eq!(audio.media, "audio");
+//! assert_eq!(audio.proto, "UDP/TLS/RTP/SAVPF");
+//! assert_eq!(audio.connections[0].address, "0.0.0.0");
+//! assert_eq!(audio.rtpmap(111).unwrap().clock_rate, 48000);
+//! assert_eq!(audio.direction(), Some(SdpDirection::SendRecv));
+//! match audio.attribute("candidate") {
+//!     Some(SdpAttribute::Candidate(candidate)) => {
+//!         assert_eq!(candidate.address, "192.168.0.196");
+//!         assert_eq!(candidate.port, 46243);
+//!         assert_eq!(candidate.typ, "host");
+//!         assert_eq!(candidate.extensions, vec![("generation", "0")]);
+//!     }
+//!     _ => panic!(),
+//! }
+//! assert_eq!(audio.attribute("ptime"), Some(&SdpAttribute::Ptime(20)));
+//!
+//! // Serialization
+//! assert_eq!(session.to_string(), offer);
+//! ```
+extern crate alloc;
+extern crate nom;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[macro_use]
+mod errorparse;
+mod attribute;
+mod builder;
+mod common;
+mod line;
+mod media;
+mod session;
+
+pub use errorparse::ErrorKind as SdpParseErrorKind;
+pub use errorparse::SdpParseError;
+
+pub use session::Bandwidth as SdpBandwidth;
+pub use session::Connection as SdpConnection;
+pub use session::Origin as SdpOrigin;
+pub use session::SessionDescription as SdpSession;
+pub use session::Timing as SdpTiming;
+
+pub use media::Media as SdpMedia;
+
+pub use attribute::Attribute as SdpAttribute;
+pub use attribute::Candidate as SdpCandidate;
+pub use attribute::Crypto as SdpCrypto;
+pub use attribute::Direction as SdpDirection;
+pub use attribute::Fingerprint as SdpFingerprint;
+pub use attribute::Fmtp as SdpFmtp;
+pub use attribute::Group as SdpGroup;
+pub use attribute::Rtcp as SdpRtcp;
+pub use attribute::RtpMap as SdpRtpMap;
+
+pub use builder::BuildError as SdpBuildError;
+pub use builder::MediaBuilder as SdpMediaBuilder;
+pub use builder::SessionBuilder as SdpSessionBuilder;
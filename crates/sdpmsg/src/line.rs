@@ -0,0 +1,88 @@
+use crate::common::{parse_all, value_error};
+use crate::errorparse::SdpParseError;
+use core::str;
+
+/// `<type>=<value>` line of session description
+pub(crate) struct Line<'a> {
+    pub(crate) line_type: char,
+    pub(crate) value: &'a str,
+    /// Line number, starts from 1
+    number: usize,
+    offset: usize,
+}
+
+impl<'a> Line<'a> {
+    /// Error with position of the line
+    pub(crate) fn error(&self, e: SdpParseError<'a>) -> nom::Err<SdpParseError<'a>> {
+        nom::Err::Error(e.line_type(self.line_type).at(self.number, self.offset))
+    }
+
+    /// Parses the whole value of line, errors get position of the line
+    pub(crate) fn parse<T>(
+        &self,
+        parser: impl FnMut(&'a str) -> nom::IResult<&'a str, T, SdpParseError<'a>>,
+    ) -> Result<T, nom::Err<SdpParseError<'a>>> {
+        parse_all(self.value, parser).map_err(|e| {
+            value_error(e).map(|e| e.line_type(self.line_type).at(self.number, self.offset))
+        })
+    }
+}
+
+/// Splits input by LF or CRLF, empty lines are allowed only at the end of input
+pub(crate) struct Lines<'a> {
+    input: &'a [u8],
+    offset: usize,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Lines<'a> {
+        Lines {
+            input,
+            offset: 0,
+            number: 0,
+        }
+    }
+
+    /// Position of the end of input
+    pub(crate) fn end(&self) -> (usize, usize) {
+        (self.number + 1, self.input.len())
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Line<'a>, nom::Err<SdpParseError<'a>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.input[self.offset..];
+        if rest.iter().all(|c| *c == b'\r' || *c == b'\n') {
+            return None;
+        }
+        let offset = self.offset;
+        self.number += 1;
+        let (line, next) = match rest.iter().position(|c| *c == b'\n') {
+            Some(pos) => (&rest[..pos], offset + pos + 1),
+            None => (rest, self.input.len()),
+        };
+        self.offset = next;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        let error = |e: SdpParseError<'a>| Some(Err(nom::Err::Error(e.at(self.number, offset))));
+        let line_type = match line {
+            [t, b'=', ..] if t.is_ascii_lowercase() => *t as char,
+            _ => return error(SdpParseError::new(crate::errorparse::ErrorKind::Line, None)),
+        };
+        match str::from_utf8(&line[2..]) {
+            Ok(value) => Some(Ok(Line {
+                line_type,
+                value,
+                number: self.number,
+                offset,
+            })),
+            Err(_) => error(
+                SdpParseError::new(crate::errorparse::ErrorKind::Encoding, None)
+                    .line_type(line_type),
+            ),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    attribute::{Attribute, Direction, Fmtp, RtpMap},
+    common::{number, space, token, tokens},
+    errorparse::{ErrorKind, SdpParseError},
+    line::Line,
+    session::{Bandwidth, Connection},
+};
+use alloc::vec::Vec;
+use core::fmt;
+use nom::{bytes::complete::tag, combinator::opt, sequence::preceded};
+
+/// Media description, `m=` line and the following lines up to the next `m=`.
+/// [rfc8866 5.14](https://tools.ietf.org/html/rfc8866#section-5.14)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Media<'a> {
+    /// `audio`, `video`, `text`, `application` or `message`
+    pub media: &'a str,
+    pub port: u16,
+    /// Number of ports for hierarchically encoded streams
+    pub num_ports: Option<u16>,
+    /// Ex: `RTP/AVP`, `UDP/TLS/RTP/SAVPF`
+    pub proto: &'a str,
+    /// RTP payload types or formats of other protocols, at least one
+    pub formats: Vec<&'a str>,
+    /// `i=`
+    pub title: Option<&'a str>,
+    /// `c=`, connection data of session is used if they are absent
+    pub connections: Vec<Connection<'a>>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    /// `k=`, obsolete
+    pub key: Option<&'a str>,
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> Media<'a> {
+    pub(crate) fn parse(input: &'a str) -> nom::IResult<&'a str, Media<'a>, SdpParseError<'a>> {
+        let (input, media) = token(input)?;
+        let (input, port) = preceded(space, number)(input)?;
+        let (input, num_ports) = opt(preceded(tag("/"), number))(input)?;
+        let (input, proto) = preceded(space, token)(input)?;
+        let (input, formats) = preceded(space, tokens)(input)?;
+        Ok((
+            input,
+            Media {
+                media,
+                port,
+                num_ports,
+                proto,
+                formats,
+                title: None,
+                connections: Vec::new(),
+                bandwidths: Vec::new(),
+                key: None,
+                attributes: Vec::new(),
+            },
+        ))
+    }
+
+    /// Adds line that follows `m=`
+    pub(crate) fn add_line(&mut self, line: &Line<'a>) -> Result<(), nom::Err<SdpParseError<'a>>> {
+        match line.line_type {
+            'i' if self.title.is_none() => self.title = Some(line.value),
+            'c' => self.connections.push(line.parse(Connection::parse)?),
+            'b' => self.bandwidths.push(line.parse(Bandwidth::parse)?),
+            'k' if self.key.is_none() => self.key = Some(line.value),
+            'a' => self.attributes.push(Attribute::parse(line.value)),
+            _ => {
+                return Err(line.error(SdpParseError::new(ErrorKind::UnexpectedLine, None)));
+            }
+        }
+        Ok(())
+    }
+
+    /// The first attribute with `name`
+    pub fn attribute(&self, name: &str) -> Option<&Attribute<'a>> {
+        self.attributes.iter().find(|a| a.name() == name)
+    }
+
+    /// Direction attribute of media description.
+    /// If it's absent, direction of session is used, `sendrecv` by default.
+    pub fn direction(&self) -> Option<Direction> {
+        self.attributes.iter().find_map(Attribute::direction)
+    }
+
+    pub fn rtpmap(&self, payload_type: u8) -> Option<&RtpMap<'a>> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::RtpMap(rtpmap) if rtpmap.payload_type == payload_type => Some(rtpmap),
+            _ => None,
+        })
+    }
+
+    pub fn fmtp(&self, format: &str) -> Option<&Fmtp<'a>> {
+        self.attributes.iter().find_map(|a| match a {
+            Attribute::Fmtp(fmtp) if fmtp.format == format => Some(fmtp),
+            _ => None,
+        })
+    }
+}
+
+/// Lines of media description, each line ends with CRLF
+impl fmt::Display for Media<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m={} {}", self.media, self.port)?;
+        if let Some(num_ports) = self.num_ports {
+            write!(f, "/{}", num_ports)?;
+        }
+        write!(f, " {}", self.proto)?;
+        for format in &self.formats {
+            write!(f, " {}", format)?;
+        }
+        f.write_str("\r\n")?;
+        if let Some(title) = self.title {
+            write!(f, "i={}\r\n", title)?;
+        }
+        for connection in &self.connections {
+            write!(f, "c={}\r\n", connection)?;
+        }
+        for bandwidth in &self.bandwidths {
+            write!(f, "b={}\r\n", bandwidth)?;
+        }
+        if let Some(key) = self.key {
+            write!(f, "k={}\r\n", key)?;
+        }
+        for attribute in &self.attributes {
+            write!(f, "a={}\r\n", attribute)?;
+        }
+        Ok(())
+    }
+}
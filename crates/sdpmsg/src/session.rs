@@ -0,0 +1,422 @@
+use crate::{
+    attribute::{Attribute, Direction},
+    common::{number, space, token},
+    errorparse::{ErrorKind, SdpParseError},
+    line::Lines,
+    media::Media,
+};
+use alloc::vec::Vec;
+use core::fmt;
+use nom::{
+    bytes::complete::{tag, take_while1},
+    combinator::opt,
+    sequence::preceded,
+};
+
+/// Originator of session and its version.
+/// [rfc8866 5.2](https://tools.ietf.org/html/rfc8866#section-5.2)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Origin<'a> {
+    /// `-` if user has no login
+    pub username: &'a str,
+    pub session_id: u64,
+    /// Is increased when session description is modified
+    pub session_version: u64,
+    /// `IN`
+    pub nettype: &'a str,
+    /// `IP4` or `IP6`
+    pub addrtype: &'a str,
+    pub unicast_address: &'a str,
+}
+
+impl<'a> Origin<'a> {
+    pub fn new(session_id: u64, session_version: u64, unicast_address: &'a str) -> Origin<'a> {
+        Origin {
+            username: "-",
+            session_id,
+            session_version,
+            nettype: "IN",
+            addrtype: if unicast_address.contains(':') {
+                "IP6"
+            } else {
+                "IP4"
+            },
+            unicast_address,
+        }
+    }
+
+    pub(crate) fn parse(input: &'a str) -> nom::IResult<&'a str, Origin<'a>, SdpParseError<'a>> {
+        let (input, username) = token(input)?;
+        let (input, session_id) = preceded(space, number)(input)?;
+        let (input, session_version) = preceded(space, number)(input)?;
+        let (input, nettype) = preceded(space, token)(input)?;
+        let (input, addrtype) = preceded(space, token)(input)?;
+        let (input, unicast_address) = preceded(space, token)(input)?;
+        Ok((
+            input,
+            Origin {
+                username,
+                session_id,
+                session_version,
+                nettype,
+                addrtype,
+                unicast_address,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Origin<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.username,
+            self.session_id,
+            self.session_version,
+            self.nettype,
+            self.addrtype,
+            self.unicast_address
+        )
+    }
+}
+
+/// Connection data.
+/// [rfc8866 5.7](https://tools.ietf.org/html/rfc8866#section-5.7)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection<'a> {
+    /// `IN`
+    pub nettype: &'a str,
+    /// `IP4` or `IP6`
+    pub addrtype: &'a str,
+    /// Address without TTL and count
+    pub address: &'a str,
+    /// TTL of IP4 multicast address
+    pub ttl: Option<u8>,
+    /// Number of multicast addresses
+    pub count: Option<u32>,
+}
+
+impl<'a> Connection<'a> {
+    /// Unicast address, `IP6` if `address` contains colon
+    pub fn new(address: &'a str) -> Connection<'a> {
+        Connection {
+            nettype: "IN",
+            addrtype: if address.contains(':') { "IP6" } else { "IP4" },
+            address,
+            ttl: None,
+            count: None,
+        }
+    }
+
+    pub(crate) fn parse(
+        input: &'a str,
+    ) -> nom::IResult<&'a str, Connection<'a>, SdpParseError<'a>> {
+        let (input, nettype) = token(input)?;
+        let (input, addrtype) = preceded(space, token)(input)?;
+        let (input, address) = preceded(space, take_while1(|c| c != ' ' && c != '/'))(input)?;
+        // TTL is present only for IP4 multicast: `<address>/<ttl>[/<count>]`
+        let (input, ttl) = if addrtype == "IP4" {
+            opt(preceded(tag("/"), number))(input)?
+        } else {
+            (input, None)
+        };
+        let (input, count) = if addrtype != "IP4" || ttl.is_some() {
+            opt(preceded(tag("/"), number))(input)?
+        } else {
+            (input, None)
+        };
+        Ok((
+            input,
+            Connection {
+                nettype,
+                addrtype,
+                address,
+                ttl,
+                count,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Connection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.nettype, self.addrtype, self.address)?;
+        if let Some(ttl) = self.ttl {
+            write!(f, "/{}", ttl)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, "/{}", count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Proposed bandwidth in kilobits per second, `AS` or `CT` (`TIAS` in bits per second).
+/// [rfc8866 5.8](https://tools.ietf.org/html/rfc8866#section-5.8)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bandwidth<'a> {
+    pub bwtype: &'a str,
+    pub bandwidth: u64,
+}
+
+impl<'a> Bandwidth<'a> {
+    pub(crate) fn parse(input: &'a str) -> nom::IResult<&'a str, Bandwidth<'a>, SdpParseError<'a>> {
+        let (input, bwtype) = take_while1(|c| c != ':')(input)?;
+        let (input, bandwidth) = preceded(tag(":"), number)(input)?;
+        Ok((input, Bandwidth { bwtype, bandwidth }))
+    }
+}
+
+impl fmt::Display for Bandwidth<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.bwtype, self.bandwidth)
+    }
+}
+
+/// Start and stop times in NTP seconds, `0 0` for unbounded session.
+/// [rfc8866 5.9](https://tools.ietf.org/html/rfc8866#section-5.9)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timing<'a> {
+    pub start: u64,
+    pub stop: u64,
+    /// Values of `r=` lines. Ex: `604800 3600 0 90000`, `7d 1h 0 25h`
+    pub repeats: Vec<&'a str>,
+}
+
+impl<'a> Timing<'a> {
+    pub fn new(start: u64, stop: u64) -> Timing<'a> {
+        Timing {
+            start,
+            stop,
+            repeats: Vec::new(),
+        }
+    }
+
+    pub(crate) fn parse(input: &'a str) -> nom::IResult<&'a str, Timing<'a>, SdpParseError<'a>> {
+        let (input, start) = number(input)?;
+        let (input, stop) = preceded(space, number)(input)?;
+        Ok((input, Timing::new(start, stop)))
+    }
+}
+
+/// Session description.
+/// [rfc8866 5](https://tools.ietf.org/html/rfc8866#section-5)
+///
+/// ```rust
+/// use sdpmsg::{SdpAttribute, SdpDirection, SdpSession};
+///
+/// let sdp = "v=0\r\n\
+///            o=jdoe 3724394400 3724394405 IN IP4 198.51.100.1\r\n\
+///            s=Call to John Smith\r\n\
+///            c=IN IP4 198.51.100.1\r\n\
+///            t=0 0\r\n\
+///            m=audio 49170 RTP/AVP 0 101\r\n\
+///            a=rtpmap:101 telephone-event/8000\r\n\
+///            a=fmtp:101 0-15\r\n\
+///            a=sendonly\r\n";
+/// let (_, session) = SdpSession::parse(sdp.as_bytes()).unwrap();
+/// assert_eq!(session.origin.session_id, 3724394400);
+/// assert_eq!(session.connection.as_ref().unwrap().address, "198.51.100.1");
+///
+/// let audio = &session.media[0];
+/// assert_eq!(audio.port, 49170);
+/// assert_eq!(audio.formats, vec!["0", "101"]);
+/// assert_eq!(audio.rtpmap(101).unwrap().encoding_name, "telephone-event");
+/// assert_eq!(audio.fmtp("101").unwrap().params, "0-15");
+/// assert_eq!(audio.direction(), Some(SdpDirection::SendOnly));
+///
+/// assert_eq!(session.to_string(), sdp);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionDescription<'a> {
+    /// `v=`, always 0
+    pub version: u8,
+    pub origin: Origin<'a>,
+    /// `s=`, `-` if session has no name
+    pub session_name: &'a str,
+    /// `i=`
+    pub information: Option<&'a str>,
+    /// `u=`
+    pub uri: Option<&'a str>,
+    /// `e=`
+    pub emails: Vec<&'a str>,
+    /// `p=`
+    pub phones: Vec<&'a str>,
+    /// `c=` for all media descriptions without own connection data
+    pub connection: Option<Connection<'a>>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    /// `t=` and `r=` lines, at least one
+    pub timings: Vec<Timing<'a>>,
+    /// `z=`
+    pub time_zones: Option<&'a str>,
+    /// `k=`, obsolete
+    pub key: Option<&'a str>,
+    pub attributes: Vec<Attribute<'a>>,
+    pub media: Vec<Media<'a>>,
+}
+
+impl<'a> SessionDescription<'a> {
+    /// Parses the whole input, the order of lines within session and media sections
+    /// is not checked. Lines are separated by CRLF or LF.
+    pub fn parse(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], SessionDescription<'a>, SdpParseError<'a>> {
+        let mut lines = Lines::new(input);
+        let version = match lines.next() {
+            Some(line) => {
+                let line = line?;
+                if line.line_type != 'v' {
+                    return Err(line.error(SdpParseError::new(
+                        ErrorKind::UnexpectedLine,
+                        Some("session description must start with v="),
+                    )));
+                }
+                let version: u8 = line.parse(number)?;
+                if version != 0 {
+                    return Err(line.error(SdpParseError::new(
+                        ErrorKind::Value,
+                        Some("unsupported version"),
+                    )));
+                }
+                version
+            }
+            None => return sdp_parse_error!(MissingLine).map_err(|e| e.map(|e| e.line_type('v'))),
+        };
+
+        let mut origin = None;
+        let mut session_name = None;
+        let mut information = None;
+        let mut uri = None;
+        let mut emails = Vec::new();
+        let mut phones = Vec::new();
+        let mut connection = None;
+        let mut bandwidths = Vec::new();
+        let mut timings: Vec<Timing> = Vec::new();
+        let mut time_zones = None;
+        let mut key = None;
+        let mut attributes = Vec::new();
+        let mut media: Vec<Media> = Vec::new();
+        let unexpected = || SdpParseError::new(ErrorKind::UnexpectedLine, None);
+
+        for line in &mut lines {
+            let line = line?;
+            if line.line_type == 'm' {
+                media.push(line.parse(Media::parse)?);
+                continue;
+            }
+            if let Some(media) = media.last_mut() {
+                media.add_line(&line)?;
+                continue;
+            }
+            match line.line_type {
+                'o' if origin.is_none() => origin = Some(line.parse(Origin::parse)?),
+                's' if session_name.is_none() => session_name = Some(line.value),
+                'i' if information.is_none() => information = Some(line.value),
+                'u' if uri.is_none() => uri = Some(line.value),
+                'e' => emails.push(line.value),
+                'p' => phones.push(line.value),
+                'c' if connection.is_none() => connection = Some(line.parse(Connection::parse)?),
+                'b' => bandwidths.push(line.parse(Bandwidth::parse)?),
+                't' => timings.push(line.parse(Timing::parse)?),
+                'r' => match timings.last_mut() {
+                    Some(timing) => timing.repeats.push(line.value),
+                    None => return Err(line.error(unexpected())),
+                },
+                'z' if time_zones.is_none() => time_zones = Some(line.value),
+                'k' if key.is_none() => key = Some(line.value),
+                'a' => attributes.push(Attribute::parse(line.value)),
+                _ => return Err(line.error(unexpected())),
+            }
+        }
+
+        let (number, offset) = lines.end();
+        let missing = |line_type| {
+            nom::Err::Error(
+                SdpParseError::new(ErrorKind::MissingLine, None)
+                    .line_type(line_type)
+                    .at(number, offset),
+            )
+        };
+        let origin = origin.ok_or_else(|| missing('o'))?;
+        let session_name = session_name.ok_or_else(|| missing('s'))?;
+        if timings.is_empty() {
+            return Err(missing('t'));
+        }
+        Ok((
+            &input[input.len()..],
+            SessionDescription {
+                version,
+                origin,
+                session_name,
+                information,
+                uri,
+                emails,
+                phones,
+                connection,
+                bandwidths,
+                timings,
+                time_zones,
+                key,
+                attributes,
+                media,
+            },
+        ))
+    }
+
+    /// The first attribute with `name`
+    pub fn attribute(&self, name: &str) -> Option<&Attribute<'a>> {
+        self.attributes.iter().find(|a| a.name() == name)
+    }
+
+    /// Direction attribute of session, it is default for media descriptions
+    pub fn direction(&self) -> Option<Direction> {
+        self.attributes.iter().find_map(Attribute::direction)
+    }
+}
+
+/// Lines of session description, each line ends with CRLF
+impl fmt::Display for SessionDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v={}\r\n", self.version)?;
+        write!(f, "o={}\r\n", self.origin)?;
+        write!(f, "s={}\r\n", self.session_name)?;
+        if let Some(information) = self.information {
+            write!(f, "i={}\r\n", information)?;
+        }
+        if let Some(uri) = self.uri {
+            write!(f, "u={}\r\n", uri)?;
+        }
+        for email in &self.emails {
+            write!(f, "e={}\r\n", email)?;
+        }
+        for phone in &self.phones {
+            write!(f, "p={}\r\n", phone)?;
+        }
+        if let Some(connection) = &self.connection {
+            write!(f, "c={}\r\n", connection)?;
+        }
+        for bandwidth in &self.bandwidths {
+            write!(f, "b={}\r\n", bandwidth)?;
+        }
+        for timing in &self.timings {
+            write!(f, "t={} {}\r\n", timing.start, timing.stop)?;
+            for repeat in &timing.repeats {
+                write!(f, "r={}\r\n", repeat)?;
+            }
+        }
+        if let Some(time_zones) = self.time_zones {
+            write!(f, "z={}\r\n", time_zones)?;
+        }
+        if let Some(key) = self.key {
+            write!(f, "k={}\r\n", key)?;
+        }
+        for attribute in &self.attributes {
+            write!(f, "a={}\r\n", attribute)?;
+        }
+        for media in &self.media {
+            write!(f, "{}", media)?;
+        }
+        Ok(())
+    }
+}
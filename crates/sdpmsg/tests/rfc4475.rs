@@ -0,0 +1,128 @@
+use sdpmsg::*;
+use sipmsg::*;
+
+/// Body of "A Short Tortuous INVITE" [rfc4475 3.1.1.1](https://tools.ietf.org/html/rfc4475#section-3.1.1.1)
+const WSINV: &str = "INVITE sip:vivekg@chair-dnrc.example.com;unknownparam SIP/2.0\r\n\
+TO :\r\n \
+ sip:vivekg@chair-dnrc.example.com ;   tag    = 1918181833n\r\n\
+from   : \"J Rosenberg \\\"\"       <sip:jdrosen@example.com>\r\n \
+  ;\r\n \
+  tag = 98asjd8\r\n\
+MaX-fOrWaRdS: 0068\r\n\
+Call-ID: wsinv.ndaksdj@192.0.2.1\r\n\
+Content-Length   : 150\r\n\
+cseq: 0009\r\n \
+  INVITE\r\n\
+Via  : SIP  /   2.0\r\n \
+ /UDP\r\n \
+    192.0.2.2;branch=390skdjuw\r\n\
+s :\r\n\
+NewFangledHeader:   newfangled value\r\n \
+ continued newfangled value\r\n\
+UnknownHeaderWithUnusualValue: ;;,,;;,;\r\n\
+Content-Type: application/sdp\r\n\
+Route: \r\n \
+ <sip:services.example.com;lr;unknownwith=value;unknown-no-value>\r\n\
+v:  SIP  / 2.0  / TCP     spindle.example.com   ;\r\n \
+  branch  =   z9hG4bK9ikj8  ,\r\n \
+ SIP  /    2.0   / UDP  192.168.255.111   ; branch=\r\n \
+ z9hG4bK30239\r\n\
+m:\"Quoted string \\\"\\\"\" <sip:jdrosen@example.com> ; newparam =\r\n \
+      newvalue ;\r\n \
+      secondparam ; q = 0.33\r\n\
+\r\n\
+v=0\r\n\
+o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.4\r\n\
+t=0 0\r\n\
+m=audio 49217 RTP/AVP 0 12\r\n\
+m=video 3227 RTP/AVP 31\r\n\
+a=rtpmap:31 LPC\r\n";
+
+#[test]
+fn parse_wsinv_body() {
+    let (_, request) = SipRequest::parse(WSINV.as_bytes()).unwrap();
+    let body = request.body.as_deref().unwrap();
+    let (rest, session) = SdpSession::parse(body).unwrap();
+    assert!(rest.is_empty());
+
+    assert_eq!(session.version, 0);
+    assert_eq!(
+        session.origin,
+        SdpOrigin {
+            username: "mhandley",
+            session_id: 29739,
+            session_version: 7272939,
+            nettype: "IN",
+            addrtype: "IP4",
+            unicast_address: "192.0.2.3",
+        }
+    );
+    assert_eq!(session.session_name, "-");
+    assert_eq!(session.connection, Some(SdpConnection::new("192.0.2.4")));
+    assert_eq!(session.timings, vec![SdpTiming::new(0, 0)]);
+    assert!(session.attributes.is_empty());
+
+    assert_eq!(session.media.len(), 2);
+    let audio = &session.media[0];
+    assert_eq!(audio.media, "audio");
+    assert_eq!(audio.port, 49217);
+    assert_eq!(audio.proto, "RTP/AVP");
+    assert_eq!(audio.formats, vec!["0", "12"]);
+    assert!(audio.attributes.is_empty());
+    let video = &session.media[1];
+    assert_eq!(video.media, "video");
+    assert_eq!(video.port, 3227);
+    assert_eq!(video.formats, vec!["31"]);
+    // rtpmap without clock rate
+    assert_eq!(
+        video.attributes,
+        vec![SdpAttribute::Other {
+            name: "rtpmap",
+            value: Some("31 LPC")
+        }]
+    );
+    assert_eq!(video.rtpmap(31), None);
+    assert_eq!(video.direction(), None);
+
+    assert_eq!(session.to_string().as_bytes(), body);
+}
+
+#[test]
+fn build_wsinv_body() {
+    let (_, request) = SipRequest::parse(WSINV.as_bytes()).unwrap();
+
+    let mut origin = SdpOrigin::new(29739, 7272939, "192.0.2.3");
+    origin.username = "mhandley";
+    let session = SdpSessionBuilder::new(origin)
+        .connection(SdpConnection::new("192.0.2.4"))
+        .media(
+            SdpMediaBuilder::new("audio", 49217, "RTP/AVP")
+                .format("0")
+                .format("12")
+                .build()
+                .unwrap(),
+        )
+        .media(
+            SdpMediaBuilder::new("video", 3227, "RTP/AVP")
+                .format("31")
+                .attribute(SdpAttribute::parse("rtpmap:31 LPC"))
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let body = session.to_string();
+    assert_eq!(body.as_bytes(), request.body.as_deref().unwrap());
+
+    // Body of the request is replaced and serialized
+    let mut request = request.clone();
+    request.body = Some(body.into_bytes().into());
+    let buf = SipSerializer::new()
+        .msg_to_vec(&SipMessage::Request(request))
+        .unwrap();
+    let (_, request) = SipRequest::parse(&buf).unwrap();
+    let (_, parsed) = SdpSession::parse(request.body.as_deref().unwrap()).unwrap();
+    assert_eq!(parsed, session);
+}
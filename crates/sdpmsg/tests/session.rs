@@ -0,0 +1,250 @@
+use sdpmsg::*;
+
+#[test]
+fn parse_all_fields() {
+    // rfc8866 5 with media section of rfc4568 and rfc8839
+    let sdp = "v=0\r\n\
+o=jdoe 3724394400 3724394405 IN IP4 198.51.100.1\r\n\
+s=Call to John Smith\r\n\
+i=SDP Offer #1\r\n\
+u=http://www.jdoe.example.com/home.html\r\n\
+e=Jane Doe <jane@jdoe.example.com>\r\n\
+p=+1 617 555-6011\r\n\
+c=IN IP4 233.252.0.1/127/3\r\n\
+b=CT:128\r\n\
+t=2873397496 2873404696\r\n\
+r=7d 1h 0 25h\r\n\
+t=0 0\r\n\
+z=2882844526 -1h 2898848070 0\r\n\
+a=recvonly\r\n\
+a=group:LS\r\n\
+a=tool:libsdp\r\n\
+m=audio 49170/2 RTP/SAVP 0 8\r\n\
+i=Voice\r\n\
+c=IN IP6 FF15::101/3\r\n\
+b=AS:64\r\n\
+a=crypto:1 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|2^20|1:32 KDR=1 UNENCRYPTED_SRTCP\r\n\
+a=candidate:2 1 UDP 1694498815 192.0.2.3 45664 typ srflx raddr 10.0.1.1 rport 8998\r\n\
+a=rtcp:53020 IN IP6 2001:db8::1\r\n\
+a=ptime:20\r\n\
+a=inactive\r\n\
+a=mid:audio1\r\n\
+a=ptime:22.5\r\n\
+m=video 51372 RTP/AVP 99\r\n\
+a=rtpmap:99 h263-1998/90000\r\n\
+a=fmtp:99 CIF=4;QCIF=2\r\n";
+    let (_, session) = SdpSession::parse(sdp.as_bytes()).unwrap();
+    assert_eq!(session.information, Some("SDP Offer #1"));
+    assert_eq!(session.uri, Some("http://www.jdoe.example.com/home.html"));
+    assert_eq!(session.emails, vec!["Jane Doe <jane@jdoe.example.com>"]);
+    assert_eq!(session.phones, vec!["+1 617 555-6011"]);
+    let connection = session.connection.as_ref().unwrap();
+    assert_eq!(connection.address, "233.252.0.1");
+    assert_eq!(connection.ttl, Some(127));
+    assert_eq!(connection.count, Some(3));
+    assert_eq!(
+        session.bandwidths,
+        vec![SdpBandwidth {
+            bwtype: "CT",
+            bandwidth: 128
+        }]
+    );
+    assert_eq!(session.timings.len(), 2);
+    assert_eq!(session.timings[0].start, 2873397496);
+    assert_eq!(session.timings[0].repeats, vec!["7d 1h 0 25h"]);
+    assert_eq!(session.time_zones, Some("2882844526 -1h 2898848070 0"));
+    assert_eq!(session.direction(), Some(SdpDirection::RecvOnly));
+    assert_eq!(
+        session.attribute("group"),
+        Some(&SdpAttribute::Group(SdpGroup {
+            semantics: "LS",
+            mids: vec![]
+        }))
+    );
+    assert_eq!(
+        session.attribute("tool"),
+        Some(&SdpAttribute::Other {
+            name: "tool",
+            value: Some("libsdp")
+        })
+    );
+
+    let audio = &session.media[0];
+    assert_eq!(audio.num_ports, Some(2));
+    assert_eq!(audio.title, Some("Voice"));
+    assert_eq!(audio.connections[0].addrtype, "IP6");
+    assert_eq!(audio.connections[0].address, "FF15::101");
+    assert_eq!(audio.connections[0].ttl, None);
+    assert_eq!(audio.connections[0].count, Some(3));
+    assert_eq!(audio.bandwidths[0].bandwidth, 64);
+    assert_eq!(
+        audio.attributes[0],
+        SdpAttribute::Crypto(SdpCrypto {
+            tag: 1,
+            suite: "AES_CM_128_HMAC_SHA1_80",
+            key_params: "inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|2^20|1:32",
+            session_params: vec!["KDR=1", "UNENCRYPTED_SRTCP"],
+        })
+    );
+    assert_eq!(
+        audio.attributes[1],
+        SdpAttribute::Candidate(SdpCandidate {
+            foundation: "2",
+            component: 1,
+            transport: "UDP",
+            priority: 1694498815,
+            address: "192.0.2.3",
+            port: 45664,
+            typ: "srflx",
+            related_address: Some("10.0.1.1"),
+            related_port: Some(8998),
+            extensions: vec![],
+        })
+    );
+    match &audio.attributes[2] {
+        SdpAttribute::Rtcp(rtcp) => {
+            assert_eq!(rtcp.port, 53020);
+            assert_eq!(rtcp.connection.as_ref().unwrap().address, "2001:db8::1");
+        }
+        _ => panic!(),
+    }
+    assert_eq!(audio.attributes[3], SdpAttribute::Ptime(20));
+    assert_eq!(audio.direction(), Some(SdpDirection::Inactive));
+    assert_eq!(audio.attribute("mid"), Some(&SdpAttribute::Mid("audio1")));
+    // Fraction of millisecond
+    assert_eq!(
+        audio.attributes[6],
+        SdpAttribute::Other {
+            name: "ptime",
+            value: Some("22.5")
+        }
+    );
+
+    let video = &session.media[1];
+    assert!(video.connections.is_empty());
+    assert_eq!(video.rtpmap(99).unwrap().encoding_name, "h263-1998");
+    assert_eq!(video.rtpmap(99).unwrap().encoding_params, None);
+    assert_eq!(video.fmtp("99").unwrap().params, "CIF=4;QCIF=2");
+
+    assert_eq!(session.to_string(), sdp);
+}
+
+#[test]
+fn parse_lf_line_endings() {
+    let sdp =
+        "v=0\no=- 1 1 IN IP4 192.0.2.1\ns=-\nc=IN IP4 192.0.2.1\nt=0 0\nm=audio 5004 RTP/AVP 0\n\n";
+    let (rest, session) = SdpSession::parse(sdp.as_bytes()).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(session.media[0].port, 5004);
+    // Serializer uses CRLF
+    assert_eq!(
+        session.to_string(),
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nc=IN IP4 192.0.2.1\r\nt=0 0\r\nm=audio 5004 RTP/AVP 0\r\n"
+    );
+
+    // The last line without line ending
+    let (_, session) =
+        SdpSession::parse(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0").unwrap();
+    assert_eq!(session.timings, vec![SdpTiming::new(0, 0)]);
+}
+
+#[test]
+fn parse_errors() {
+    let error = |sdp: &[u8]| match SdpSession::parse(sdp) {
+        Err(nom::Err::Error(e)) => (e.kind, e.line_type, e.line),
+        _ => panic!("parsed {:?}", String::from_utf8_lossy(sdp)),
+    };
+    assert_eq!(
+        error(b""),
+        (SdpParseErrorKind::MissingLine, Some('v'), None)
+    );
+    assert_eq!(
+        error(b"o=- 1 1 IN IP4 192.0.2.1\r\n"),
+        (SdpParseErrorKind::UnexpectedLine, Some('o'), Some(1))
+    );
+    assert_eq!(
+        error(b"v=1\r\n"),
+        (SdpParseErrorKind::Value, Some('v'), Some(1))
+    );
+    assert_eq!(
+        error(b"v=0\r\ns=-\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::MissingLine, Some('o'), Some(4))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\n"),
+        (SdpParseErrorKind::MissingLine, Some('t'), Some(4))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\ns=-\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::UnexpectedLine, Some('s'), Some(4))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nr=7d 1h 0 25h\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::UnexpectedLine, Some('r'), Some(4))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4\r\ns=-\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::Value, Some('o'), Some(2))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 5004 RTP/AVP 0\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::UnexpectedLine, Some('t'), Some(6))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 70000 RTP/AVP 0\r\n"),
+        (SdpParseErrorKind::Value, Some('m'), Some(5))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\n\r\ns=-\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::Line, None, Some(3))
+    );
+    assert_eq!(
+        error(b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=\xff\r\nt=0 0\r\n"),
+        (SdpParseErrorKind::Encoding, Some('s'), Some(3))
+    );
+}
+
+#[test]
+fn build_session() {
+    let origin = SdpOrigin::new(1, 2, "2001:db8::1");
+    assert_eq!(origin.addrtype, "IP6");
+    let media = || SdpMediaBuilder::new("audio", 5004, "RTP/AVP").format("0");
+    assert_eq!(
+        SdpMediaBuilder::new("audio", 5004, "RTP/AVP").build(),
+        Err(SdpBuildError::MissingFormats)
+    );
+    assert_eq!(
+        SdpSessionBuilder::new(origin.clone())
+            .media(media().build().unwrap())
+            .build(),
+        Err(SdpBuildError::MissingConnection)
+    );
+
+    let session = SdpSessionBuilder::new(origin)
+        .session_name("Talk")
+        .timing(SdpTiming::new(3724394400, 3724398000))
+        .attribute(SdpAttribute::Direction(SdpDirection::SendOnly.reverse()))
+        .media(
+            media()
+                .connection(SdpConnection::new("2001:db8::2"))
+                .attribute(SdpAttribute::Ptime(30))
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let sdp = session.to_string();
+    assert_eq!(
+        sdp,
+        "v=0\r\n\
+         o=- 1 2 IN IP6 2001:db8::1\r\n\
+         s=Talk\r\n\
+         t=3724394400 3724398000\r\n\
+         a=recvonly\r\n\
+         m=audio 5004 RTP/AVP 0\r\n\
+         c=IN IP6 2001:db8::2\r\n\
+         a=ptime:30\r\n"
+    );
+    let (_, parsed) = SdpSession::parse(sdp.as_bytes()).unwrap();
+    assert_eq!(parsed, session);
+}
@@ -0,0 +1,573 @@
+use crate::{
+    common::{date_time::DateTime, sip_method::SipMethod},
+    headers::{SipCSeq, SipHeader, SipHeaders, SipNameAddr, SipRFCHeader, SipUri, Uri},
+    message::SipVersion,
+    request::{Request, RequestLine},
+    response::{Response, StatusCode, StatusLine},
+    validate::Violation,
+};
+use alloc::{borrow::Cow, format, string::String};
+
+#[derive(Debug, PartialEq)]
+pub enum BuildError {
+    /// Mandatory header is absent
+    MissingHeader(SipRFCHeader),
+    /// Header must be present only one time
+    DuplicateHeader(SipRFCHeader),
+    /// Method of CSeq header is not equal to the method of request
+    CSeqMethodMismatch,
+    /// Value passed to the builder can't be parsed as the header
+    InvalidHeader(SipRFCHeader),
+}
+
+impl From<Violation> for BuildError {
+    fn from(violation: Violation) -> BuildError {
+        match violation {
+            Violation::MissingHeader(hdr) => BuildError::MissingHeader(hdr),
+            Violation::DuplicateHeader(hdr) => BuildError::DuplicateHeader(hdr),
+            Violation::InvalidHeader(hdr) => BuildError::InvalidHeader(hdr),
+            Violation::CSeqNumberTooLarge => BuildError::InvalidHeader(SipRFCHeader::CSeq),
+            Violation::CSeqMethodMismatch => BuildError::CSeqMethodMismatch,
+            Violation::ContentLengthMismatch { .. } => {
+                BuildError::InvalidHeader(SipRFCHeader::ContentLength)
+            }
+        }
+    }
+}
+
+/// Appends `text` as quoted-string
+fn push_quoted(value: &mut String, text: &str) {
+    value.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            value.push('\\');
+        }
+        value.push(c);
+    }
+    value.push('"');
+}
+
+/// Value of From, To or Contact header. Display name is written in quotes as is,
+/// like it is parsed, uri is in angle brackets.
+fn name_addr_value(addr: &SipNameAddr) -> String {
+    let mut value = match addr.display_name {
+        Some(display_name) => format!("\"{}\" <{}>", display_name, addr.uri),
+        None => format!("<{}>", addr.uri),
+    };
+    match addr.params {
+        Some(params) => {
+            for key in params.keys() {
+                match params.get(key) {
+                    Some(Some(param_value)) => value.push_str(&format!(";{}={}", key, param_value)),
+                    _ => value.push_str(&format!(";{}", key)),
+                }
+            }
+            if let (Some(tag), false) = (addr.tag, params.contains("tag")) {
+                value.push_str(&format!(";tag={}", tag));
+            }
+        }
+        None => {
+            if let Some(tag) = addr.tag {
+                value.push_str(&format!(";tag={}", tag));
+            }
+        }
+    }
+    value
+}
+
+/// Adds header parsed from `value`, or remembers the first header with invalid value
+fn push_rfc_header<'a>(
+    headers: &mut SipHeaders<'a>,
+    invalid_header: &mut Option<SipRFCHeader>,
+    hdr: SipRFCHeader,
+    value: &str,
+) {
+    match SipHeader::new_owned(hdr.as_str(), value) {
+        Ok(header) => headers.push_back(header),
+        Err(_) => {
+            invalid_header.get_or_insert(hdr);
+        }
+    }
+}
+
+/// Constructs request. Headers are serialized in the order they were added,
+/// Content-Length is added by serializer.
+/// ```rust
+/// use sipmsg::{SipMethod, SipNameAddr, SipRequestBuilder, SipSerializer, SipUri, Uri};
+///
+/// let to_uri = Uri::from(SipUri::parse(b"sip:bob@biloxi.com").unwrap().1);
+/// let from_uri = Uri::from(SipUri::parse(b"sip:alice@atlanta.com").unwrap().1);
+/// let request = SipRequestBuilder::new(SipMethod::OPTIONS, to_uri.clone())
+///     .via("UDP", "pc33.atlanta.com", "z9hG4bK7")
+///     .max_forwards(70)
+///     .to(&SipNameAddr { display_name: None, uri: &to_uri, tag: None, params: None })
+///     .from(&SipNameAddr {
+///         display_name: Some("Alice"),
+///         uri: &from_uri,
+///         tag: Some("1928301774"),
+///         params: None,
+///     })
+///     .call_id("a84b4c76e66710")
+///     .cseq(63104)
+///     .build()
+///     .unwrap();
+///
+/// let mut buf = Vec::new();
+/// SipSerializer::new().serialize_req(&request, &mut buf).unwrap();
+/// assert_eq!(
+///     buf,
+///     b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+///       Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK7\r\n\
+///       Max-Forwards: 70\r\n\
+///       To: <sip:bob@biloxi.com>\r\n\
+///       From: \"Alice\" <sip:alice@atlanta.com>;tag=1928301774\r\n\
+///       Call-ID: a84b4c76e66710\r\n\
+///       CSeq: 63104 OPTIONS\r\n\
+///       Content-Length: 0\r\n\r\n"
+/// );
+/// ```
+pub struct RequestBuilder<'a> {
+    method: SipMethod,
+    uri: Uri<'a>,
+    headers: SipHeaders<'a>,
+    body: Option<Cow<'a, [u8]>>,
+    /// First header that was not added because of invalid value
+    invalid_header: Option<SipRFCHeader>,
+}
+
+impl<'a> RequestBuilder<'a> {
+    pub fn new(method: SipMethod, uri: impl Into<Uri<'a>>) -> RequestBuilder<'a> {
+        RequestBuilder {
+            method,
+            uri: uri.into(),
+            headers: SipHeaders::new(),
+            body: None,
+            invalid_header: None,
+        }
+    }
+
+    /// Add header after all headers with the same name
+    pub fn header(mut self, header: SipHeader<'a>) -> RequestBuilder<'a> {
+        self.headers.push_back(header);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Cow<'a, [u8]>>) -> RequestBuilder<'a> {
+        self.body = Some(body.into());
+        self
+    }
+
+    fn rfc_header(mut self, hdr: SipRFCHeader, value: &str) -> RequestBuilder<'a> {
+        push_rfc_header(&mut self.headers, &mut self.invalid_header, hdr, value);
+        self
+    }
+
+    /// Add Via header of SIP/2.0 protocol, `sent_by` is host with optional port
+    /// [rfc3261 section-20.42](https://tools.ietf.org/html/rfc3261#section-20.42)
+    pub fn via(self, transport: &str, sent_by: &str, branch: &str) -> RequestBuilder<'a> {
+        let value = format!("SIP/2.0/{} {};branch={}", transport, sent_by, branch);
+        self.rfc_header(SipRFCHeader::Via, &value)
+    }
+
+    pub fn max_forwards(self, max_forwards: u8) -> RequestBuilder<'a> {
+        self.rfc_header(SipRFCHeader::MaxForwards, &format!("{}", max_forwards))
+    }
+
+    pub fn to(self, addr: &SipNameAddr) -> RequestBuilder<'a> {
+        self.rfc_header(SipRFCHeader::To, &name_addr_value(addr))
+    }
+
+    pub fn from(self, addr: &SipNameAddr) -> RequestBuilder<'a> {
+        self.rfc_header(SipRFCHeader::From, &name_addr_value(addr))
+    }
+
+    pub fn call_id(self, call_id: &str) -> RequestBuilder<'a> {
+        self.rfc_header(SipRFCHeader::CallID, call_id)
+    }
+
+    /// Add CSeq header with the method of request
+    pub fn cseq(self, seq: u32) -> RequestBuilder<'a> {
+        let value = format!("{} {}", seq, self.method.as_str());
+        self.rfc_header(SipRFCHeader::CSeq, &value)
+    }
+
+    /// Creates request, the first violation of `SipRequest::validate` is returned as error
+    pub fn build(self) -> Result<Request<'a>, BuildError> {
+        if let Some(hdr) = self.invalid_header {
+            return Err(BuildError::InvalidHeader(hdr));
+        }
+
+        let raw = format!("{} {} SIP/2.0\r\n", self.method.as_str(), self.uri);
+        let rl = RequestLine {
+            method: self.method,
+            uri: self.uri,
+            sip_version: SipVersion(2, 0),
+            raw: Cow::Owned(raw.into_bytes()),
+        };
+        let request = Request::new(rl, self.headers, self.body);
+        match request.validate().into_iter().next() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(request),
+        }
+    }
+}
+
+/// Constructs response. Headers are serialized in the order they were added,
+/// Content-Length is added by serializer.
+/// ```rust
+/// use sipmsg::{SipHeader, SipResponseBuilder, SipResponseStatusCode};
+///
+/// let response = SipResponseBuilder::new(SipResponseStatusCode::Ringing)
+///     .header(SipHeader::new_owned("Via", "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK7").unwrap())
+///     .header(SipHeader::new_owned("To", "<sip:bob@biloxi.com>;tag=a6c85cf").unwrap())
+///     .header(SipHeader::new_owned("From", "<sip:alice@atlanta.com>;tag=1928301774").unwrap())
+///     .header(SipHeader::new_owned("Call-ID", "a84b4c76e66710").unwrap())
+///     .header(SipHeader::new_owned("CSeq", "314159 INVITE").unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(response.sl.raw, "SIP/2.0 180 Ringing\r\n".as_bytes());
+/// ```
+pub struct ResponseBuilder<'a> {
+    status_code: StatusCode,
+    reason_phrase: Cow<'a, str>,
+    headers: SipHeaders<'a>,
+    body: Option<Cow<'a, [u8]>>,
+    /// First header that was not added because of invalid value
+    invalid_header: Option<SipRFCHeader>,
+}
+
+impl<'a> ResponseBuilder<'a> {
+    /// Reason phrase is the default phrase of `status_code`
+    pub fn new(status_code: StatusCode) -> ResponseBuilder<'a> {
+        ResponseBuilder {
+            status_code,
+            reason_phrase: Cow::Borrowed(status_code.reason_phrase()),
+            headers: SipHeaders::new(),
+            body: None,
+            invalid_header: None,
+        }
+    }
+
+    pub fn reason_phrase(mut self, reason_phrase: impl Into<Cow<'a, str>>) -> ResponseBuilder<'a> {
+        self.reason_phrase = reason_phrase.into();
+        self
+    }
+
+    /// Add header after all headers with the same name
+    pub fn header(mut self, header: SipHeader<'a>) -> ResponseBuilder<'a> {
+        self.headers.push_back(header);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Cow<'a, [u8]>>) -> ResponseBuilder<'a> {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub(crate) fn rfc_header(mut self, hdr: SipRFCHeader, value: &str) -> ResponseBuilder<'a> {
+        push_rfc_header(&mut self.headers, &mut self.invalid_header, hdr, value);
+        self
+    }
+
+    pub fn to(self, addr: &SipNameAddr) -> ResponseBuilder<'a> {
+        self.rfc_header(SipRFCHeader::To, &name_addr_value(addr))
+    }
+
+    pub fn from(self, addr: &SipNameAddr) -> ResponseBuilder<'a> {
+        self.rfc_header(SipRFCHeader::From, &name_addr_value(addr))
+    }
+
+    pub fn call_id(self, call_id: &str) -> ResponseBuilder<'a> {
+        self.rfc_header(SipRFCHeader::CallID, call_id)
+    }
+
+    pub fn cseq(self, cseq: &SipCSeq) -> ResponseBuilder<'a> {
+        let value = format!("{} {}", cseq.seq, cseq.method.as_str());
+        self.rfc_header(SipRFCHeader::CSeq, &value)
+    }
+
+    /// Add Contact header with `uri` in angle brackets
+    pub fn contact(self, uri: &SipUri) -> ResponseBuilder<'a> {
+        self.rfc_header(SipRFCHeader::Contact, &format!("<{}>", uri))
+    }
+
+    /// Add Allow header for each method
+    pub fn allow(self, methods: &[SipMethod]) -> ResponseBuilder<'a> {
+        methods.iter().fold(self, |builder, method| {
+            builder.rfc_header(SipRFCHeader::Allow, method.as_str())
+        })
+    }
+
+    /// Add Supported header for each option tag
+    pub fn supported(self, option_tags: &[&str]) -> ResponseBuilder<'a> {
+        option_tags.iter().fold(self, |builder, option_tag| {
+            builder.rfc_header(SipRFCHeader::Supported, option_tag)
+        })
+    }
+
+    /// Add Warning header. `text` is quoted by builder.
+    /// [rfc3261 section-20.43](https://tools.ietf.org/html/rfc3261#section-20.43)
+    pub fn warning(self, code: u16, agent: &str, text: &str) -> ResponseBuilder<'a> {
+        let mut value = format!("{} {} ", code, agent);
+        push_quoted(&mut value, text);
+        self.rfc_header(SipRFCHeader::Warning, &value)
+    }
+
+    /// Add Date header, ex. `SipDateTime::now()`
+    /// [rfc3261 section-20.17](https://tools.ietf.org/html/rfc3261#section-20.17)
+    pub fn date(mut self, date: DateTime) -> ResponseBuilder<'a> {
+        self.headers.push_back(date.to_header());
+        self
+    }
+
+    /// Creates response, the first violation of `SipResponse::validate` is returned as error
+    pub fn build(self) -> Result<Response<'a>, BuildError> {
+        if let Some(hdr) = self.invalid_header {
+            return Err(BuildError::InvalidHeader(hdr));
+        }
+        let raw = format!(
+            "SIP/2.0 {} {}\r\n",
+            self.status_code.code(),
+            self.reason_phrase
+        );
+        let sl = StatusLine {
+            sip_version: SipVersion(2, 0),
+            status_code: self.status_code,
+            reason_phrase: self.reason_phrase,
+            raw: Cow::Owned(raw.into_bytes()),
+        };
+        let response = Response::new(sl, self.headers, self.body);
+        match response.validate().into_iter().next() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SipMessage, SipSerializer};
+
+    fn request_builder(method: SipMethod, cseq: &str) -> RequestBuilder<'static> {
+        let (_, uri) =
+            SipUri::parse(b"sip:alice:secret@[2001:db8::10]:5070;transport=tcp").unwrap();
+        RequestBuilder::new(method, uri)
+            .header(SipHeader::new_owned("Via", "SIP/2.0/TCP 10.0.0.1;branch=z9hG4bK1").unwrap())
+            .header(SipHeader::new_owned("To", "<sip:alice@atlanta.com>").unwrap())
+            .header(SipHeader::new_owned("From", "<sip:bob@biloxi.com>;tag=456248").unwrap())
+            .header(SipHeader::new_owned("Call-ID", "843817637684230@998sdasdh09").unwrap())
+            .header(SipHeader::new_owned("CSeq", cseq).unwrap())
+    }
+
+    #[test]
+    fn build_request() {
+        assert_eq!(
+            request_builder(SipMethod::REGISTER, "1826 REGISTER")
+                .build()
+                .err(),
+            Some(BuildError::MissingHeader(SipRFCHeader::MaxForwards))
+        );
+        assert_eq!(
+            request_builder(SipMethod::REGISTER, "1826 INVITE")
+                .header(SipHeader::new_owned("Max-Forwards", "70").unwrap())
+                .build()
+                .err(),
+            Some(BuildError::CSeqMethodMismatch)
+        );
+        assert_eq!(
+            request_builder(SipMethod::REGISTER, "1826 REGISTER")
+                .header(SipHeader::new_owned("Max-Forwards", "70").unwrap())
+                .header(SipHeader::new_owned("Call-ID", "a84b4c76e66710").unwrap())
+                .build()
+                .err(),
+            Some(BuildError::DuplicateHeader(SipRFCHeader::CallID))
+        );
+
+        let request = request_builder(SipMethod::REGISTER, "1826 REGISTER")
+            .header(SipHeader::new_owned("Via", "SIP/2.0/TCP 10.0.0.2;branch=z9hG4bK2").unwrap())
+            .header(SipHeader::new_owned("Max-Forwards", "70").unwrap())
+            .body(b"body")
+            .build()
+            .unwrap();
+        let buf = SipSerializer::new()
+            .msg_to_vec(&SipMessage::Request(request))
+            .unwrap();
+        assert_eq!(
+            buf,
+            "REGISTER sip:alice:secret@[2001:db8::10]:5070;transport=tcp SIP/2.0\r\n\
+            Via: SIP/2.0/TCP 10.0.0.1;branch=z9hG4bK1\r\n\
+            Via: SIP/2.0/TCP 10.0.0.2;branch=z9hG4bK2\r\n\
+            To: <sip:alice@atlanta.com>\r\n\
+            From: <sip:bob@biloxi.com>;tag=456248\r\n\
+            Call-ID: 843817637684230@998sdasdh09\r\n\
+            CSeq: 1826 REGISTER\r\n\
+            Max-Forwards: 70\r\n\
+            Content-Length: 4\r\n\r\n\
+            body"
+                .as_bytes()
+        );
+
+        let (_, msg) = SipMessage::parse(&buf).unwrap();
+        let request = msg.request().unwrap();
+        assert_eq!(request.rl.method, SipMethod::REGISTER);
+        assert_eq!(
+            request.rl.uri.sip_uri().unwrap().hostport.host,
+            "2001:db8::10"
+        );
+        assert_eq!(request.body.as_deref().unwrap(), b"body");
+    }
+
+    #[test]
+    fn build_request_typed_headers() {
+        let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+        let uri = Uri::from(uri);
+        let from_hdr = SipHeader::new_owned(
+            "From",
+            "\"A. \\\"Q\\\"\" <sip:alice@atlanta.com>;tag=88sja8x;lr",
+        )
+        .unwrap();
+        let from = SipNameAddr::from_header(&from_hdr).unwrap();
+        let to = SipNameAddr {
+            display_name: None,
+            uri: &uri,
+            tag: None,
+            params: None,
+        };
+        let request = RequestBuilder::new(SipMethod::INVITE, uri.clone())
+            .via("TCP", "10.0.0.1:5070", "z9hG4bK1")
+            .max_forwards(70)
+            .to(&to)
+            .from(&from)
+            .call_id("843817637684230@998sdasdh09")
+            .cseq(2)
+            .build()
+            .unwrap();
+        assert_eq!(request.headers.top_via().unwrap().port, Some(5070));
+        assert_eq!(
+            request.headers.from_addr().unwrap().display_name,
+            Some("A. \\\"Q\\\"")
+        );
+        assert_eq!(request.headers.from_addr().unwrap().tag, Some("88sja8x"));
+        assert_eq!(
+            request.headers.cseq(),
+            Some(SipCSeq {
+                seq: 2,
+                method: SipMethod::INVITE
+            })
+        );
+
+        let to = SipNameAddr {
+            display_name: Some("Bob \"B\""),
+            ..to
+        };
+        assert_eq!(
+            request_builder(SipMethod::INVITE, "1 INVITE")
+                .to(&to)
+                .max_forwards(70)
+                .build()
+                .err(),
+            Some(BuildError::InvalidHeader(SipRFCHeader::To))
+        );
+        assert_eq!(
+            RequestBuilder::new(SipMethod::INVITE, uri.clone())
+                .via("TCP", "10.0.0.1 5070", "z9hG4bK1")
+                .build()
+                .err(),
+            Some(BuildError::InvalidHeader(SipRFCHeader::Via))
+        );
+        assert_eq!(
+            request_builder(SipMethod::INVITE, "2147483648 INVITE")
+                .max_forwards(70)
+                .build()
+                .err(),
+            Some(BuildError::InvalidHeader(SipRFCHeader::CSeq))
+        );
+        assert_eq!(
+            request_builder(SipMethod::INVITE, "1 INVITE")
+                .header(SipHeader::new_owned("Max-Forwards", "256").unwrap())
+                .build()
+                .err(),
+            Some(BuildError::InvalidHeader(SipRFCHeader::MaxForwards))
+        );
+        assert_eq!(
+            request_builder(SipMethod::INVITE, "1 INVITE")
+                .max_forwards(70)
+                .header(SipHeader::new_owned("Content-Length", "5").unwrap())
+                .body(b"body")
+                .build()
+                .err(),
+            Some(BuildError::InvalidHeader(SipRFCHeader::ContentLength))
+        );
+    }
+
+    #[test]
+    fn build_response() {
+        assert_eq!(
+            ResponseBuilder::new(StatusCode::Unauthorized)
+                .header(
+                    SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1").unwrap()
+                )
+                .header(SipHeader::new_owned("To", "<sip:alice@atlanta.com>;tag=1").unwrap())
+                .header(SipHeader::new_owned("From", "<sip:bob@biloxi.com>;tag=2").unwrap())
+                .header(SipHeader::new_owned("CSeq", "1 REGISTER").unwrap())
+                .build()
+                .err(),
+            Some(BuildError::MissingHeader(SipRFCHeader::CallID))
+        );
+
+        let response = ResponseBuilder::new(StatusCode::Unauthorized)
+            .reason_phrase("Authentication Required")
+            .header(SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1").unwrap())
+            .header(SipHeader::new_owned("To", "<sip:alice@atlanta.com>;tag=1").unwrap())
+            .header(SipHeader::new_owned("From", "<sip:bob@biloxi.com>;tag=2").unwrap())
+            .header(SipHeader::new_owned("Call-ID", "a84b4c76e66710").unwrap())
+            .header(SipHeader::new_owned("CSeq", "1 REGISTER").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(response.sl.status_code, StatusCode::Unauthorized);
+        assert_eq!(
+            SipSerializer::new()
+                .msg_to_vec(&SipMessage::Response(response))
+                .unwrap(),
+            "SIP/2.0 401 Authentication Required\r\n\
+            Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1\r\n\
+            To: <sip:alice@atlanta.com>;tag=1\r\n\
+            From: <sip:bob@biloxi.com>;tag=2\r\n\
+            Call-ID: a84b4c76e66710\r\n\
+            CSeq: 1 REGISTER\r\n\
+            Content-Length: 0\r\n\r\n"
+                .as_bytes()
+        );
+
+        let request = request_builder(SipMethod::REGISTER, "1826 REGISTER")
+            .max_forwards(70)
+            .build()
+            .unwrap();
+        let headers = &request.headers;
+        let response = ResponseBuilder::new(StatusCode::OK)
+            .header(headers.get_rfc_s(SipRFCHeader::Via).unwrap().clone())
+            .to(&SipNameAddr {
+                tag: Some("a6c85cf"),
+                ..headers.to_addr().unwrap()
+            })
+            .from(&headers.from_addr().unwrap())
+            .call_id(headers.call_id().unwrap().value)
+            .cseq(&headers.cseq().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            SipSerializer::new()
+                .msg_to_vec(&SipMessage::Response(response))
+                .unwrap(),
+            "SIP/2.0 200 OK\r\n\
+            Via: SIP/2.0/TCP 10.0.0.1;branch=z9hG4bK1\r\n\
+            To: <sip:alice@atlanta.com>;tag=a6c85cf\r\n\
+            From: <sip:bob@biloxi.com>;tag=456248\r\n\
+            Call-ID: 843817637684230@998sdasdh09\r\n\
+            CSeq: 1826 REGISTER\r\n\
+            Content-Length: 0\r\n\r\n"
+                .as_bytes()
+        );
+    }
+}
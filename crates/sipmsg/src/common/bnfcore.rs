@@ -38,6 +38,19 @@ pub fn is_crlf(i: &[u8]) -> bool {
     is_cr(i[0]) && is_lf(i[1])
 }
 
+/// Length of line ending at the beginning of input: 2 for CRLF, 1 for bare LF, otherwise 0.
+/// Parsers of header values accept bare LF, it is rejected before by `ParseOptions`.
+#[inline]
+pub fn line_ending_len(i: &[u8]) -> usize {
+    if is_crlf(i) {
+        2
+    } else if !i.is_empty() && is_lf(i[0]) {
+        1
+    } else {
+        0
+    }
+}
+
 /// CTL = %x00-1F / %x7F
 #[inline]
 pub fn is_ctl(c: u8) -> bool {
@@ -263,3 +276,27 @@ pub fn is_user_unreserved_char(c: u8) -> bool {
 pub fn is_password_char(c: u8) -> bool {
     is_unreserved(c) || c == b'&' || c == b'=' || c == b'+' || c == b'$' || c == b','
 }
+
+/// param-unreserved  =  "[" / "]" / "/" / ":" / "&" / "+" / "$"
+#[inline]
+pub fn is_param_unreserved(c: u8) -> bool {
+    c == b'[' || c == b']' || c == b'/' || c == b':' || c == b'&' || c == b'+' || c == b'$'
+}
+
+/// paramchar  =  param-unreserved / unreserved / escaped
+#[inline]
+pub fn is_paramchar(c: u8) -> bool {
+    is_unreserved(c) || is_param_unreserved(c)
+}
+
+/// hnv-unreserved  =  "[" / "]" / "/" / "?" / ":" / "+" / "$"
+#[inline]
+pub fn is_hnv_unreserved_char(c: u8) -> bool {
+    c == b'[' || c == b']' || c == b'/' || c == b'?' || c == b':' || c == b'+' || c == b'$'
+}
+
+/// hname / hvalue  =  *( hnv-unreserved / unreserved / escaped )
+#[inline]
+pub fn is_hnv_char(c: u8) -> bool {
+    is_unreserved(c) || is_hnv_unreserved_char(c)
+}
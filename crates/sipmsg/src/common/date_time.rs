@@ -0,0 +1,353 @@
+//! Date and time of Date header (rfc1123-date) in GMT.
+//!
+//! Calendar conversions are based on
+//! [chrono-compatible low-level date algorithms](https://howardhinnant.github.io/date_algorithms.html).
+use crate::{
+    common::{bnfcore::is_digit, errorparse::SipParseError},
+    headers::{SipHeader, SipHeaderValueType},
+};
+
+use alloc::string::ToString;
+use core::fmt;
+use nom::{
+    bytes::complete::{tag, take, take_while_m_n},
+    character::complete::char,
+    sequence::tuple,
+};
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const SECONDS_PER_DAY: i64 = 86400;
+/// Days from 0000-03-01 to 1970-01-01
+const DAYS_TO_UNIX_EPOCH: i64 = 719468;
+const DAYS_PER_ERA: i64 = 146097;
+
+// `u16::is_multiple_of` requires rust 1.87
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01
+fn days_from_civil(year: u16, month: u8, day: u8) -> i64 {
+    // Year starts from March, so the leap day is the last day of year
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * DAYS_PER_ERA + day_of_era - DAYS_TO_UNIX_EPOCH
+}
+
+/// (year, month, day) of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + DAYS_TO_UNIX_EPOCH;
+    let era = days.div_euclid(DAYS_PER_ERA);
+    let day_of_era = days - era * DAYS_PER_ERA;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month as u8, day as u8)
+}
+
+fn to_number(digits: &[u8]) -> u16 {
+    digits
+        .iter()
+        .fold(0, |number, digit| number * 10 + (digit - b'0') as u16)
+}
+
+/// Date and time in GMT with one second precision.
+/// Year is in range 0000-9999 as `4DIGIT` of rfc1123-date.
+/// ```rust
+/// use sipmsg::{SipDateTime, SipHeader};
+///
+/// let hdr = SipHeader::new_owned("Date", "Sat, 13 Nov 2010 23:29:00 GMT").unwrap();
+/// let date = SipDateTime::from_header(&hdr).unwrap();
+/// assert_eq!((date.year(), date.month(), date.day()), (2010, 11, 13));
+/// assert_eq!(date.unix_seconds(), 1289690940);
+/// assert_eq!(SipDateTime::from_unix_seconds(1289690940), Some(date));
+/// assert_eq!(date.to_string(), "Sat, 13 Nov 2010 23:29:00 GMT");
+///
+/// assert!(SipHeader::new_owned("Date", "Mon, 30 Feb 2010 23:29:00 GMT").is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Returns `None` if one of values is out of range.
+    /// `month` and `day` start from 1, time is in range 00:00:00 - 23:59:59
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<DateTime> {
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Returns `None` if the year is out of range 0000-9999
+    pub fn from_unix_seconds(seconds: i64) -> Option<DateTime> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+        DateTime::new(
+            year as u16,
+            month,
+            day,
+            (time / 3600) as u8,
+            (time % 3600 / 60) as u8,
+            (time % 60) as u8,
+        )
+    }
+
+    /// Seconds since 1970-01-01 00:00:00 GMT, negative for earlier dates
+    pub fn unix_seconds(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// Current time of system clock
+    #[cfg(feature = "std")]
+    pub fn now() -> DateTime {
+        let seconds = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        // Clock of system is far from the year 10000
+        DateTime::from_unix_seconds(seconds).unwrap()
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// 1 - 12
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// 1 - 31
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Day of week, 0 is Sunday
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 is Thursday
+        (days_from_civil(self.year, self.month, self.day) + 4).rem_euclid(7) as u8
+    }
+
+    /// Returns `None` if header is not Date
+    pub fn from_header(header: &SipHeader) -> Option<DateTime> {
+        if header.value.vtype != SipHeaderValueType::DateString {
+            return None;
+        }
+        let (_, date) = DateTime::parse(header.value.vstr.as_bytes()).ok()?;
+        Some(date)
+    }
+
+    /// Date header with this date, ex. for outgoing responses
+    /// [rfc3261 section-20.17](https://tools.ietf.org/html/rfc3261#section-20.17)
+    pub fn to_header(&self) -> SipHeader<'static> {
+        // Formatted date is always valid value of Date header
+        SipHeader::new_owned("Date", &self.to_string()).unwrap()
+    }
+
+    /// rfc1123-date  =  wkday "," SP date1 SP time SP "GMT"
+    ///
+    /// Weekday must be one of `wkday` names, but it is not compared with the date.
+    /// Day can be one digit as in [rfc1123](https://tools.ietf.org/html/rfc1123#page-55).
+    pub fn parse<'a>(input: &'a [u8]) -> nom::IResult<&'a [u8], DateTime, SipParseError<'a>> {
+        let (input, wkday) = take(3usize)(input)?;
+        if !WEEKDAYS.iter().any(|name| name.as_bytes() == wkday) {
+            return sip_parse_error!(HeaderValue, "Invalid wkday value in Date header");
+        }
+        let (input, _) = tag(", ")(input)?;
+        let (input, (day, _, month, _, year, _)) = tuple((
+            take_while_m_n(1, 2, is_digit),
+            char(' '),
+            take(3usize),
+            char(' '),
+            take_while_m_n(4, 4, is_digit),
+            char(' '),
+        ))(input)?;
+        let month = match MONTHS.iter().position(|name| name.as_bytes() == month) {
+            Some(idx) => idx as u8 + 1,
+            None => return sip_parse_error!(HeaderValue, "Invalid month value in Date header"),
+        };
+        let (input, (hour, _, minute, _, second, _, _)) = tuple((
+            take_while_m_n(2, 2, is_digit),
+            char(':'),
+            take_while_m_n(2, 2, is_digit),
+            char(':'),
+            take_while_m_n(2, 2, is_digit),
+            char(' '),
+            tag("GMT"),
+        ))(input)?;
+        match DateTime::new(
+            to_number(year),
+            month,
+            to_number(day) as u8,
+            to_number(hour) as u8,
+            to_number(minute) as u8,
+            to_number(second) as u8,
+        ) {
+            Some(date) => Ok((input, date)),
+            None => sip_parse_error!(HeaderValue, "Date or time is out of range in Date header"),
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[self.weekday() as usize],
+            self.day,
+            MONTHS[self.month as usize - 1],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_seconds_test() {
+        let epoch = DateTime::new(1970, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(epoch.unix_seconds(), 0);
+        assert_eq!(epoch.weekday(), 4);
+        assert_eq!(DateTime::from_unix_seconds(0), Some(epoch));
+
+        let leap_day = DateTime::new(2000, 2, 29, 12, 0, 0).unwrap();
+        assert_eq!(leap_day.unix_seconds(), 951825600);
+        assert_eq!(DateTime::from_unix_seconds(951825600), Some(leap_day));
+        assert_eq!(leap_day.to_string(), "Tue, 29 Feb 2000 12:00:00 GMT");
+
+        let before_epoch = DateTime::new(1969, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(before_epoch.unix_seconds(), -1);
+        assert_eq!(DateTime::from_unix_seconds(-1), Some(before_epoch));
+
+        let first = DateTime::new(0, 1, 1, 0, 0, 0).unwrap();
+        let last = DateTime::new(9999, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(
+            DateTime::from_unix_seconds(first.unix_seconds()),
+            Some(first)
+        );
+        assert_eq!(DateTime::from_unix_seconds(last.unix_seconds()), Some(last));
+        assert_eq!(DateTime::from_unix_seconds(first.unix_seconds() - 1), None);
+        assert_eq!(DateTime::from_unix_seconds(last.unix_seconds() + 1), None);
+
+        // Every day of four centuries is converted back to the same date
+        let mut prev = DateTime::new(1899, 12, 31, 0, 0, 0).unwrap();
+        for days in days_from_civil(1900, 1, 1)..days_from_civil(2300, 1, 1) {
+            let date = DateTime::from_unix_seconds(days * SECONDS_PER_DAY).unwrap();
+            assert!(date > prev);
+            assert_eq!(date.unix_seconds(), days * SECONDS_PER_DAY);
+            assert_eq!((date.weekday() + 7 - prev.weekday()) % 7, 1);
+            prev = date;
+        }
+    }
+
+    #[test]
+    fn new_test() {
+        assert!(DateTime::new(2004, 2, 29, 0, 0, 0).is_some());
+        assert!(DateTime::new(1900, 2, 29, 0, 0, 0).is_none());
+        assert!(DateTime::new(2010, 4, 31, 0, 0, 0).is_none());
+        assert!(DateTime::new(2010, 0, 1, 0, 0, 0).is_none());
+        assert!(DateTime::new(2010, 13, 1, 0, 0, 0).is_none());
+        assert!(DateTime::new(2010, 1, 0, 0, 0, 0).is_none());
+        assert!(DateTime::new(2010, 1, 1, 24, 0, 0).is_none());
+        assert!(DateTime::new(2010, 1, 1, 0, 60, 0).is_none());
+        assert!(DateTime::new(2010, 1, 1, 0, 0, 60).is_none());
+        assert!(DateTime::new(10000, 1, 1, 0, 0, 0).is_none());
+    }
+
+    #[test]
+    fn parse_test() {
+        let (input, date) = DateTime::parse(b"Thu, 21 Feb 2002 13:02:03 GMT\r\n").unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(date, DateTime::new(2002, 2, 21, 13, 2, 3).unwrap());
+
+        let (_, date) = DateTime::parse(b"Mon, 1 Jan 2001 00:00:00 GMT").unwrap();
+        assert_eq!(date.to_string(), "Mon, 01 Jan 2001 00:00:00 GMT");
+
+        assert!(DateTime::parse(b"Thu, 21 Feb 2002 13:02:03 UTC").is_err());
+        assert!(DateTime::parse(b"Thu, 21 Feb 2002 13:02:03").is_err());
+        assert!(DateTime::parse(b"Thu, 21 Feb 2002 1:02:03 GMT").is_err());
+        assert!(DateTime::parse(b"Thu, 21 Feb 2002 24:00:00 GMT").is_err());
+        assert!(DateTime::parse(b"Thu, 21 Feb 02 13:02:03 GMT").is_err());
+        assert!(DateTime::parse(b"Thu, 121 Feb 2002 13:02:03 GMT").is_err());
+        assert!(DateTime::parse(b"Thu, 30 Feb 2002 13:02:03 GMT").is_err());
+        assert!(DateTime::parse(b"Thu, 21 feb 2002 13:02:03 GMT").is_err());
+        assert!(DateTime::parse(b"Thursday, 21 Feb 2002 13:02:03 GMT").is_err());
+    }
+}
@@ -1,94 +1,233 @@
 use core::convert::From;
+use core::fmt;
 use core::str;
 use nom;
-use nom::error::{ErrorKind, ParseError};
+use nom::error::ParseError;
 
-#[derive(Debug)]
+/// Kind of parse error
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ErrorKind {
+    /// Type of message can't be detected by the first line
+    UnknownMessage,
+    /// Request-Line is invalid, e.g. unknown method or SIP version
+    RequestLine,
+    StatusLine,
+    /// Header line has no valid name and colon
+    HeaderName,
+    /// Value of header `SipParseError::header` is invalid
+    HeaderValue,
+    /// Request-URI or URI of header value is invalid
+    Uri,
+    /// Content-Length header is invalid, repeated or absent when it is mandatory
+    ContentLength,
+    /// Body is shorter than Content-Length
+    TruncatedBody,
+    /// Message is longer than allowed
+    MessageTooLong,
+    /// Line is longer than `ParseOptions::max_line_length`
+    LineTooLong,
+    /// Count of header lines is greater than `ParseOptions::max_headers`
+    TooManyHeaders,
+    /// Header that has only one value is repeated
+    DuplicateHeader,
+    /// Invalid element out of known context: quoted string, parameter, host, etc.
+    Syntax,
+    /// Error of nom parser out of known context
+    Nom(nom::error::ErrorKind),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnknownMessage => f.write_str("unknown message type"),
+            ErrorKind::RequestLine => f.write_str("bad request line"),
+            ErrorKind::StatusLine => f.write_str("bad status line"),
+            ErrorKind::HeaderName => f.write_str("bad header name"),
+            ErrorKind::HeaderValue => f.write_str("bad header value"),
+            ErrorKind::Uri => f.write_str("bad URI"),
+            ErrorKind::ContentLength => f.write_str("bad Content-Length"),
+            ErrorKind::TruncatedBody => f.write_str("truncated body"),
+            ErrorKind::MessageTooLong => f.write_str("message is too long"),
+            ErrorKind::LineTooLong => f.write_str("line is too long"),
+            ErrorKind::TooManyHeaders => f.write_str("too many headers"),
+            ErrorKind::DuplicateHeader => f.write_str("duplicate header"),
+            ErrorKind::Syntax => f.write_str("syntax error"),
+            ErrorKind::Nom(kind) => write!(f, "parser error {:?}", kind),
+        }
+    }
+}
+
+/// Parse error with position of the error.
+/// Position is relative to the input of the called `parse` function.
+/// ```rust
+/// use sipmsg::{SipMessage, SipParseErrorKind};
+///
+/// let msg = "OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+///            Call-ID: a84b4c76e66710\r\n\
+///            Max-Forwards: seventy\r\n\r\n";
+/// match SipMessage::parse(msg.as_bytes()) {
+///     Err(nom::Err::Error(e)) => {
+///         assert_eq!(e.kind, SipParseErrorKind::HeaderValue);
+///         assert_eq!(e.header, Some("Max-Forwards"));
+///         assert_eq!(e.line, Some(3));
+///         assert_eq!(e.offset, Some(75));
+///     }
+///     _ => panic!(),
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
 pub struct SipParseError<'a> {
-    pub code: u32,
+    pub kind: ErrorKind,
     pub message: Option<&'a str>,
+    /// Byte offset of error from the beginning of input
+    pub offset: Option<usize>,
+    /// Line number of `offset`, starts from 1
+    pub line: Option<usize>,
+    /// Name of header that is being parsed
+    pub header: Option<&'a str>,
+    /// Length of input after the position of error
+    rest_len: Option<usize>,
 }
 
-impl<'a> From<(&'a str, ErrorKind)> for SipParseError<'a> {
-    fn from(error: (&'a str, ErrorKind)) -> Self {
-        SipParseError {
-            code: error.1 as u32,
-            message: Some(error.0),
-        }
+impl<'a> From<(&'a str, nom::error::ErrorKind)> for SipParseError<'a> {
+    fn from(error: (&'a str, nom::error::ErrorKind)) -> Self {
+        SipParseError::from_error_kind(error.0, error.1)
     }
 }
 
 impl<'a> ParseError<&'a str> for SipParseError<'a> {
-    fn from_error_kind(error: &'a str, kind: ErrorKind) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+    fn from_error_kind(error: &'a str, kind: nom::error::ErrorKind) -> Self {
+        SipParseError::new(ErrorKind::Nom(kind), None).with_rest_len(error.len())
     }
 
-    fn append(error: &'a str, kind: ErrorKind, _other: SipParseError) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+    fn append(error: &'a str, kind: nom::error::ErrorKind, _other: SipParseError) -> Self {
+        SipParseError::from_error_kind(error, kind)
     }
 }
 
+/// Returns parse error with kind `ErrorKind::$kind`
 #[macro_export]
 macro_rules! sip_parse_error {
-    // error with message
-    ($error_code:expr) => {
-        Err(nom::Err::Error(SipParseError::new($error_code, None)))
+    // error without message
+    ($kind:ident) => {
+        Err(nom::Err::Error($crate::errorparse::SipParseError::new(
+            $crate::errorparse::ErrorKind::$kind,
+            None,
+        )))
     };
 
-    // error without message
-    ($error_code:expr, $message:expr) => {
-        Err(nom::Err::Error(SipParseError::new(
-            $error_code,
+    // error with message
+    ($kind:ident, $message:expr) => {
+        Err(nom::Err::Error($crate::errorparse::SipParseError::new(
+            $crate::errorparse::ErrorKind::$kind,
             Some($message),
         )))
     };
 }
 
 impl<'a> SipParseError<'a> {
-    pub fn new(code: u32, message: Option<&'a str>) -> SipParseError {
+    pub fn new(kind: ErrorKind, message: Option<&'a str>) -> SipParseError<'a> {
         SipParseError {
-            code: code,
-            message: message,
+            kind,
+            message,
+            offset: None,
+            line: None,
+            header: None,
+            rest_len: None,
         }
     }
-}
 
-impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
-    fn from_error_kind(error: &'a [u8], kind: ErrorKind) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
-            }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
-            }
-        }
+    fn with_rest_len(mut self, rest_len: usize) -> SipParseError<'a> {
+        self.rest_len = Some(rest_len);
+        self
+    }
+
+    /// Copy of error without borrowed message and header name
+    pub fn into_owned(self) -> SipParseError<'static> {
         SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+            kind: self.kind,
+            message: None,
+            offset: self.offset,
+            line: self.line,
+            header: None,
+            rest_len: self.rest_len,
         }
     }
 
-    fn append(error: &'a [u8], kind: ErrorKind, _other: SipParseError) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
-            }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
-            }
+    /// Sets `kind` if error has no specific kind, and moves position to `start`
+    /// if it is out of `start` and the following `end_len` bytes of input.
+    pub(crate) fn context(mut self, kind: ErrorKind, start: &[u8], end_len: usize) -> Self {
+        if let ErrorKind::Syntax | ErrorKind::Nom(_) = self.kind {
+            self.kind = kind;
         }
-        SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+        match self.rest_len {
+            Some(len) if len <= start.len() && len >= end_len => {}
+            _ => self.rest_len = Some(start.len()),
         }
+        self
+    }
+
+    /// Sets position of error to the beginning of `input`
+    pub(crate) fn at(mut self, input: &[u8]) -> Self {
+        self.rest_len = Some(input.len());
+        self
+    }
+
+    /// Error was found in a part of input, `after_len` is length of input after this part
+    pub(crate) fn in_part(mut self, after_len: usize) -> Self {
+        self.rest_len = self.rest_len.map(|len| len + after_len);
+        self
+    }
+
+    pub(crate) fn header(mut self, name: &'a str) -> Self {
+        self.header = Some(name);
+        self
+    }
+
+    /// Calculates offset and line number in `input`
+    pub(crate) fn locate(mut self, input: &[u8]) -> Self {
+        let rest_len = self.rest_len.unwrap_or(input.len()).min(input.len());
+        let offset = input.len() - rest_len;
+        self.rest_len = Some(rest_len);
+        self.offset = Some(offset);
+        self.line = Some(input[..offset].iter().filter(|c| **c == b'\n').count() + 1);
+        self
+    }
+}
+
+/// Calculates position of error in `input`
+pub(crate) fn locate<'a, T>(
+    input: &[u8],
+    result: nom::IResult<&'a [u8], T, SipParseError<'a>>,
+) -> nom::IResult<&'a [u8], T, SipParseError<'a>> {
+    result.map_err(|e| e.map(|e| e.locate(input)))
+}
+
+impl fmt::Display for SipParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(header) = self.header {
+            write!(f, " of {}", header)?;
+        }
+        if let (Some(line), Some(offset)) = (self.line, self.offset) {
+            write!(f, " at line {} (offset {})", line, offset)?;
+        }
+        if let Some(message) = self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SipParseError<'_> {}
+
+impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
+    fn from_error_kind(error: &'a [u8], kind: nom::error::ErrorKind) -> Self {
+        SipParseError::new(ErrorKind::Nom(kind), None).with_rest_len(error.len())
+    }
+
+    fn append(error: &'a [u8], kind: nom::error::ErrorKind, _other: SipParseError) -> Self {
+        SipParseError::from_error_kind(error, kind)
     }
 }
@@ -0,0 +1,213 @@
+//! Escaping of SIP URI components.
+//! [rfc3261 section-19.1.2](https://tools.ietf.org/html/rfc3261#section-19.1.2)
+use crate::common::bnfcore::{
+    is_escaped, is_hnv_char, is_paramchar, is_password_char, is_reserved, is_unreserved,
+    is_user_unreserved_char,
+};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    fmt::Write,
+    hash::{Hash, Hasher},
+    str,
+};
+
+/// Component of SIP URI, defines characters that are written without escaping
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UriComponent {
+    /// `unreserved / user-unreserved`
+    User,
+    /// `unreserved / "&" / "=" / "+" / "$" / ","`
+    Password,
+    /// Name and value of URI parameter, `paramchar`
+    Param,
+    /// Name and value of URI header, `hnv-unreserved / unreserved`
+    Header,
+}
+
+impl UriComponent {
+    fn is_allowed(self, c: u8) -> bool {
+        match self {
+            UriComponent::User => is_unreserved(c) || is_user_unreserved_char(c),
+            UriComponent::Password => is_password_char(c),
+            UriComponent::Param => is_paramchar(c),
+            UriComponent::Header => is_hnv_char(c),
+        }
+    }
+}
+
+/// Escapes all characters that are not allowed in `component`, including `%`.
+/// ```rust
+/// use sipmsg::{percent_encode, SipUriComponent};
+///
+/// assert_eq!(percent_encode("alice smith", SipUriComponent::User), "alice%20smith");
+/// assert_eq!(percent_encode("a;b", SipUriComponent::User), "a;b");
+/// assert_eq!(percent_encode("a;b", SipUriComponent::Param), "a%3Bb");
+/// assert_eq!(percent_encode("<sip:bob@biloxi.com>", SipUriComponent::Header),
+///            "%3Csip:bob%40biloxi.com%3E");
+/// ```
+pub fn percent_encode(s: &str, component: UriComponent) -> Cow<'_, str> {
+    if s.bytes().all(|c| component.is_allowed(c)) {
+        return Cow::Borrowed(s);
+    }
+    let mut result = String::with_capacity(s.len() + 8);
+    for c in s.bytes() {
+        if component.is_allowed(c) {
+            result.push(c as char);
+        } else {
+            let _ = write!(result, "%{:02X}", c);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Decodes `%` HEX HEX sequences, invalid sequences are kept as is
+pub fn percent_decode_bytes(s: &str) -> Cow<'_, [u8]> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s.as_bytes());
+    }
+    let input = s.as_bytes();
+    let mut result = Vec::with_capacity(input.len());
+    let mut idx = 0;
+    while idx < input.len() {
+        if is_escaped(&input[idx..]) {
+            result.push(hex_value(input[idx + 1]) << 4 | hex_value(input[idx + 2]));
+            idx += 3;
+        } else {
+            result.push(input[idx]);
+            idx += 1;
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Decodes `%` HEX HEX sequences.
+/// If decoded bytes are not UTF-8 the string is returned as is, use `percent_decode_bytes`.
+/// ```rust
+/// use sipmsg::percent_decode;
+///
+/// assert_eq!(percent_decode("alice%40atlanta.com"), "alice@atlanta.com");
+/// assert_eq!(percent_decode("caf%C3%A9"), "café");
+/// assert_eq!(percent_decode("%FF"), "%FF");
+/// ```
+pub fn percent_decode(s: &str) -> Cow<'_, str> {
+    match percent_decode_bytes(s) {
+        Cow::Borrowed(_) => Cow::Borrowed(s),
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(decoded) => Cow::Owned(decoded),
+            Err(_) => Cow::Borrowed(s),
+        },
+    }
+}
+
+/// Character of escaped string after decoding
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Unit {
+    Char(u8),
+    /// Reserved character that was escaped, it is not equal to the character itself
+    EscapedReserved(u8),
+}
+
+fn hex_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+struct Units<'s> {
+    input: &'s [u8],
+    ignore_case: bool,
+}
+
+impl Iterator for Units<'_> {
+    type Item = Unit;
+
+    fn next(&mut self) -> Option<Unit> {
+        let c = *self.input.first()?;
+        if c == b'%' && is_escaped(self.input) {
+            let c = hex_value(self.input[1]) << 4 | hex_value(self.input[2]);
+            self.input = &self.input[3..];
+            if is_reserved(c) {
+                return Some(Unit::EscapedReserved(c));
+            }
+            return Some(Unit::Char(self.fold_case(c)));
+        }
+        self.input = &self.input[1..];
+        Some(Unit::Char(self.fold_case(c)))
+    }
+}
+
+impl Units<'_> {
+    fn fold_case(&self, c: u8) -> u8 {
+        if self.ignore_case {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    }
+}
+
+fn units(s: &str, ignore_case: bool) -> Units<'_> {
+    Units {
+        input: s.as_bytes(),
+        ignore_case,
+    }
+}
+
+/// Compares escaped strings. Characters other than reserved are equal to their escaped form.
+/// [rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4)
+pub(crate) fn escaped_eq(a: &str, b: &str, ignore_case: bool) -> bool {
+    units(a, ignore_case).eq(units(b, ignore_case))
+}
+
+/// Hash that is equal for strings equal by `escaped_eq`
+pub(crate) fn escaped_hash<H: Hasher>(s: &str, ignore_case: bool, state: &mut H) {
+    for unit in units(s, ignore_case) {
+        unit.hash(state);
+    }
+    // Separator, so "ab" + "c" and "a" + "bc" have different hashes
+    state.write_u8(0xff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_compare() {
+        assert!(escaped_eq("%61lice", "alice", false));
+        assert!(escaped_eq("%4a%4A", "JJ", false));
+        assert!(!escaped_eq("%4a", "j", false));
+        assert!(escaped_eq("%4a", "j", true));
+        assert!(!escaped_eq("bob%40biloxi.com", "bob@biloxi.com", false));
+        assert!(escaped_eq("bob%40biloxi.com", "bob%40biloxi.com", false));
+        assert!(!escaped_eq("ab", "abc", false));
+        assert!(escaped_eq("100%", "100%", false));
+    }
+
+    #[test]
+    fn encode_decode() {
+        assert_eq!(percent_encode("100%", UriComponent::User), "100%25");
+        assert_eq!(percent_encode("a:b@c", UriComponent::User), "a%3Ab%40c");
+        assert_eq!(
+            percent_encode("p@ss:w", UriComponent::Password),
+            "p%40ss%3Aw"
+        );
+        assert_eq!(percent_encode("[::1]", UriComponent::Param), "[::1]");
+        assert_eq!(percent_encode("a&b=c", UriComponent::Header), "a%26b%3Dc");
+        assert!(matches!(
+            percent_encode("alice", UriComponent::User),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(percent_encode("café", UriComponent::User), "caf%C3%A9");
+
+        assert_eq!(percent_decode("100%25"), "100%");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%4a%4A"), "JJ");
+        assert_eq!(percent_decode_bytes("a%FFb").as_ref(), b"a\xffb");
+        assert_eq!(percent_decode("a%FFb"), "a%FFb");
+        let encoded = percent_encode("sip:bob@biloxi.com;x=%", UriComponent::Header);
+        assert_eq!(percent_decode(&encoded), "sip:bob@biloxi.com;x=%");
+    }
+}
@@ -1,5 +1,9 @@
 use crate::common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom};
-use core::str;
+use alloc::borrow::Cow;
+use core::{
+    hash::{Hash, Hasher},
+    str,
+};
 use nom::bytes::complete::{take, take_until, take_while1};
 
 // domainlabel      =  alphanum / alphanum *( alphanum / "-" ) alphanum
@@ -7,9 +11,9 @@ use nom::bytes::complete::{take, take_until, take_while1};
 // hostname         =  *( domainlabel "." ) toplabel [ "." ]
 // host             =  hostname / IPv4address / IPv6reference
 // hostport         =  host [ ":" port ]
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct HostPort<'a> {
-    pub host: &'a str, // hostname / IPv4address / IPv6reference
+    pub host: Cow<'a, str>, // hostname / IPv4address / IPv6reference
     pub port: Option<u16>,
 }
 
@@ -18,6 +22,29 @@ fn host_char_allowed(c: u8) -> bool {
 }
 
 impl<'a> HostPort<'a> {
+    pub fn into_owned(self) -> HostPort<'static> {
+        HostPort {
+            host: Cow::Owned(self.host.into_owned()),
+            port: self.port,
+        }
+    }
+
+    /// Hosts are compared case-insensitively, port must be present in both or absent in both.
+    /// Host name is never equal to IP address even if it is resolved to this address.
+    /// [rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4)
+    pub fn equivalent(&self, other: &HostPort) -> bool {
+        self.host.eq_ignore_ascii_case(&other.host) && self.port == other.port
+    }
+
+    /// Hash that is equal for equivalent values
+    pub fn hash_equivalent<H: Hasher>(&self, state: &mut H) {
+        for c in self.host.bytes() {
+            state.write_u8(c.to_ascii_lowercase());
+        }
+        state.write_u8(0xff);
+        self.port.hash(state);
+    }
+
     pub fn take_ipv6_host(input: &'a [u8]) -> nom::IResult<&[u8], &[u8], SipParseError> {
         let (input, _) = take(1usize)(input)?; // skip '['
         let (input, ipv6_host) = take_until("]")(input)?;
@@ -46,7 +73,7 @@ impl<'a> HostPort<'a> {
 
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], HostPort<'a>, SipParseError> {
         if input.is_empty() {
-            return sip_parse_error!(1);
+            return sip_parse_error!(Syntax);
         }
 
         let (rest, (host, port)) = HostPort::take_hostport(input)?;
@@ -56,7 +83,7 @@ impl<'a> HostPort<'a> {
             return Ok((
                 rest,
                 HostPort {
-                    host: host_str,
+                    host: Cow::Borrowed(host_str),
                     port: None,
                 },
             ));
@@ -66,21 +93,17 @@ impl<'a> HostPort<'a> {
             Ok(port_str) => match u16::from_str_radix(port_str, 10) {
                 Ok(port) => {
                     let (_, host_str) = from_utf8_nom(host)?;
-                    return Ok((
+                    Ok((
                         rest,
                         HostPort {
-                            host: host_str,
+                            host: Cow::Borrowed(host_str),
                             port: Some(port),
                         },
-                    ));
-                }
-                Err(_) => {
-                    return sip_parse_error!(2);
+                    ))
                 }
+                Err(_) => sip_parse_error!(Syntax),
             },
-            Err(_) => {
-                return sip_parse_error!(3, "Convert bytes to utf8 is failed");
-            }
+            Err(_) => sip_parse_error!(Syntax, "Convert bytes to utf8 is failed"),
         }
     }
 }
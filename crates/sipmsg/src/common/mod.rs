@@ -1,6 +1,8 @@
 #[macro_use]
 pub mod errorparse;
 
+pub mod date_time;
+pub mod escape;
 pub mod hostport;
 pub mod nom_wrappers;
 
@@ -1,17 +1,13 @@
 use crate::{
     common::{
-        bnfcore::{is_cr, is_crlf, is_escaped, is_wsp},
+        bnfcore::{is_cr, is_escaped, is_lf, is_wsp, line_ending_len},
         take_sws_token,
     },
     errorparse::SipParseError,
 };
 
 use core::str::from_utf8;
-use nom::{
-    bytes::complete::{tag, take_while1},
-    character::complete,
-    sequence::tuple,
-};
+use nom::{bytes::complete::take_while1, character::complete, sequence::tuple};
 
 pub fn take_while_with_escaped(
     input: &[u8],
@@ -47,7 +43,7 @@ fn take_until_nonescaped_quote(
         idx += 1;
     }
 
-    sip_parse_error!(1, "take_until_nonescaped_quote error!")
+    sip_parse_error!(Syntax, "take_until_nonescaped_quote error!")
 }
 
 pub fn take_quoted_string(
@@ -61,12 +57,29 @@ pub fn take_quoted_string(
 
 /// LWS  =  [*WSP CRLF] 1*WSP ; linear whitespace
 pub fn take_lws(source_input: &[u8]) -> nom::IResult<&[u8], &[u8], SipParseError> {
-    if source_input.is_empty() || (!is_wsp(source_input[0]) && !is_cr(source_input[0])) {
-        return sip_parse_error!(1, "take_lws failed");
+    if source_input.is_empty()
+        || (!is_wsp(source_input[0]) && !is_cr(source_input[0]) && !is_lf(source_input[0]))
+    {
+        return sip_parse_error!(Syntax, "take_lws failed");
     }
     take_sws(source_input)
 }
 
+/// Takes input before the line ending (CRLF or bare LF)
+pub fn take_until_line_ending(input: &[u8]) -> nom::IResult<&[u8], &[u8], SipParseError<'_>> {
+    match input.iter().position(|c| is_lf(*c)) {
+        Some(pos) => {
+            let end = if pos > 0 && is_cr(input[pos - 1]) {
+                pos - 1
+            } else {
+                pos
+            };
+            Ok((&input[end..], &input[..end]))
+        }
+        None => sip_parse_error!(Syntax, "Line ending is absent"),
+    }
+}
+
 /// SWS  =  [LWS] ; sep whitespace
 pub fn take_sws(source_input: &[u8]) -> nom::IResult<&[u8], &[u8], SipParseError> {
     let mut taken_chars = 0;
@@ -77,10 +90,10 @@ pub fn take_sws(source_input: &[u8]) -> nom::IResult<&[u8], &[u8], SipParseError
     taken_chars += spaces.len();
     let mut tmp_inp = input;
     loop {
-        if is_crlf(tmp_inp) && (tmp_inp.len() > 2 && is_wsp(tmp_inp[2])) {
-            let (inp, _) = tag("\r\n")(tmp_inp)?;
-            taken_chars += 2;
-            let (input, spaces) = complete::space0(inp)?; // *WSP
+        let line_ending = line_ending_len(tmp_inp);
+        if line_ending != 0 && tmp_inp.len() > line_ending && is_wsp(tmp_inp[line_ending]) {
+            taken_chars += line_ending;
+            let (input, spaces) = complete::space0(&tmp_inp[line_ending..])?; // *WSP
             taken_chars += spaces.len();
             tmp_inp = input;
             continue;
@@ -104,7 +117,7 @@ pub fn take_while_trim_sws(
 pub fn from_utf8_nom(v: &[u8]) -> nom::IResult<&str, &str, SipParseError> {
     match from_utf8(v) {
         Ok(res_str) => Ok(("", res_str)),
-        Err(_) => sip_parse_error!(1, "Error: from_utf8_nom failed"),
+        Err(_) => sip_parse_error!(Syntax, "Error: from_utf8_nom failed"),
     }
 }
 
@@ -165,6 +178,7 @@ mod tests {
         test_sws_case("   \r\n\t \tvalue", "value", "   \r\n\t \t");
         test_sws_case("  \r\nvalue", "\r\nvalue", "  ");
         test_sws_case("  \r\n", "\r\n", "  ");
+        test_sws_case(" \n value", "value", " \n ");
     }
     fn test_take_while_trim_sws_case(
         test_string: &str,
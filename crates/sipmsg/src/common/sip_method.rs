@@ -1,5 +1,8 @@
+use crate::common::bnfcore::is_token_char;
+use alloc::string::{String, ToString};
 use unicase::Ascii;
-#[derive(Copy, Clone, PartialEq, Debug)]
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub enum SipMethod {
     ACK,
     BYE,
@@ -15,6 +18,9 @@ pub enum SipMethod {
     REGISTER,
     SUBSCRIBE,
     UPDATE,
+    /// Method that is not listed above.
+    /// `extension-method = token` [rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)
+    Extension(String),
 }
 
 impl SipMethod {
@@ -34,10 +40,13 @@ impl SipMethod {
             &SipMethod::REGISTER => "REGISTER",
             &SipMethod::SUBSCRIBE => "SUBSCRIBE",
             &SipMethod::UPDATE => "UPDATE",
+            SipMethod::Extension(method) => method,
         }
     }
 
+    /// Returns `None` if `s` is not a token
     pub fn from_str(s: &str) -> Option<SipMethod> {
+        let method = s;
         let s = Ascii::new(s);
         macro_rules! match_str {
             ($input_str:expr, $enum_result:expr) => {
@@ -60,6 +69,9 @@ impl SipMethod {
         match_str!("REGISTER", SipMethod::REGISTER);
         match_str!("SUBSCRIBE", SipMethod::SUBSCRIBE);
         match_str!("UPDATE", SipMethod::UPDATE);
+        if !method.is_empty() && method.bytes().all(is_token_char) {
+            return Some(SipMethod::Extension(method.to_string()));
+        }
         None
     }
 }
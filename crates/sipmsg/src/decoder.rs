@@ -0,0 +1,199 @@
+use crate::{common::errorparse::SipParseError, SipMessage, SipParseOptions, SipRFCHeader};
+use alloc::vec::Vec;
+
+/// Default limit of bytes that can be buffered for one message
+const MAX_MESSAGE_SIZE: usize = 65535;
+
+const CRLF: &[u8] = b"\r\n";
+const DOUBLE_CRLF: &[u8] = b"\r\n\r\n";
+
+/// Result of one step of stream decoding
+#[allow(clippy::large_enum_variant)]
+pub enum StreamItem<'a> {
+    /// Buffered bytes are not enough to complete the next message
+    NeedMoreData,
+    /// Keep-alive ping (double CRLF). Peer expects single CRLF in response.
+    /// [rfc5626 section-4.4.1](https://tools.ietf.org/html/rfc5626#section-4.4.1)
+    Ping,
+    /// Keep-alive pong (single CRLF). Single CRLF at the end of buffered bytes is
+    /// reported only by decoder created with `expect_pongs`, otherwise it may be
+    /// the first half of ping.
+    Pong,
+    /// Complete SIP message framed by Content-Length
+    Message(SipMessage<'a>),
+}
+
+/// Stateful decoder of SIP messages from stream transports (TCP, TLS).
+///
+/// Bytes are appended with `push` as they arrive, `decode` returns next item.
+/// Bytes of returned item are released on the next call of `decode`.
+/// After an error the stream can't be resynchronized and connection should be closed.
+/// ```rust
+/// use sipmsg::{SipStreamDecoder, SipStreamItem, SipMethod};
+///
+/// let mut decoder = SipStreamDecoder::new();
+/// decoder.push(b"\r\n\r\nOPTIONS sip:user@example.com SIP/2.0\r\nContent-Le");
+/// assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Ping));
+/// assert!(matches!(decoder.decode().unwrap(), SipStreamItem::NeedMoreData));
+/// decoder.push(b"ngth: 4\r\n\r\nbody");
+/// match decoder.decode().unwrap() {
+///     SipStreamItem::Message(msg) => {
+///         assert_eq!(msg.request().unwrap().rl.method, SipMethod::OPTIONS);
+///         assert_eq!(msg.request().unwrap().body.as_deref().unwrap(), b"body");
+///     }
+///     _ => panic!(),
+/// }
+/// assert!(matches!(decoder.decode().unwrap(), SipStreamItem::NeedMoreData));
+/// ```
+pub struct StreamDecoder {
+    buffer: Vec<u8>,
+    /// Count of bytes that belong to item returned by previous `decode`
+    consumed: usize,
+    /// Count of bytes that already searched for the end of headers
+    scanned: usize,
+    /// Full length of message when headers are parsed, but body is not received yet
+    expected_len: Option<usize>,
+    max_message_size: usize,
+    expect_pongs: bool,
+}
+
+impl Default for StreamDecoder {
+    fn default() -> Self {
+        StreamDecoder::new()
+    }
+}
+
+impl StreamDecoder {
+    pub fn new() -> StreamDecoder {
+        StreamDecoder::with_max_message_size(MAX_MESSAGE_SIZE)
+    }
+
+    /// Message that exceeds `max_message_size` is reported as error
+    pub fn with_max_message_size(max_message_size: usize) -> StreamDecoder {
+        StreamDecoder {
+            buffer: Vec::new(),
+            consumed: 0,
+            scanned: 0,
+            expected_len: None,
+            max_message_size,
+            expect_pongs: false,
+        }
+    }
+
+    /// Decoder of the side that sends pings, e.g. the client of
+    /// [rfc5626 section-4.4.1](https://tools.ietf.org/html/rfc5626#section-4.4.1) flow.
+    /// Single CRLF is reported as `Pong` without waiting for more bytes.
+    pub fn expect_pongs(mut self) -> StreamDecoder {
+        self.expect_pongs = true;
+        self
+    }
+
+    /// Append received bytes
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Count of received bytes that are not decoded yet
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.consumed
+    }
+
+    fn release_consumed(&mut self) {
+        if self.consumed != 0 {
+            self.buffer.drain(..self.consumed);
+            self.consumed = 0;
+        }
+    }
+
+    fn take_keep_alive<'a>(&mut self) -> Option<StreamItem<'a>> {
+        if self.buffer.starts_with(DOUBLE_CRLF) {
+            self.consumed = DOUBLE_CRLF.len();
+            return Some(StreamItem::Ping);
+        }
+        if self.buffer.starts_with(CRLF) {
+            let partial_ping = match self.buffer.len() {
+                2 => !self.expect_pongs,
+                3 => self.buffer[2] == b'\r',
+                _ => false,
+            };
+            if partial_ping {
+                // Probably it is the ping that is not received completely
+                return Some(StreamItem::NeedMoreData);
+            }
+            self.consumed = CRLF.len();
+            return Some(StreamItem::Pong);
+        }
+        None
+    }
+
+    /// Returns true if the whole header part is present in buffer
+    fn find_headers_end(&mut self) -> bool {
+        let start = self.scanned.saturating_sub(DOUBLE_CRLF.len() - 1);
+        if self.buffer[start..]
+            .windows(DOUBLE_CRLF.len())
+            .any(|w| w == DOUBLE_CRLF)
+        {
+            return true;
+        }
+        self.scanned = self.buffer.len();
+        false
+    }
+
+    /// Decode next item from buffered bytes
+    pub fn decode(&mut self) -> Result<StreamItem<'_>, nom::Err<SipParseError<'_>>> {
+        self.release_consumed();
+
+        if let Some(expected_len) = self.expected_len {
+            if self.buffer.len() < expected_len {
+                return Ok(StreamItem::NeedMoreData);
+            }
+        } else {
+            if let Some(item) = self.take_keep_alive() {
+                return Ok(item);
+            }
+            if !self.find_headers_end() {
+                if self.buffer.len() > self.max_message_size {
+                    return sip_parse_error!(MessageTooLong, "Headers of message are too long");
+                }
+                return Ok(StreamItem::NeedMoreData);
+            }
+        }
+
+        let buffer = &self.buffer[..];
+        match SipMessage::parse_stream(buffer, &SipParseOptions::default()) {
+            Ok((rest, msg)) => {
+                let headers = match &msg {
+                    SipMessage::Request(r) => &r.headers,
+                    SipMessage::Response(r) => &r.headers,
+                };
+                if headers.get_rfc(SipRFCHeader::ContentLength).is_none() {
+                    return sip_parse_error!(
+                        ContentLength,
+                        "Content-Length header is mandatory for stream transports"
+                    );
+                }
+                let message_len = buffer.len() - rest.len();
+                if message_len > self.max_message_size {
+                    return sip_parse_error!(MessageTooLong, "Message is too long");
+                }
+                self.consumed = message_len;
+                self.scanned = 0;
+                self.expected_len = None;
+                Ok(StreamItem::Message(msg))
+            }
+            Err(nom::Err::Incomplete(needed)) => {
+                let missing = match needed {
+                    nom::Needed::Size(size) => size.get(),
+                    nom::Needed::Unknown => 1,
+                };
+                let expected_len = buffer.len() + missing;
+                if expected_len > self.max_message_size {
+                    return sip_parse_error!(MessageTooLong, "Message is too long");
+                }
+                self.expected_len = Some(expected_len);
+                Ok(StreamItem::NeedMoreData)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,633 @@
+//! Digest access authentication
+//! [rfc3261 section-22.4](https://tools.ietf.org/html/rfc3261#section-22.4),
+//! [rfc2617](https://tools.ietf.org/html/rfc2617), [rfc7616](https://tools.ietf.org/html/rfc7616)
+//! with algorithms of [rfc8760](https://tools.ietf.org/html/rfc8760).
+//!
+//! Nonces are generated and checked by the caller, here only the digests are computed.
+mod md5;
+mod sha2;
+
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{SipHeader, SipHeaderTagType, SipHeaderValueType, SipRFCHeader},
+    request::Request,
+};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str};
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+/// Compares all bytes, so the time doesn't depend on the position of first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Writes `"value"`, quotes and backslashes of value are escaped
+fn write_quoted(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            f.write_str("\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    f.write_str("\"")
+}
+
+fn tag_str<'a>(header: &'a SipHeader, tag: SipHeaderTagType) -> Option<&'a str> {
+    str::from_utf8(header.value.tags()?.get(&tag)?).ok()
+}
+
+/// Header value with `Digest` auth scheme
+fn is_digest(header: &SipHeader) -> bool {
+    header.value.vtype == SipHeaderValueType::AuthorizationDigest
+        && matches!(tag_str(header, SipHeaderTagType::AuthSchema),
+                    Some(scheme) if scheme.eq_ignore_ascii_case("Digest"))
+}
+
+/// Digest algorithm. `-sess` variants hash the client nonce into HA1.
+/// [rfc8760](https://tools.ietf.org/html/rfc8760)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+    Sha512_256,
+    Sha512_256Sess,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha256Sess => "SHA-256-sess",
+            Algorithm::Sha512_256 => "SHA-512-256",
+            Algorithm::Sha512_256Sess => "SHA-512-256-sess",
+        }
+    }
+
+    /// Case-insensitive
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Algorithm> {
+        [
+            Algorithm::Md5,
+            Algorithm::Md5Sess,
+            Algorithm::Sha256,
+            Algorithm::Sha256Sess,
+            Algorithm::Sha512_256,
+            Algorithm::Sha512_256Sess,
+        ]
+        .iter()
+        .find(|algorithm| algorithm.as_str().eq_ignore_ascii_case(s))
+        .copied()
+    }
+
+    pub fn is_sess(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::Md5Sess | Algorithm::Sha256Sess | Algorithm::Sha512_256Sess
+        )
+    }
+
+    /// Lowercase hex of hash of `parts` joined by `:`
+    pub fn hash(&self, parts: &[&[u8]]) -> String {
+        macro_rules! hash_parts {
+            ($hasher:expr) => {{
+                let mut hasher = $hasher;
+                for (idx, part) in parts.iter().enumerate() {
+                    if idx != 0 {
+                        hasher.update(b":");
+                    }
+                    hasher.update(part);
+                }
+                to_hex(&hasher.finalize())
+            }};
+        }
+        match self {
+            Algorithm::Md5 | Algorithm::Md5Sess => hash_parts!(md5::Md5::new()),
+            Algorithm::Sha256 | Algorithm::Sha256Sess => hash_parts!(sha2::Sha256::new()),
+            Algorithm::Sha512_256 | Algorithm::Sha512_256Sess => {
+                hash_parts!(sha2::Sha512_256::new())
+            }
+        }
+    }
+
+    /// `H(username:realm:password)`, it can be stored instead of the password.
+    /// For `-sess` algorithms the client nonce is added at verification time.
+    pub fn ha1(&self, username: &str, realm: &str, password: &str) -> String {
+        self.hash(&[username.as_bytes(), realm.as_bytes(), password.as_bytes()])
+    }
+}
+
+/// Quality of protection
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Qop {
+    /// Authentication
+    Auth,
+    /// Authentication with integrity protection of body
+    AuthInt,
+}
+
+impl Qop {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Qop::Auth => "auth",
+            Qop::AuthInt => "auth-int",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Qop> {
+        if s.eq_ignore_ascii_case("auth") {
+            Some(Qop::Auth)
+        } else if s.eq_ignore_ascii_case("auth-int") {
+            Some(Qop::AuthInt)
+        } else {
+            None
+        }
+    }
+}
+
+/// Digest challenge of WWW-Authenticate (401 Unauthorized) or
+/// Proxy-Authenticate (407 Proxy Authentication Required) header.
+/// ```rust
+/// use sipmsg::{SipDigestAlgorithm, SipDigestChallenge, SipDigestQop};
+///
+/// let mut challenge =
+///     SipDigestChallenge::new("atlanta.com", "84a4cc6f3082121f32b42a2187831a9e");
+/// challenge.algorithm = SipDigestAlgorithm::Sha256;
+/// let header = challenge.proxy_authenticate().unwrap();
+/// assert_eq!(
+///     header.value.vstr,
+///     "Digest realm=\"atlanta.com\", nonce=\"84a4cc6f3082121f32b42a2187831a9e\", \
+///      algorithm=SHA-256, qop=\"auth\""
+/// );
+///
+/// let parsed = SipDigestChallenge::from_header(&header).unwrap();
+/// assert_eq!(parsed.realm, "atlanta.com");
+/// assert_eq!(parsed.qop, vec![SipDigestQop::Auth]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Challenge<'a> {
+    pub realm: &'a str,
+    pub nonce: &'a str,
+    pub opaque: Option<&'a str>,
+    pub algorithm: Algorithm,
+    /// Offered qop values, empty if qop is absent
+    /// ([rfc2069](https://tools.ietf.org/html/rfc2069) compatibility)
+    pub qop: Vec<Qop>,
+    /// Nonce is expired, but the credentials were valid. Client may retry without asking user.
+    pub stale: bool,
+}
+
+impl<'a> Challenge<'a> {
+    /// MD5 challenge with `qop="auth"`
+    pub fn new(realm: &'a str, nonce: &'a str) -> Challenge<'a> {
+        Challenge {
+            realm,
+            nonce,
+            opaque: None,
+            algorithm: Algorithm::Md5,
+            qop: alloc::vec![Qop::Auth],
+            stale: false,
+        }
+    }
+
+    /// Returns `None` if header is not a digest challenge, algorithm or qop is unknown
+    pub fn from_header(header: &'a SipHeader) -> Option<Challenge<'a>> {
+        if !is_digest(header) {
+            return None;
+        }
+        let algorithm = match tag_str(header, SipHeaderTagType::Algorithm) {
+            Some(algorithm) => Algorithm::from_str(algorithm)?,
+            None => Algorithm::Md5,
+        };
+        let qop = match tag_str(header, SipHeaderTagType::QopValue) {
+            // Unknown options are ignored, but at least one must be known
+            Some(qop) => {
+                let qop: Vec<Qop> = qop
+                    .split(',')
+                    .filter_map(|q| Qop::from_str(q.trim()))
+                    .collect();
+                if qop.is_empty() {
+                    return None;
+                }
+                qop
+            }
+            None => Vec::new(),
+        };
+        Some(Challenge {
+            realm: tag_str(header, SipHeaderTagType::Realm)?,
+            nonce: tag_str(header, SipHeaderTagType::Nonce)?,
+            opaque: tag_str(header, SipHeaderTagType::Opaque),
+            algorithm,
+            qop,
+            stale: matches!(tag_str(header, SipHeaderTagType::Stale),
+                            Some(stale) if stale.eq_ignore_ascii_case("true")),
+        })
+    }
+
+    pub fn www_authenticate(&self) -> Result<SipHeader<'static>, nom::Err<SipParseError<'static>>> {
+        SipHeader::new_owned(SipRFCHeader::WWWAuthenticate.as_str(), &self.to_string())
+    }
+
+    pub fn proxy_authenticate(
+        &self,
+    ) -> Result<SipHeader<'static>, nom::Err<SipParseError<'static>>> {
+        SipHeader::new_owned(SipRFCHeader::ProxyAuthenticate.as_str(), &self.to_string())
+    }
+
+    /// Computes credentials to repeat `request` with Authorization or Proxy-Authorization header.
+    /// `auth` is chosen if it is offered, otherwise `auth-int`.
+    /// `cnonce` is a random string of client, `nc` is the number of requests
+    /// sent with this nonce including this one. They are not used if qop is not offered.
+    pub fn credentials(
+        &self,
+        request: &Request,
+        username: &'a str,
+        password: &str,
+        cnonce: &'a str,
+        nc: u32,
+    ) -> Credentials<'a> {
+        let qop = if self.qop.contains(&Qop::Auth) {
+            Some(Qop::Auth)
+        } else {
+            self.qop.first().copied()
+        };
+        let mut credentials = Credentials {
+            username,
+            realm: self.realm,
+            nonce: self.nonce,
+            uri: Cow::Owned(request.rl.uri.to_string()),
+            response: Cow::Borrowed(""),
+            algorithm: self.algorithm,
+            cnonce: (qop.is_some() || self.algorithm.is_sess()).then_some(cnonce),
+            opaque: self.opaque,
+            qop,
+            nc: qop.map(|_| nc),
+        };
+        let ha1 = self.algorithm.ha1(username, self.realm, password);
+        // cnonce and nc required by qop and algorithm are set above
+        let response = credentials
+            .request_digest(request, &ha1)
+            .unwrap_or_default();
+        credentials.response = Cow::Owned(response);
+        credentials
+    }
+}
+
+/// Header value. Ex:
+/// `Digest realm="atlanta.com", nonce="84a4cc6f3082121f32b42a2187831a9e", algorithm=MD5, qop="auth"`
+impl fmt::Display for Challenge<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Digest realm=")?;
+        write_quoted(f, self.realm)?;
+        f.write_str(", nonce=")?;
+        write_quoted(f, self.nonce)?;
+        if let Some(opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            write_quoted(f, opaque)?;
+        }
+        if self.stale {
+            f.write_str(", stale=TRUE")?;
+        }
+        write!(f, ", algorithm={}", self.algorithm.as_str())?;
+        for (idx, qop) in self.qop.iter().enumerate() {
+            let prefix = if idx == 0 { ", qop=\"" } else { "," };
+            write!(f, "{}{}", prefix, qop.as_str())?;
+        }
+        if !self.qop.is_empty() {
+            f.write_str("\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Digest credentials of Authorization or Proxy-Authorization header.
+/// ```rust
+/// use sipmsg::{SipDigestAlgorithm, SipDigestCredentials, SipHeader, SipRequest};
+///
+/// let register = "REGISTER sip:biloxi.com SIP/2.0\r\n\
+/// Via: SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7\r\n\
+/// Max-Forwards: 70\r\n\
+/// To: Bob <sip:bob@biloxi.com>\r\n\
+/// From: Bob <sip:bob@biloxi.com>;tag=456248\r\n\
+/// Call-ID: 843817637684230@998sdasdh09\r\n\
+/// CSeq: 1826 REGISTER\r\n\
+/// Authorization: Digest username=\"bob\", realm=\"biloxi.com\", \
+/// nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"sip:biloxi.com\", qop=auth, \
+/// nc=00000001, cnonce=\"0a4f113b\", response=\"9e2d1006810044fd79f39476209ae31a\"\r\n\
+/// Content-Length: 0\r\n\r\n";
+/// let (_, request) = SipRequest::parse(register.as_bytes()).unwrap();
+/// let authorization = &request.headers.get_rfc(sipmsg::SipRFCHeader::Authorization).unwrap()[0];
+/// let credentials = SipDigestCredentials::from_header(authorization).unwrap();
+/// assert_eq!(credentials.username, "bob");
+/// assert!(credentials.verify(&request, "zanzibar"));
+/// assert!(!credentials.verify(&request, "zanzibar2"));
+///
+/// // Server can store HA1 instead of the password
+/// let ha1 = SipDigestAlgorithm::Md5.ha1("bob", "biloxi.com", "zanzibar");
+/// assert!(credentials.verify_ha1(&request, &ha1));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Credentials<'a> {
+    pub username: &'a str,
+    pub realm: &'a str,
+    pub nonce: &'a str,
+    /// digest-uri, Request-URI of request
+    pub uri: Cow<'a, str>,
+    /// request-digest, lowercase hex digits
+    pub response: Cow<'a, str>,
+    pub algorithm: Algorithm,
+    pub cnonce: Option<&'a str>,
+    pub opaque: Option<&'a str>,
+    pub qop: Option<Qop>,
+    /// nonce-count
+    pub nc: Option<u32>,
+}
+
+impl<'a> Credentials<'a> {
+    /// Returns `None` if header is not digest credentials, algorithm or qop is unknown
+    pub fn from_header(header: &'a SipHeader) -> Option<Credentials<'a>> {
+        if !is_digest(header) {
+            return None;
+        }
+        let algorithm = match tag_str(header, SipHeaderTagType::Algorithm) {
+            Some(algorithm) => Algorithm::from_str(algorithm)?,
+            None => Algorithm::Md5,
+        };
+        let qop = match tag_str(header, SipHeaderTagType::QopValue) {
+            Some(qop) => Some(Qop::from_str(qop)?),
+            None => None,
+        };
+        let nc = match tag_str(header, SipHeaderTagType::NonceCount) {
+            Some(nc) => Some(u32::from_str_radix(nc, 16).ok()?),
+            None => None,
+        };
+        Some(Credentials {
+            username: tag_str(header, SipHeaderTagType::Username)?,
+            realm: tag_str(header, SipHeaderTagType::Realm)?,
+            nonce: tag_str(header, SipHeaderTagType::Nonce)?,
+            uri: Cow::Borrowed(tag_str(header, SipHeaderTagType::DigestUri)?),
+            response: Cow::Borrowed(tag_str(header, SipHeaderTagType::Dresponse)?),
+            algorithm,
+            cnonce: tag_str(header, SipHeaderTagType::Cnonce),
+            opaque: tag_str(header, SipHeaderTagType::Opaque),
+            qop,
+            nc,
+        })
+    }
+
+    pub fn authorization(&self) -> Result<SipHeader<'static>, nom::Err<SipParseError<'static>>> {
+        SipHeader::new_owned(SipRFCHeader::Authorization.as_str(), &self.to_string())
+    }
+
+    pub fn proxy_authorization(
+        &self,
+    ) -> Result<SipHeader<'static>, nom::Err<SipParseError<'static>>> {
+        SipHeader::new_owned(SipRFCHeader::ProxyAuthorization.as_str(), &self.to_string())
+    }
+
+    /// Checks that response was computed with `password` for `request`.
+    /// Realm, nonce, nonce count and digest-uri are checked by the caller.
+    pub fn verify(&self, request: &Request, password: &str) -> bool {
+        self.verify_ha1(
+            request,
+            &self.algorithm.ha1(self.username, self.realm, password),
+        )
+    }
+
+    /// Same as `verify`, `ha1` is `H(username:realm:password)`
+    /// computed by `Algorithm::ha1` with the algorithm of credentials
+    pub fn verify_ha1(&self, request: &Request, ha1: &str) -> bool {
+        match self.request_digest(request, ha1) {
+            Some(response) => constant_time_eq(response.as_bytes(), self.response.as_bytes()),
+            None => false,
+        }
+    }
+
+    fn request_digest(&self, request: &Request, ha1: &str) -> Option<String> {
+        let method = request.rl.method.as_str();
+        self.compute_digest(method, request.body.as_deref().unwrap_or(&[]), ha1)
+    }
+
+    /// Returns `None` if cnonce or nc required by qop or algorithm is absent
+    fn compute_digest(&self, method: &str, body: &[u8], ha1: &str) -> Option<String> {
+        let algorithm = self.algorithm;
+        let ha1 = if algorithm.is_sess() {
+            let cnonce = self.cnonce?;
+            algorithm.hash(&[ha1.as_bytes(), self.nonce.as_bytes(), cnonce.as_bytes()])
+        } else {
+            ha1.to_string()
+        };
+        let method = method.as_bytes();
+        let ha2 = match self.qop {
+            Some(Qop::AuthInt) => {
+                let body_hash = algorithm.hash(&[body]);
+                algorithm.hash(&[method, self.uri.as_bytes(), body_hash.as_bytes()])
+            }
+            _ => algorithm.hash(&[method, self.uri.as_bytes()]),
+        };
+        match self.qop {
+            Some(qop) => {
+                let nc = alloc::format!("{:08x}", self.nc?);
+                Some(algorithm.hash(&[
+                    ha1.as_bytes(),
+                    self.nonce.as_bytes(),
+                    nc.as_bytes(),
+                    self.cnonce?.as_bytes(),
+                    qop.as_str().as_bytes(),
+                    ha2.as_bytes(),
+                ]))
+            }
+            None => Some(algorithm.hash(&[ha1.as_bytes(), self.nonce.as_bytes(), ha2.as_bytes()])),
+        }
+    }
+}
+
+/// Header value. Ex: `Digest username="bob", realm="biloxi.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093",
+/// uri="sip:bob@biloxi.com", response="bf57e4e0d0bffc0fbaedce64d59add5e", algorithm=MD5`
+impl fmt::Display for Credentials<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Digest username=")?;
+        write_quoted(f, self.username)?;
+        f.write_str(", realm=")?;
+        write_quoted(f, self.realm)?;
+        f.write_str(", nonce=")?;
+        write_quoted(f, self.nonce)?;
+        f.write_str(", uri=")?;
+        write_quoted(f, &self.uri)?;
+        f.write_str(", response=")?;
+        write_quoted(f, &self.response)?;
+        write!(f, ", algorithm={}", self.algorithm.as_str())?;
+        if let Some(cnonce) = self.cnonce {
+            f.write_str(", cnonce=")?;
+            write_quoted(f, cnonce)?;
+        }
+        if let Some(opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            write_quoted(f, opaque)?;
+        }
+        if let Some(qop) = self.qop {
+            write!(f, ", qop={}", qop.as_str())?;
+        }
+        if let Some(nc) = self.nc {
+            write!(f, ", nc={:08x}", nc)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_credentials<'a>(
+        algorithm: Algorithm,
+        username: &'a str,
+        realm: &'a str,
+        uri: &'a str,
+        nonce: &'a str,
+        cnonce: &'a str,
+    ) -> Credentials<'a> {
+        Credentials {
+            username,
+            realm,
+            nonce,
+            uri: Cow::Borrowed(uri),
+            response: Cow::Borrowed(""),
+            algorithm,
+            cnonce: Some(cnonce),
+            opaque: None,
+            qop: Some(Qop::Auth),
+            nc: Some(1),
+        }
+    }
+
+    #[test]
+    fn rfc2617_example() {
+        // rfc2617 section-3.5
+        let credentials = http_credentials(
+            Algorithm::Md5,
+            "Mufasa",
+            "testrealm@host.com",
+            "/dir/index.html",
+            "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+            "0a4f113b",
+        );
+        let ha1 = Algorithm::Md5.ha1("Mufasa", "testrealm@host.com", "Circle Of Life");
+        assert_eq!(
+            credentials.compute_digest("GET", b"", &ha1).unwrap(),
+            "6629fae49393a05397450978507c4ef1"
+        );
+    }
+
+    #[test]
+    fn rfc7616_examples() {
+        // rfc7616 section-3.9.1
+        let password = "Circle of Life";
+        let mut credentials = http_credentials(
+            Algorithm::Md5,
+            "Mufasa",
+            "http-auth@example.org",
+            "/dir/index.html",
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v",
+            "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+        );
+        let ha1 = Algorithm::Md5.ha1("Mufasa", "http-auth@example.org", password);
+        assert_eq!(
+            credentials.compute_digest("GET", b"", &ha1).unwrap(),
+            "8ca523f5e9506fed4657c9700eebdbec"
+        );
+        credentials.algorithm = Algorithm::Sha256;
+        let ha1 = Algorithm::Sha256.ha1("Mufasa", "http-auth@example.org", password);
+        assert_eq!(
+            credentials.compute_digest("GET", b"", &ha1).unwrap(),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+
+        // Parameters of section-3.9.2 without userhash. The response printed in the RFC
+        // is wrong (https://www.rfc-editor.org/errata/rfc7616), expected value is computed
+        // by Python hashlib.new("sha512_256").
+        let credentials = http_credentials(
+            Algorithm::Sha512_256,
+            "J\u{e4}s\u{f8}n Doe",
+            "api@example.org",
+            "/doc/",
+            "5TsQWLVdgBdmrQ0XsxbDODV+57QdFR34I9HAbC/RVvkK",
+            "NTg6RKcb9boFIAS3KrFK9BGeh+iDa/sm6jUMp2wds69v",
+        );
+        let ha1 =
+            Algorithm::Sha512_256.ha1(credentials.username, "api@example.org", "Secret, or not?");
+        assert_eq!(
+            credentials.compute_digest("GET", b"", &ha1).unwrap(),
+            "9bd7522221dd4f145c46ce70965aa0455887c7562ff053e0c07f1b677d64c56c"
+        );
+    }
+
+    #[test]
+    fn sess_and_auth_int() {
+        let mut credentials = http_credentials(
+            Algorithm::Md5Sess,
+            "bob",
+            "biloxi.com",
+            "sip:bob@biloxi.com",
+            "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+            "0a4f113b",
+        );
+        let ha1 = Algorithm::Md5Sess.ha1("bob", "biloxi.com", "zanzibar");
+        assert_eq!(
+            credentials
+                .compute_digest("INVITE", b"v=0\r\n", &ha1)
+                .unwrap(),
+            "e4e4ea61d186d07a92c9e1f6919902e9"
+        );
+
+        credentials.algorithm = Algorithm::Sha256Sess;
+        credentials.qop = Some(Qop::AuthInt);
+        let ha1 = Algorithm::Sha256Sess.ha1("bob", "biloxi.com", "zanzibar");
+        assert_eq!(
+            credentials
+                .compute_digest("INVITE", b"v=0\r\n", &ha1)
+                .unwrap(),
+            "0b4a1efc19e1b701ec5775b6b16dde0fc416102e34a6c5bd6f957b20b5d79170"
+        );
+        credentials.cnonce = None;
+        assert_eq!(credentials.compute_digest("INVITE", b"v=0\r\n", &ha1), None);
+
+        // rfc2069 compatibility
+        credentials.algorithm = Algorithm::Md5;
+        credentials.qop = None;
+        credentials.nc = None;
+        let ha1 = Algorithm::Md5.ha1("bob", "biloxi.com", "zanzibar");
+        assert_eq!(
+            credentials.compute_digest("INVITE", b"", &ha1).unwrap(),
+            "bf57e4e0d0bffc0fbaedce64d59add5e"
+        );
+    }
+
+    #[test]
+    fn algorithm_from_str() {
+        assert_eq!(Algorithm::from_str("md5"), Some(Algorithm::Md5));
+        assert_eq!(
+            Algorithm::from_str("SHA-512-256-SESS"),
+            Some(Algorithm::Sha512_256Sess)
+        );
+        assert_eq!(Algorithm::from_str("SHA-512"), None);
+    }
+}
@@ -39,7 +39,7 @@ pub fn take<'a>(
 ) -> nom::IResult<&[u8], (&[u8] /*vstr*/, HeaderTags<'a>), SipParseError> {
     let (input, auth_schema) = take_while1(is_token_char)(source_input)?;
     let mut tags = HeaderTags::new();
-    tags.insert(HeaderTagType::AuthSchema, auth_schema);
+    tags.insert(HeaderTagType::AuthSchema, auth_schema.into());
     let (input, _) = take_sws(input)?; // LWS
     let mut input_tmp = input;
     // I use this value in end of fucntion. But compiler throw warning:
@@ -65,10 +65,10 @@ pub fn take<'a>(
             let tt = tag_type.unwrap();
             if tt == HeaderTagType::NonceCount {
                 if param_value.len() != 8 {
-                    return sip_parse_error!(2, "Invalid nonce len");
+                    return sip_parse_error!(Syntax, "Invalid nonce len");
                 }
             }
-            tags.insert(tt, param_value);
+            tags.insert(tt, param_value.into());
         }
         input_tmp = input;
 
@@ -5,7 +5,10 @@ use crate::common::{
     nom_wrappers::{from_utf8_nom, take_quoted_string, take_sws, take_while_trim_sws},
     take_sws_token,
 };
-use alloc::collections::btree_map::{BTreeMap, Keys};
+use alloc::{
+    borrow::Cow,
+    collections::btree_map::{BTreeMap, Keys},
+};
 use nom::{bytes::complete::take_while, multi::many0};
 use unicase::Ascii;
 
@@ -31,7 +34,7 @@ impl<'a> GenericParam<'a> {
         let (input, _) = take_sws_token::equal(input)?;
 
         if input.is_empty() {
-            return sip_parse_error!(2, "generic-param parse error");
+            return sip_parse_error!(Syntax, "generic-param parse error");
         }
 
         let (input, parameter_value) = if input[0] == b'"' {
@@ -50,24 +53,41 @@ impl<'a> GenericParam<'a> {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct GenericParams<'a> {
-    params: BTreeMap<Ascii<&'a str>, Option<&'a str>>,
+    params: BTreeMap<Ascii<Cow<'a, str>>, Option<Cow<'a, str>>>,
 }
 
 impl<'a> GenericParams<'a> {
-    pub fn get(&self, key: &'a str) -> Option<&Option<&'a str>> {
-        let key = Ascii::new(key);
-        self.params.get(&key)
+    /// Returns `Some(None)` for parameter without value
+    pub fn get<'s>(&'s self, key: &'s str) -> Option<Option<&'s str>> {
+        let params: &'s BTreeMap<Ascii<Cow<'s, str>>, Option<Cow<'s, str>>> = &self.params;
+        params
+            .get(&Ascii::new(Cow::Borrowed(key)))
+            .map(|value| value.as_deref())
     }
 
-    pub fn keys(&self) -> Keys<'_, Ascii<&'a str>, Option<&'a str>> {
+    pub fn keys(&self) -> Keys<'_, Ascii<Cow<'a, str>>, Option<Cow<'a, str>>> {
         self.params.keys()
     }
 
-    pub fn contains(&self, key: &'a str) -> bool {
-        let key = Ascii::new(key);
-        self.params.contains_key(&key)
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn into_owned(self) -> GenericParams<'static> {
+        GenericParams {
+            params: self
+                .params
+                .into_iter()
+                .map(|(k, v)| {
+                    (
+                        Ascii::new(Cow::Owned(k.into_inner().into_owned())),
+                        v.map(|v| Cow::Owned(v.into_owned())),
+                    )
+                })
+                .collect(),
+        }
     }
 
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], GenericParams<'a>, SipParseError> {
@@ -75,7 +95,15 @@ impl<'a> GenericParams<'a> {
         Ok((
             input,
             GenericParams {
-                params: vec_res.into_iter().collect(),
+                params: vec_res
+                    .into_iter()
+                    .map(|(k, v)| {
+                        (
+                            Ascii::new(Cow::Borrowed(k.into_inner())),
+                            v.map(Cow::Borrowed),
+                        )
+                    })
+                    .collect(),
             },
         ))
     }
@@ -85,7 +113,7 @@ fn many_params_parser(
     input: &[u8],
 ) -> nom::IResult<&[u8], (Ascii<&str>, Option<&str>), SipParseError> {
     if input.len() < 2 || input[0] != b';' {
-        return sip_parse_error!(1, "GenericParamsParser parse error");
+        return sip_parse_error!(Syntax, "GenericParamsParser parse error");
     }
     GenericParam::parse(&input[1..])
 }
@@ -94,7 +122,7 @@ mod tests {
     use super::*;
 
     fn assert_eq_gp(gparams: &GenericParams, key: &str, val: Option<&str>) {
-        assert_eq!(gparams.get(key), Some(&val));
+        assert_eq!(gparams.get(key), Some(val));
     }
     #[test]
     fn patameters_contains_test() {
@@ -1,19 +1,28 @@
 use crate::{
-    common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom, take_sws_token},
+    common::{
+        bnfcore::*,
+        errorparse::{locate, ErrorKind, SipParseError},
+        take_sws_token,
+    },
     headers::{
         parsers::ExtensionParser,
         traits::{HeaderValueParserFn, SipHeaderParser},
-        GenericParams, SipRFCHeader, SipUri,
+        GenericParams, SipRFCHeader, SipUri, Uri,
     },
+    SipParseOptions,
+};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    string::String,
 };
-use alloc::collections::{BTreeMap, VecDeque};
 use core::str;
 use nom::{bytes::complete::take_while1, character::complete};
 use unicase::Ascii;
 
 // All possible types of value
 // Glossary: R-required, O-optional
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum HeaderValueType {
     EmptyValue,           // SIP header with empty value. Haven't tags
     TokenValue,           // Haven't tags. Simple value of token chars
@@ -52,7 +61,7 @@ pub enum HeaderValueType {
     ExtensionHeader, // No tags
 }
 
-#[derive(PartialEq, Debug, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Debug, Eq, PartialOrd, Ord)]
 pub enum HeaderTagType {
     PureValue,
     AinfoType,   // nextnonce, qop, rspauth, etc.
@@ -96,23 +105,23 @@ pub enum HeaderTagType {
     WarnText,
 }
 
-pub type HeaderTags<'a> = BTreeMap<HeaderTagType, &'a [u8]>;
+pub type HeaderTags<'a> = BTreeMap<HeaderTagType, Cow<'a, [u8]>>;
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct HeaderValue<'a> {
-    pub vstr: &'a str,
+    pub vstr: Cow<'a, str>,
     pub vtype: HeaderValueType,
     vtags: Option<HeaderTags<'a>>,
-    sip_uri: Option<SipUri<'a>>,
+    uri: Option<Uri<'a>>,
 }
 
 impl<'a> HeaderValue<'a> {
     pub fn create_empty_value() -> HeaderValue<'a> {
         HeaderValue {
-            vstr: "",
+            vstr: Cow::Borrowed(""),
             vtype: HeaderValueType::EmptyValue,
             vtags: None,
-            sip_uri: None,
+            uri: None,
         }
     }
 
@@ -120,17 +129,15 @@ impl<'a> HeaderValue<'a> {
         val: &'a [u8],
         vtype: HeaderValueType,
         vtags: Option<HeaderTags<'a>>,
-        sip_uri: Option<SipUri<'a>>,
+        uri: Option<Uri<'a>>,
     ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
-        let (_, vstr) = from_utf8_nom(val)?;
-
         Ok((
             val,
             HeaderValue {
-                vstr: vstr,
+                vstr: String::from_utf8_lossy(val),
                 vtype: vtype,
                 vtags: vtags,
-                sip_uri: sip_uri,
+                uri,
             },
         ))
     }
@@ -139,22 +146,40 @@ impl<'a> HeaderValue<'a> {
         self.vtags.as_ref()
     }
 
+    pub fn uri(&self) -> Option<&Uri<'a>> {
+        self.uri.as_ref()
+    }
+
+    /// Returns `None` if URI is absent or it isn't SIP URI
     pub fn sip_uri(&self) -> Option<&SipUri<'a>> {
-        self.sip_uri.as_ref()
+        self.uri.as_ref()?.sip_uri()
+    }
+
+    pub fn into_owned(self) -> HeaderValue<'static> {
+        HeaderValue {
+            vstr: Cow::Owned(self.vstr.into_owned()),
+            vtype: self.vtype,
+            vtags: self.vtags.map(|tags| {
+                tags.into_iter()
+                    .map(|(k, v)| (k, Cow::Owned(v.into_owned())))
+                    .collect()
+            }),
+            uri: self.uri.map(|uri| uri.into_owned()),
+        }
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 /// [rfc3261 section-7.3](https://tools.ietf.org/html/rfc3261#section-7.3)
 pub struct Header<'a> {
     /// SIP header name
-    pub name: Ascii<&'a str>,
+    pub name: Ascii<Cow<'a, str>>,
     /// SIP header value
     pub value: HeaderValue<'a>,
     /// SIP parameters
     parameters: Option<GenericParams<'a>>,
     /// Raw representation part of string that contain value and params
-    pub raw_value_param: &'a[u8]
+    pub raw_value_param: Cow<'a, [u8]>,
 }
 
 impl<'a> Header<'a> {
@@ -162,13 +187,50 @@ impl<'a> Header<'a> {
         name: &'a str,
         value: HeaderValue<'a>,
         parameters: Option<GenericParams<'a>>,
-        raw_value_param: &'a[u8],
+        raw_value_param: &'a [u8],
     ) -> Header<'a> {
         Header {
-            name: { Ascii::new(name) },
+            name: Ascii::new(Cow::Borrowed(name)),
             value: value,
             parameters: parameters,
-            raw_value_param: raw_value_param
+            raw_value_param: Cow::Borrowed(raw_value_param),
+        }
+    }
+
+    /// Creates a header that owns its data, so it doesn't borrow `name` and `value`.
+    /// The value is checked by the parser of header `name`.
+    /// Comma-separated list of values is not accepted, use one header per value.
+    /// ```rust
+    /// use sipmsg::SipHeader;
+    ///
+    /// let via = SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK776asdhds").unwrap();
+    /// assert_eq!(via.value.vstr, "SIP/2.0/UDP 10.0.0.1");
+    /// assert_eq!(via.params().unwrap().get("branch"), Some(Some("z9hG4bK776asdhds")));
+    /// assert!(SipHeader::new_owned("Max-Forwards", "seventy").is_err());
+    /// ```
+    pub fn new_owned(
+        name: &str,
+        value: &str,
+    ) -> Result<Header<'static>, nom::Err<SipParseError<'static>>> {
+        let mut line = String::with_capacity(name.len() + value.len() + 4);
+        line.push_str(name);
+        line.push_str(": ");
+        line.push_str(value);
+        line.push_str("\r\n");
+        let to_static_err = |e: nom::Err<SipParseError>| e.map(|e| e.into_owned());
+        let (rest, (_, mut headers)) = Header::parse(line.as_bytes()).map_err(to_static_err)?;
+        if rest != b"\r\n" || headers.len() != 1 {
+            return sip_parse_error!(HeaderValue, "Header must contain exactly one value");
+        }
+        Ok(headers.pop_front().unwrap().into_owned())
+    }
+
+    pub fn into_owned(self) -> Header<'static> {
+        Header {
+            name: Ascii::new(Cow::Owned(self.name.into_inner().into_owned())),
+            value: self.value.into_owned(),
+            parameters: self.parameters.map(|p| p.into_owned()),
+            raw_value_param: Cow::Owned(self.raw_value_param.into_owned()),
         }
     }
 
@@ -188,7 +250,7 @@ impl<'a> Header<'a> {
         let (input, _) = take_sws_token::colon(input)?;
         match str::from_utf8(header_name) {
             Ok(hdr_str) => Ok((input, hdr_str)),
-            Err(_) => sip_parse_error!(1, "Bad header name"),
+            Err(_) => sip_parse_error!(HeaderName, "Bad header name"),
         }
     }
 
@@ -198,7 +260,7 @@ impl<'a> Header<'a> {
         parser: HeaderValueParserFn,
     ) -> nom::IResult<&'a [u8], (HeaderValue<'a>, Option<GenericParams<'a>>), SipParseError<'a>>
     {
-        if is_crlf(input) {
+        if line_ending_len(input) != 0 {
             return Ok((input, (HeaderValue::create_empty_value(), None))); // This is header with empty value
         }
 
@@ -207,11 +269,11 @@ impl<'a> Header<'a> {
 
         // skip whitespaces after take value
         let (inp, _) = complete::space0(inp)?;
-        if inp.is_empty() {
-            return sip_parse_error!(1, "Error parse header value");
-        }
-        if inp[0] != b',' && inp[0] != b';' && inp[0] != b' ' && !is_crlf(inp) {
-            return sip_parse_error!(2, "Error parse header value");
+        if inp.is_empty()
+            || (inp[0] != b',' && inp[0] != b';' && inp[0] != b' ' && line_ending_len(inp) == 0)
+        {
+            let e = SipParseError::new(ErrorKind::HeaderValue, Some("Error parse header value"));
+            return Err(nom::Err::Error(e.at(inp)));
         }
 
         if inp[0] == b';' {
@@ -234,24 +296,70 @@ impl<'a> Header<'a> {
     pub fn parse(
         input: &'a [u8],
     ) -> nom::IResult<&[u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError> {
+        Header::parse_ext(input, &SipParseOptions::default())
+    }
+
+    /// Parses header line with tolerances and limits of `options`
+    pub fn parse_ext(
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError<'a>>
+    {
+        locate(input, Header::take(input, options))
+    }
+
+    /// Parses header line, the line must end with line ending
+    pub(crate) fn take(
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError<'a>>
+    {
+        let (value_input, header_name) = Header::take_name(input)
+            .map_err(|e| e.map(|e| e.context(ErrorKind::HeaderName, input, 0)))?;
+        let (line, line_rest_len) = options.take_line(input, true).map_err(|e| {
+            e.map(|e| {
+                e.context(ErrorKind::HeaderValue, value_input, 0)
+                    .header(header_name)
+            })
+        })?;
+        let value_error = |e: nom::Err<SipParseError<'a>>| {
+            e.map(|e| {
+                e.context(ErrorKind::HeaderValue, value_input, line_rest_len)
+                    .header(header_name)
+            })
+        };
+        if !options.allow_invalid_utf8 {
+            if let Err(utf8_error) = str::from_utf8(line) {
+                let e = SipParseError::new(ErrorKind::HeaderValue, Some("Invalid UTF-8"));
+                let e = e.at(&input[utf8_error.valid_up_to()..]);
+                return Err(value_error(nom::Err::Error(e)));
+            }
+        }
         let mut headers = VecDeque::new();
-        let (input, header_name) = Header::take_name(input)?;
         let (rfc_type, value_parser) = Header::find_parser(header_name);
-        let mut inp = input;
+        let mut inp = value_input;
         loop {
-            let (input, (value, params)) = Header::take_value(inp, value_parser)?;
+            let (input, (value, params)) =
+                Header::take_value(inp, value_parser).map_err(value_error)?;
             headers.push_back(Header::new(header_name, value, params, &inp[..inp.len() - input.len()]));
             if input.is_empty() {
-                return sip_parse_error!(1, "header input is empty");
+                return Err(value_error(nom::Err::Error(SipParseError::new(
+                    ErrorKind::HeaderValue,
+                    Some("header input is empty"),
+                ))));
             }
             if input[0] == b',' {
-                let (input, _) = take_sws_token::comma(input)?;
+                let (input, _) = take_sws_token::comma(input).map_err(value_error)?;
                 inp = input;
                 continue;
             }
             inp = input;
             break;
         }
+        if line_ending_len(inp) == 0 {
+            let e = SipParseError::new(ErrorKind::HeaderValue, Some("Unexpected data after value"));
+            return Err(value_error(nom::Err::Error(e.at(inp))));
+        }
         Ok((inp, (rfc_type, headers)))
     }
 }
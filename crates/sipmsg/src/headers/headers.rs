@@ -1,53 +1,185 @@
 use crate::{
-    common::{bnfcore::is_crlf, errorparse::SipParseError},
-    headers::{SipHeader, SipRFCHeader},
+    common::{
+        date_time::DateTime,
+        errorparse::{locate, ErrorKind, SipParseError},
+    },
+    headers::{
+        typed::{CSeq, CallId, NameAddr, Via},
+        SipHeader, SipRFCHeader,
+    },
+    SipParseOptions,
 };
-use alloc::collections::{
-    btree_map::{BTreeMap, Keys},
-    VecDeque,
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{
+        btree_map::{BTreeMap, Keys},
+        VecDeque,
+    },
+    vec::Vec,
 };
 use core::str;
-use nom::bytes::complete::tag;
-use unicase::Ascii;
+use once_cell::race::OnceBox;
+use unicase::{eq_ascii, Ascii};
 
+/// Name of header line
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) enum HeaderKey<'a> {
+    Rfc(SipRFCHeader),
+    Ext(Ascii<Cow<'a, str>>),
+}
+
+impl<'a> HeaderKey<'a> {
+    fn from_header(header: &SipHeader<'a>) -> HeaderKey<'a> {
+        match SipRFCHeader::from_str(&header.name) {
+            Some(hdr) => HeaderKey::Rfc(hdr),
+            None => HeaderKey::Ext(header.name.clone()),
+        }
+    }
+
+    fn into_owned(self) -> HeaderKey<'static> {
+        match self {
+            HeaderKey::Rfc(hdr) => HeaderKey::Rfc(hdr),
+            HeaderKey::Ext(name) => {
+                HeaderKey::Ext(Ascii::new(Cow::Owned(name.into_inner().into_owned())))
+            }
+        }
+    }
+}
+
+/// Header line in wire order. One line can contain several comma-separated values.
+#[derive(Clone)]
+pub(crate) struct HeaderLine<'a> {
+    pub(crate) key: HeaderKey<'a>,
+    /// Index of the first value of line among values with the same name.
+    /// While values are not parsed it is index of line among lines with the same name.
+    pub(crate) first: usize,
+    /// Count of values in line
+    pub(crate) count: usize,
+    /// Original bytes of line without CRLF, `None` if the line was modified
+    pub(crate) raw: Option<Cow<'a, [u8]>>,
+    /// Line with line ending if its values are not moved from `LazyLines` yet,
+    /// `first` of such line is its index in `LazyLines`, `count` is zero
+    pub(crate) unparsed: Option<&'a [u8]>,
+}
+
+/// Values of lines with the same name that were parsed on access
+#[derive(Clone)]
+struct LazyValues<'a> {
+    values: VecDeque<SipHeader<'a>>,
+    /// `first` and `count` of each line, `None` if the line is invalid
+    bounds: Vec<Option<(usize, usize)>>,
+    invalid: Vec<InvalidHeader<'a>>,
+}
+
+/// Lines with the same name that are not parsed at parse time,
+/// see `SipParseOptions::lazy_headers`
+#[derive(Clone, Default)]
+struct LazyLines<'a> {
+    count: usize,
+    /// Parsed on the first access
+    values: OnceBox<LazyValues<'a>>,
+}
+
+/// Header line that was skipped by parser because of invalid value,
+/// see `SipParseOptions::allow_invalid_headers`
+#[derive(Clone, PartialEq, Debug)]
+pub struct InvalidHeader<'a> {
+    /// Name of header, `None` if the name is invalid
+    pub name: Option<Ascii<Cow<'a, str>>>,
+    /// Original bytes of line without line ending
+    pub raw: Cow<'a, [u8]>,
+    pub error: SipParseError<'a>,
+}
+
+impl<'a> InvalidHeader<'a> {
+    /// Copies all borrowed data, error loses its message and header name
+    pub fn into_owned(self) -> InvalidHeader<'static> {
+        InvalidHeader {
+            name: self
+                .name
+                .map(|name| Ascii::new(Cow::Owned(name.into_inner().into_owned()))),
+            raw: Cow::Owned(self.raw.into_owned()),
+            error: self.error.into_owned(),
+        }
+    }
+}
+
+/// Headers of message.
+///
+/// Values of header lines can be edited. Edited lines are serialized one value per line,
+/// other lines keep their wire order and original bytes.
+///
+/// If headers are parsed with `SipParseOptions::lazy_headers`, only names and bounds of lines
+/// are checked at parse time. Values of lines with the same name are parsed together
+/// on the first access and kept, values of invalid lines are skipped.
+/// Lines are serialized as is until their header is edited.
+/// `parse_all` reports invalid lines, see `SipParseOptions::allow_invalid_headers`.
+/// ```rust
+/// use sipmsg::{SipHeader, SipHeaders, SipRFCHeader};
+///
+/// let (_, mut headers) = SipHeaders::parse(
+///     b"Via: SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+///       Route: <sip:p1.example.com;lr>, <sip:p2.example.com;lr>\r\n\
+///       Max-Forwards: 70\r\n\
+///       Proxy-Authorization: Digest username=\"bob\"\r\n\r\n",
+/// )
+/// .unwrap();
+///
+/// headers.push_front(SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1").unwrap());
+/// let route = headers.pop_front_rfc(SipRFCHeader::Route).unwrap();
+/// assert_eq!(route.value.vstr, "<sip:p1.example.com;lr>");
+/// headers.set(SipHeader::new_owned("Max-Forwards", "69").unwrap());
+/// headers.remove_rfc(SipRFCHeader::ProxyAuthorization);
+///
+/// let values: Vec<&str> = headers.iter().map(|h| h.value.vstr.as_ref()).collect();
+/// assert_eq!(
+///     values,
+///     [
+///         "SIP/2.0/UDP 10.0.0.1",
+///         "SIP/2.0/UDP 10.0.0.2",
+///         "<sip:p2.example.com;lr>",
+///         "69"
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
 pub struct Headers<'a> {
     rfc_headers: BTreeMap<SipRFCHeader, VecDeque<SipHeader<'a>>>,
-    ext_headers: Option<BTreeMap<Ascii<&'a str>, VecDeque<SipHeader<'a>>>>,
+    ext_headers: Option<BTreeMap<Ascii<Cow<'a, str>>, VecDeque<SipHeader<'a>>>>,
+    /// Order of header lines in message
+    lines: Vec<HeaderLine<'a>>,
+    /// Lines with lazy values by name. Names of such lines are present
+    /// in `rfc_headers` and `ext_headers` with empty values.
+    lazy: BTreeMap<HeaderKey<'a>, LazyLines<'a>>,
+    /// Options of parser if there are lazy lines
+    lazy_options: Option<SipParseOptions>,
+    /// Skipped lines in wire order, invalid lazy lines are added when values are moved
+    /// from `lazy`
+    invalid: Vec<InvalidHeader<'a>>,
 }
 
 impl<'a> Headers<'a> {
     pub fn get_ext(&self, key: &'a str) -> Option<&VecDeque<SipHeader<'a>>> {
-        match &self.ext_headers {
-            Some(hdrs) => hdrs.get(&Ascii::new(key)),
-            None => None,
-        }
+        self.values(&HeaderKey::Ext(Ascii::new(Cow::Borrowed(key))))
     }
     /// Get headers that defined in rfc
     pub fn get_rfc(&self, hdr: SipRFCHeader) -> Option<&VecDeque<SipHeader<'a>>> {
-        self.rfc_headers.get(&hdr)
+        self.values(&HeaderKey::Rfc(hdr))
     }
 
     /// get single value
     /// Returns some value if header by key should be present only one time
     pub fn get_ext_s(&self, key: &'a str) -> Option<&SipHeader<'a>> {
-        match &self.ext_headers {
-            Some(hdrs) => match hdrs.get(&Ascii::new(key)) {
-                Some(s) => {
-                    if s.len() == 1 {
-                        return Some(&s[0]);
-                    } else {
-                        return None;
-                    };
-                }
-                None => None,
-            },
-            None => None,
+        match self.get_ext(key) {
+            Some(s) if s.len() == 1 => Some(&s[0]),
+            _ => None,
         }
     }
 
     /// Get header that defined in rfc
     pub fn get_rfc_s(&self, hdr: SipRFCHeader) -> Option<&SipHeader<'a>> {
-        match self.rfc_headers.get(&hdr) {
+        match self.get_rfc(hdr) {
             Some(s) => {
                 if s.len() == 1 {
                     return Some(&s[0]);
@@ -59,6 +191,54 @@ impl<'a> Headers<'a> {
         }
     }
 
+    /// Topmost Via header
+    pub fn top_via(&self) -> Option<Via<'_>> {
+        Via::from_header(self.get_rfc(SipRFCHeader::Via)?.front()?)
+    }
+
+    /// All Via headers in order
+    pub fn vias(&self) -> impl Iterator<Item = Via<'_>> {
+        self.get_rfc(SipRFCHeader::Via)
+            .into_iter()
+            .flatten()
+            .filter_map(Via::from_header)
+    }
+
+    pub fn cseq(&self) -> Option<CSeq> {
+        CSeq::from_header(self.get_rfc_s(SipRFCHeader::CSeq)?)
+    }
+
+    pub fn call_id(&self) -> Option<CallId<'_>> {
+        CallId::from_header(self.get_rfc_s(SipRFCHeader::CallID)?)
+    }
+
+    pub fn date(&self) -> Option<DateTime> {
+        DateTime::from_header(self.get_rfc_s(SipRFCHeader::Date)?)
+    }
+
+    pub fn from_addr(&self) -> Option<NameAddr<'_>> {
+        NameAddr::from_header(self.get_rfc_s(SipRFCHeader::From)?)
+    }
+
+    pub fn to_addr(&self) -> Option<NameAddr<'_>> {
+        NameAddr::from_header(self.get_rfc_s(SipRFCHeader::To)?)
+    }
+
+    /// Contact values except `*`
+    pub fn contacts(&self) -> impl Iterator<Item = NameAddr<'_>> {
+        self.get_rfc(SipRFCHeader::Contact)
+            .into_iter()
+            .flatten()
+            .filter_map(NameAddr::from_header)
+    }
+
+    /// Header lines that were skipped because of invalid value.
+    /// Lines are present only if headers are parsed with `SipParseOptions::allow_invalid_headers`,
+    /// or with `SipParseOptions::lazy_headers` after `parse_all` or editing of the header.
+    pub fn invalid_headers(&self) -> &[InvalidHeader<'a>] {
+        &self.invalid
+    }
+
     /// Returns length of unique headers
     // TODO rename to unique_len and add total_len
     pub fn len(&self) -> usize {
@@ -68,13 +248,425 @@ impl<'a> Headers<'a> {
         }
     }
 
-    fn new() -> Headers<'a> {
+    /// Iterator over all header values in wire order.
+    /// Comma-separated values of one line are returned as separate headers.
+    pub fn iter(&self) -> impl Iterator<Item = &SipHeader<'a>> + '_ {
+        self.lines
+            .iter()
+            .flat_map(move |line| self.line_values(line))
+    }
+
+    /// Add header before all values with the same name.
+    /// If there are no such values, header is added to the top of headers.
+    pub fn push_front(&mut self, header: SipHeader<'a>) {
+        let key = HeaderKey::from_header(&header);
+        let _ = self.parse_lines(&key);
+        let pos = self
+            .lines
+            .iter()
+            .position(|line| line.key == key)
+            .unwrap_or(0);
+        for line in self.lines.iter_mut().filter(|line| line.key == key) {
+            line.first += 1;
+        }
+        self.values_mut(&key).push_front(header);
+        self.insert_line(pos, key, 0);
+    }
+
+    /// Add header after all values with the same name.
+    /// If there are no such values, header is added to the bottom of headers.
+    pub fn push_back(&mut self, header: SipHeader<'a>) {
+        let key = HeaderKey::from_header(&header);
+        let _ = self.parse_lines(&key);
+        let pos = self
+            .lines
+            .iter()
+            .rposition(|line| line.key == key)
+            .map_or(self.lines.len(), |pos| pos + 1);
+        let first = self.values_count(&key);
+        self.values_mut(&key).push_back(header);
+        self.insert_line(pos, key, first);
+    }
+
+    /// Replace all values with the same name by `header`.
+    /// It takes place of the first line with the same name.
+    pub fn set(&mut self, header: SipHeader<'a>) {
+        let key = HeaderKey::from_header(&header);
+        let _ = self.parse_lines(&key);
+        let pos = self.lines.iter().position(|line| line.key == key);
+        self.remove(&key);
+        self.values_mut(&key).push_back(header);
+        self.insert_line(pos.unwrap_or(self.lines.len()), key, 0);
+    }
+
+    /// Remove and return the topmost value of header
+    pub fn pop_front_rfc(&mut self, hdr: SipRFCHeader) -> Option<SipHeader<'a>> {
+        self.pop_front(&HeaderKey::Rfc(hdr))
+    }
+
+    /// Remove and return the topmost value of extension header
+    pub fn pop_front_ext(&mut self, key: &str) -> Option<SipHeader<'a>> {
+        let key = self.find_ext_key(key)?;
+        self.pop_front(&key)
+    }
+
+    /// Remove all values of header
+    pub fn remove_rfc(&mut self, hdr: SipRFCHeader) -> Option<VecDeque<SipHeader<'a>>> {
+        self.remove(&HeaderKey::Rfc(hdr))
+    }
+
+    /// Remove all values of extension header
+    pub fn remove_ext(&mut self, key: &str) -> Option<VecDeque<SipHeader<'a>>> {
+        let key = self.find_ext_key(key)?;
+        self.remove(&key)
+    }
+
+    pub(crate) fn lines(&self) -> core::slice::Iter<'_, HeaderLine<'a>> {
+        self.lines.iter()
+    }
+
+    pub(crate) fn line_values(
+        &self,
+        line: &HeaderLine<'a>,
+    ) -> alloc::collections::vec_deque::Iter<'_, SipHeader<'a>> {
+        if line.unparsed.is_some() {
+            // Lazy values exist while lines are unparsed, invalid lines have no values
+            let lazy = self.lazy_values(&line.key).unwrap();
+            let (first, count) = lazy.bounds[line.first].unwrap_or((0, 0));
+            return lazy.values.range(first..first + count);
+        }
+        let values = match &line.key {
+            HeaderKey::Rfc(hdr) => self.rfc_headers.get(hdr),
+            HeaderKey::Ext(name) => self.ext_headers.as_ref().and_then(|h| h.get(name)),
+        };
+        // Index always refers to existing values
+        values.unwrap().range(line.first..line.first + line.count)
+    }
+
+    pub(crate) fn new() -> Headers<'a> {
         Headers {
             ext_headers: None,
             rfc_headers: BTreeMap::<SipRFCHeader, VecDeque<SipHeader<'a>>>::new(),
+            lines: Vec::new(),
+            lazy: BTreeMap::new(),
+            lazy_options: None,
+            invalid: Vec::new(),
+        }
+    }
+
+    /// Parses values of all lines that are not parsed yet, see `SipParseOptions::lazy_headers`.
+    /// Invalid lines are removed and added to `invalid_headers`. If invalid headers are not
+    /// allowed by options, the error of the first of them is returned.
+    /// Position of error is relative to the line.
+    pub fn parse_all(&mut self) -> Result<(), SipParseError<'a>> {
+        let keys: Vec<HeaderKey<'a>> = self.lazy.keys().cloned().collect();
+        let mut error = None;
+        for key in keys {
+            error = error.or(self.parse_lines(&key).err());
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Copies all borrowed data, so headers don't depend on the lifetime of input buffer
+    pub fn into_owned(mut self) -> Headers<'static> {
+        // Errors of unparsed lines are not reported, such lines are removed
+        let _ = self.parse_all();
+        let owned_values = |values: VecDeque<SipHeader<'a>>| -> VecDeque<SipHeader<'static>> {
+            values.into_iter().map(|hdr| hdr.into_owned()).collect()
+        };
+        Headers {
+            rfc_headers: self
+                .rfc_headers
+                .into_iter()
+                .map(|(key, values)| (key, owned_values(values)))
+                .collect(),
+            ext_headers: self.ext_headers.map(|ext_headers| {
+                ext_headers
+                    .into_iter()
+                    .map(|(key, values)| {
+                        let key = Ascii::new(Cow::Owned(key.into_inner().into_owned()));
+                        (key, owned_values(values))
+                    })
+                    .collect()
+            }),
+            lines: self
+                .lines
+                .into_iter()
+                .map(|line| HeaderLine {
+                    key: line.key.into_owned(),
+                    first: line.first,
+                    count: line.count,
+                    raw: line.raw.map(|raw| Cow::Owned(raw.into_owned())),
+                    unparsed: None,
+                })
+                .collect(),
+            lazy: BTreeMap::new(),
+            lazy_options: None,
+            invalid: self
+                .invalid
+                .into_iter()
+                .map(|invalid| invalid.into_owned())
+                .collect(),
+        }
+    }
+
+    /// Calculates positions of errors of invalid headers in `input`
+    pub(crate) fn locate_invalid_headers(&mut self, input: &[u8]) {
+        for invalid in self.invalid.iter_mut() {
+            invalid.error = invalid.error.clone().locate(input);
+        }
+    }
+
+    /// Values of header, lazy values are parsed on the first access
+    fn values(&self, key: &HeaderKey<'a>) -> Option<&VecDeque<SipHeader<'a>>> {
+        let values = match key {
+            HeaderKey::Rfc(hdr) => self.rfc_headers.get(hdr)?,
+            HeaderKey::Ext(name) => self.ext_headers.as_ref()?.get(name)?,
+        };
+        if values.is_empty() {
+            return self
+                .lazy_values(key)
+                .map(|lazy| &lazy.values)
+                .filter(|values| !values.is_empty());
+        }
+        Some(values)
+    }
+
+    fn lazy_values(&self, key: &HeaderKey<'a>) -> Option<&LazyValues<'a>> {
+        let lazy = self.lazy.get(key)?;
+        Some(
+            lazy.values
+                .get_or_init(|| Box::new(self.parse_lazy_values(key))),
+        )
+    }
+
+    fn parse_lazy_values(&self, key: &HeaderKey<'a>) -> LazyValues<'a> {
+        let options = self.lazy_options.unwrap_or_default();
+        let mut lazy = LazyValues {
+            values: VecDeque::new(),
+            bounds: Vec::new(),
+            invalid: Vec::new(),
+        };
+        for line in self.lines.iter().filter(|line| &line.key == key) {
+            let raw = match line.unparsed {
+                Some(raw) => raw,
+                None => continue,
+            };
+            let error = match locate(raw, SipHeader::take(raw, &options)) {
+                Ok((_, (_, values))) => {
+                    lazy.bounds.push(Some((lazy.values.len(), values.len())));
+                    lazy.values.extend(values);
+                    continue;
+                }
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => e,
+                Err(nom::Err::Incomplete(_)) => {
+                    SipParseError::new(ErrorKind::HeaderValue, None).locate(raw)
+                }
+            };
+            lazy.bounds.push(None);
+            let name = SipHeader::take_name(raw).ok().map(|(_, name)| name);
+            lazy.invalid.push(InvalidHeader {
+                name: name.map(|name| Ascii::new(Cow::Borrowed(name))),
+                raw: line.raw.clone().unwrap_or(Cow::Borrowed(raw)),
+                error,
+            });
+        }
+        lazy
+    }
+
+    /// Moves lazy values of `key` to headers, values are parsed if they were not accessed.
+    /// Invalid lines are moved to invalid headers, returns the error of the first of them
+    /// if invalid headers are not allowed.
+    fn parse_lines(&mut self, key: &HeaderKey<'a>) -> Result<(), SipParseError<'a>> {
+        let lazy = match self.lazy.get(key) {
+            Some(lazy) => match lazy.values.get() {
+                Some(values) => values.clone(),
+                None => self.parse_lazy_values(key),
+            },
+            None => return Ok(()),
+        };
+        self.lazy.remove(key);
+        let mut bounds = lazy.bounds.into_iter();
+        let mut idx = 0;
+        while idx < self.lines.len() {
+            let line = &mut self.lines[idx];
+            if &line.key == key && line.unparsed.is_some() {
+                match bounds.next().flatten() {
+                    Some((first, count)) => {
+                        line.first = first;
+                        line.count = count;
+                        line.unparsed = None;
+                    }
+                    None => {
+                        self.lines.remove(idx);
+                        continue;
+                    }
+                }
+            }
+            idx += 1;
+        }
+        if lazy.values.is_empty() {
+            self.remove_values(key);
+        } else {
+            *self.values_mut(key) = lazy.values;
+        }
+        let error = lazy.invalid.first().map(|invalid| invalid.error.clone());
+        self.invalid.extend(lazy.invalid);
+        let options = self.lazy_options.unwrap_or_default();
+        if self.lazy.is_empty() {
+            self.lazy_options = None;
+        }
+        match error {
+            Some(e) if !options.allow_invalid_headers => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds header line without parsing of values, returns input at the line ending.
+    /// Content-Length is parsed because it frames the body. Single-valued headers are parsed
+    /// if duplicates are not allowed, so repeated and comma-separated values are rejected
+    /// as by eager parser.
+    fn take_lazy_line(
+        &mut self,
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], (), SipParseError<'a>> {
+        let (value_input, header_name) = SipHeader::take_name(input)
+            .map_err(|e| e.map(|e| e.context(ErrorKind::HeaderName, input, 0)))?;
+        let key = match SipRFCHeader::from_str(header_name) {
+            Some(hdr_type) => {
+                if hdr_type == SipRFCHeader::ContentLength
+                    || (!options.allow_duplicate_headers && hdr_type.is_single_valued())
+                {
+                    return self.take_header_line(input, options);
+                }
+                HeaderKey::Rfc(hdr_type)
+            }
+            None => HeaderKey::Ext(Ascii::new(Cow::Borrowed(header_name))),
+        };
+        let (line, rest_len) = options.take_line(input, true).map_err(|e| {
+            e.map(|e| {
+                e.context(ErrorKind::HeaderValue, value_input, 0)
+                    .header(header_name)
+            })
+        })?;
+        self.lazy_options = Some(*options);
+        // Only the name is listed until values are moved from `lazy`
+        self.values_mut(&key);
+        let lazy = self.lazy.entry(key.clone()).or_default();
+        lazy.count += 1;
+        self.lines.push(HeaderLine {
+            key,
+            first: lazy.count - 1,
+            count: 0,
+            raw: Some(Cow::Borrowed(line)),
+            unparsed: Some(&input[..input.len() - rest_len]),
+        });
+        Ok((&input[line.len()..], ()))
+    }
+
+    fn find_ext_key(&self, key: &str) -> Option<HeaderKey<'a>> {
+        self.ext_headers
+            .as_ref()?
+            .keys()
+            .find(|name| eq_ascii(name.as_ref(), key))
+            .map(|name| HeaderKey::Ext(name.clone()))
+    }
+
+    fn values_count(&self, key: &HeaderKey<'a>) -> usize {
+        match key {
+            HeaderKey::Rfc(hdr) => self.rfc_headers.get(hdr).map_or(0, |v| v.len()),
+            HeaderKey::Ext(name) => self
+                .ext_headers
+                .as_ref()
+                .and_then(|h| h.get(name))
+                .map_or(0, |v| v.len()),
         }
     }
 
+    fn values_mut(&mut self, key: &HeaderKey<'a>) -> &mut VecDeque<SipHeader<'a>> {
+        match key {
+            HeaderKey::Rfc(hdr) => self.rfc_headers.entry(*hdr).or_default(),
+            HeaderKey::Ext(name) => self
+                .ext_headers
+                .get_or_insert_with(BTreeMap::new)
+                .entry(name.clone())
+                .or_default(),
+        }
+    }
+
+    fn remove_values(&mut self, key: &HeaderKey<'a>) -> Option<VecDeque<SipHeader<'a>>> {
+        match key {
+            HeaderKey::Rfc(hdr) => self.rfc_headers.remove(hdr),
+            HeaderKey::Ext(name) => {
+                let ext_headers = self.ext_headers.as_mut()?;
+                let values = ext_headers.remove(name);
+                if ext_headers.is_empty() {
+                    self.ext_headers = None;
+                }
+                values
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &HeaderKey<'a>) -> Option<VecDeque<SipHeader<'a>>> {
+        let _ = self.parse_lines(key);
+        self.lines.retain(|line| &line.key != key);
+        self.remove_values(key)
+    }
+
+    fn pop_front(&mut self, key: &HeaderKey<'a>) -> Option<SipHeader<'a>> {
+        let _ = self.parse_lines(key);
+        let values = self.values_mut(key);
+        let header = values.pop_front();
+        if values.is_empty() {
+            self.remove(key);
+            return header;
+        }
+        // The first line loses its first value, other lines are shifted
+        let pos = self.lines.iter().position(|line| &line.key == key)?;
+        for line in self.lines[pos + 1..]
+            .iter_mut()
+            .filter(|line| &line.key == key)
+        {
+            line.first -= 1;
+        }
+        let line = &mut self.lines[pos];
+        line.count -= 1;
+        line.raw = None;
+        if line.count == 0 {
+            self.lines.remove(pos);
+        }
+        header
+    }
+
+    fn insert_line(&mut self, pos: usize, key: HeaderKey<'a>, first: usize) {
+        self.lines.insert(
+            pos,
+            HeaderLine {
+                key,
+                first,
+                count: 1,
+                raw: None,
+                unparsed: None,
+            },
+        );
+    }
+
+    fn add_line(&mut self, key: HeaderKey<'a>, count: usize, raw: Option<Cow<'a, [u8]>>) {
+        let first = self.values_count(&key);
+        self.lines.push(HeaderLine {
+            key,
+            first,
+            count,
+            raw,
+            unparsed: None,
+        });
+    }
+
     fn add_rfc_header(
         &mut self,
         header_type: SipRFCHeader,
@@ -92,7 +684,8 @@ impl<'a> Headers<'a> {
 
     fn add_extension_header(&mut self, mut vec_headers: VecDeque<SipHeader<'a>>) {
         if self.ext_headers == None {
-            self.ext_headers = Some(BTreeMap::<Ascii<&'a str>, VecDeque<SipHeader<'a>>>::new());
+            self.ext_headers =
+                Some(BTreeMap::<Ascii<Cow<'a, str>>, VecDeque<SipHeader<'a>>>::new());
         }
 
         if self
@@ -111,7 +704,7 @@ impl<'a> Headers<'a> {
             self.ext_headers
                 .as_mut()
                 .unwrap()
-                .insert(vec_headers[0].name, vec_headers);
+                .insert(vec_headers[0].name.clone(), vec_headers);
         }
     }
 
@@ -121,7 +714,7 @@ impl<'a> Headers<'a> {
 
     pub fn get_ext_headers_keys(
         &self,
-    ) -> Option<Keys<'_, Ascii<&'a str>, VecDeque<SipHeader<'a>>>> {
+    ) -> Option<Keys<'_, Ascii<Cow<'a, str>>, VecDeque<SipHeader<'a>>>> {
         if self.ext_headers == None {
             return None;
         }
@@ -129,27 +722,108 @@ impl<'a> Headers<'a> {
     }
 
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], Headers<'a>, SipParseError> {
+        Headers::parse_ext(input, &SipParseOptions::default())
+    }
+
+    /// Parses headers with tolerances and limits of `options`
+    pub fn parse_ext(
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], Headers<'a>, SipParseError<'a>> {
+        locate(input, Headers::take(input, options))
+    }
+
+    pub(crate) fn take(
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], Headers<'a>, SipParseError<'a>> {
         let mut headers_result = Headers::new();
         let mut inp2 = input;
+        let mut count_lines = 0;
         loop {
-            let (input, (rfc_type, vec_headers)) = SipHeader::parse(inp2)?;
-            match rfc_type {
-                Some(hdr_type) => {
-                    headers_result.add_rfc_header(hdr_type, vec_headers);
-                }
-                None => {
-                    headers_result.add_extension_header(vec_headers);
-                }
+            if options.max_headers == Some(count_lines) {
+                let e = SipParseError::new(ErrorKind::TooManyHeaders, None);
+                return Err(nom::Err::Error(e.at(inp2)));
             }
-            let (input, _) = tag("\r\n")(input)?; // move to header parse
+            count_lines += 1;
+            let (input, _) = if options.lazy_headers {
+                headers_result.take_lazy_line(inp2, options)?
+            } else if options.allow_invalid_headers {
+                headers_result.take_or_skip_header_line(inp2, options)?
+            } else {
+                headers_result.take_header_line(inp2, options)?
+            };
+            let (input, _) = options.take_line_ending(input)?; // move to header parse
             inp2 = input; // skip crlf of header field
-            if is_crlf(inp2) {
+            if options.is_line_ending(inp2) {
                 // end of headers and start of body part
                 break;
             }
         }
+        headers_result.locate_invalid_headers(input);
         Ok((inp2, headers_result))
     }
+
+    /// Adds header line, returns input at the line ending
+    fn take_header_line(
+        &mut self,
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], (), SipParseError<'a>> {
+        let (rest, (rfc_type, vec_headers)) = SipHeader::take(input, options)?;
+        let raw_line = &input[..input.len() - rest.len()];
+        match rfc_type {
+            Some(hdr_type) => {
+                if !options.allow_duplicate_headers
+                    && hdr_type.is_single_valued()
+                    && (vec_headers.len() > 1 || self.get_rfc(hdr_type).is_some())
+                {
+                    let e = SipParseError::new(ErrorKind::DuplicateHeader, None);
+                    return Err(nom::Err::Error(e.at(input).header(hdr_type.as_str())));
+                }
+                let key = HeaderKey::Rfc(hdr_type);
+                self.add_line(key, vec_headers.len(), Some(Cow::Borrowed(raw_line)));
+                self.add_rfc_header(hdr_type, vec_headers);
+            }
+            None => {
+                let key = HeaderKey::Ext(vec_headers[0].name.clone());
+                self.add_line(key, vec_headers.len(), Some(Cow::Borrowed(raw_line)));
+                self.add_extension_header(vec_headers);
+            }
+        }
+        Ok((rest, ()))
+    }
+
+    /// Adds header line, if the line is invalid it is added to invalid headers.
+    /// Invalid Content-Length and lines without valid line ending are not skipped.
+    fn take_or_skip_header_line(
+        &mut self,
+        input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], (), SipParseError<'a>> {
+        let mut e = match self.take_header_line(input, options) {
+            Err(nom::Err::Error(e)) => e,
+            result => return result,
+        };
+        let name = SipHeader::take_name(input).ok().map(|(_, name)| name);
+        // Content-Length frames the body
+        if name.and_then(SipRFCHeader::from_str) == Some(SipRFCHeader::ContentLength) {
+            return Err(nom::Err::Error(e));
+        }
+        let line = match options.take_line(input, true) {
+            Ok((line, _)) => line,
+            Err(_) => return Err(nom::Err::Error(e)),
+        };
+        if let (None, Some(name)) = (e.header, name) {
+            e = e.header(name);
+        }
+        self.invalid.push(InvalidHeader {
+            name: name.map(|name| Ascii::new(Cow::Borrowed(name))),
+            raw: Cow::Borrowed(line),
+            error: e,
+        });
+        Ok((&input[line.len()..], ()))
+    }
 }
 
 #[cfg(test)]
@@ -189,7 +863,7 @@ mod tests {
                         .params()
                         .unwrap()
                         .get(&"q"),
-                    Some(&Some("0.1"))
+                    Some(Some("0.1"))
                 );
 
                 assert_eq!(
@@ -209,4 +883,184 @@ mod tests {
             Err(_) => panic!(),
         }
     }
+
+    #[test]
+    fn headers_wire_order_test() {
+        let (_, hdrs) = Headers::parse(
+            "Via: SIP/2.0/UDP funky.example.com, SIP/2.0/UDP 192.168.1.111\r\n\
+             Extention-Header: Value1\r\n\
+             To: sip:user@example.com\r\n\
+             extention-header: Value2\r\n\
+             Via: SIP/2.0/TCP 192.168.1.112\r\n\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let values: Vec<&str> = hdrs.iter().map(|h| h.value.vstr.as_ref()).collect();
+        assert_eq!(
+            values,
+            [
+                "SIP/2.0/UDP funky.example.com",
+                "SIP/2.0/UDP 192.168.1.111",
+                "Value1",
+                "sip:user@example.com",
+                "Value2",
+                "SIP/2.0/TCP 192.168.1.112"
+            ]
+        );
+    }
+
+    #[test]
+    fn headers_edit_test() {
+        let (_, mut hdrs) = Headers::parse(
+            "Route: <sip:p1.example.com;lr>, <sip:p2.example.com;lr>\r\n\
+             X-Tag: a\r\n\
+             Route: <sip:p3.example.com;lr>\r\n\
+             x-tag: b\r\n\
+             Max-Forwards: 70\r\n\r\n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let route = hdrs.pop_front_rfc(SipRFCHeader::Route).unwrap();
+        assert_eq!(route.value.vstr, "<sip:p1.example.com;lr>");
+        let route = hdrs.pop_front_rfc(SipRFCHeader::Route).unwrap();
+        assert_eq!(route.value.vstr, "<sip:p2.example.com;lr>");
+        assert_eq!(hdrs.get_rfc(SipRFCHeader::Route).unwrap().len(), 1);
+
+        hdrs.push_back(SipHeader::new_owned("Record-Route", "<sip:p4.example.com;lr>").unwrap());
+        hdrs.push_back(SipHeader::new_owned("X-TAG", "c").unwrap());
+        hdrs.push_front(SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.1").unwrap());
+        hdrs.set(SipHeader::new_owned("Max-Forwards", "69").unwrap());
+        assert_eq!(hdrs.pop_front_ext("x-tag").unwrap().value.vstr, "a");
+
+        let values: Vec<&str> = hdrs.iter().map(|h| h.value.vstr.as_ref()).collect();
+        assert_eq!(
+            values,
+            [
+                "SIP/2.0/UDP 10.0.0.1",
+                "<sip:p3.example.com;lr>",
+                "b",
+                "c",
+                "69",
+                "<sip:p4.example.com;lr>"
+            ]
+        );
+        assert_eq!(hdrs.get_ext("x-tag").unwrap().len(), 2);
+
+        assert_eq!(hdrs.remove_ext("X-Tag").unwrap().len(), 2);
+        assert!(hdrs.get_ext_headers_keys().is_none());
+        assert!(hdrs.remove_rfc(SipRFCHeader::Route).is_some());
+        assert!(hdrs.pop_front_rfc(SipRFCHeader::Route).is_none());
+        assert_eq!(hdrs.iter().count(), 3);
+    }
+
+    #[test]
+    fn headers_sync_test() {
+        fn assert_sync<T: Sync + Send>() {}
+        assert_sync::<Headers<'static>>();
+    }
+
+    #[test]
+    fn headers_lazy_test() {
+        let options = SipParseOptions {
+            lazy_headers: true,
+            ..SipParseOptions::default()
+        };
+        let (rest, mut hdrs) = Headers::parse_ext(
+            "Route: <sip:p1.example.com;lr>, <sip:p2.example.com;lr>\r\n\
+             X-Tag: a\r\n\
+             Route: <sip:p3.example.com;lr>\r\n\
+             Max-Forwards: seventy\r\n\
+             x-tag: b\r\n\
+             Via: SIP/2.0/UDP 10.0.0.1\r\n\r\nbody"
+                .as_bytes(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(rest, b"\r\nbody");
+        assert!(hdrs.lines.iter().all(|line| line.unparsed.is_some()));
+        assert_eq!(hdrs.len(), 4);
+        assert!(hdrs.lazy[&HeaderKey::Rfc(SipRFCHeader::Route)]
+            .values
+            .get()
+            .is_none());
+
+        // Values are parsed on access
+        assert_eq!(hdrs.get_rfc(SipRFCHeader::Route).unwrap().len(), 3);
+        assert!(hdrs.lazy[&HeaderKey::Rfc(SipRFCHeader::Via)]
+            .values
+            .get()
+            .is_none());
+        assert_eq!(hdrs.get_ext("x-tag").unwrap().len(), 2);
+        assert_eq!(hdrs.top_via().unwrap().host, "10.0.0.1");
+        assert!(hdrs.get_rfc(SipRFCHeader::MaxForwards).is_none());
+        let values: Vec<&str> = hdrs.iter().map(|h| h.value.vstr.as_ref()).collect();
+        assert_eq!(
+            values,
+            [
+                "<sip:p1.example.com;lr>",
+                "<sip:p2.example.com;lr>",
+                "a",
+                "<sip:p3.example.com;lr>",
+                "b",
+                "SIP/2.0/UDP 10.0.0.1"
+            ]
+        );
+        assert!(hdrs.invalid_headers().is_empty());
+
+        // Editing moves values of the edited header only
+        let route = hdrs.pop_front_rfc(SipRFCHeader::Route).unwrap();
+        assert_eq!(route.value.vstr, "<sip:p1.example.com;lr>");
+        hdrs.push_front(SipHeader::new_owned("Via", "SIP/2.0/UDP 10.0.0.2").unwrap());
+        assert_eq!(hdrs.get_rfc(SipRFCHeader::Via).unwrap().len(), 2);
+        assert_eq!(hdrs.lazy.len(), 2);
+        assert_eq!(hdrs.iter().count(), 6);
+
+        let mut strict_hdrs = hdrs.clone();
+        let e = strict_hdrs.parse_all().unwrap_err();
+        assert_eq!(e.kind, ErrorKind::HeaderValue);
+        assert_eq!(e.header, Some("Max-Forwards"));
+        assert!(strict_hdrs.lazy.is_empty());
+        assert!(strict_hdrs.lazy_options.is_none());
+        assert_eq!(strict_hdrs.len(), 3);
+        assert_eq!(strict_hdrs.iter().count(), 6);
+        assert_eq!(strict_hdrs.invalid_headers().len(), 1);
+        assert_eq!(
+            strict_hdrs.invalid_headers()[0].raw,
+            &b"Max-Forwards: seventy"[..]
+        );
+        assert!(strict_hdrs.parse_all().is_ok());
+
+        hdrs.lazy_options = Some(SipParseOptions {
+            allow_invalid_headers: true,
+            ..options
+        });
+        assert!(hdrs.parse_all().is_ok());
+        assert_eq!(hdrs.invalid_headers().len(), 1);
+        assert_eq!(hdrs.iter().count(), 6);
+
+        // Single-valued headers are checked as by eager parser
+        for input in [
+            "CSeq: 1 INVITE\r\nCSeq: 2 INVITE\r\n\r\n",
+            "CSeq: 1 INVITE, 2 INVITE\r\n\r\n",
+        ]
+        .iter()
+        {
+            for lazy_headers in [false, true].iter() {
+                let options = SipParseOptions {
+                    lazy_headers: *lazy_headers,
+                    ..SipParseOptions::strict()
+                };
+                match Headers::parse_ext(input.as_bytes(), &options) {
+                    Err(nom::Err::Error(e)) => assert_eq!(e.kind, ErrorKind::DuplicateHeader),
+                    _ => panic!(),
+                }
+                let options = SipParseOptions {
+                    lazy_headers: *lazy_headers,
+                    ..SipParseOptions::default()
+                };
+                assert!(Headers::parse_ext(input.as_bytes(), &options).is_ok());
+            }
+        }
+    }
 }
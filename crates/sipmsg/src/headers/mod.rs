@@ -1,5 +1,7 @@
 mod headers;
+pub(crate) use headers::HeaderKey;
 pub use headers::Headers as SipHeaders;
+pub use headers::InvalidHeader as SipInvalidHeader;
 
 mod header;
 pub use header::Header as SipHeader;
@@ -17,6 +19,21 @@ pub use generic_params::GenericParams;
 pub mod sipuri;
 pub use sipuri::SipUri;
 
+pub mod uri_params;
+pub use uri_params::UriParams;
+
+pub mod teluri;
+pub use teluri::TelUri;
+
+pub mod uri;
+pub use uri::{AbsoluteUri, Uri};
+
+mod typed;
+pub use typed::CSeq as SipCSeq;
+pub use typed::CallId as SipCallId;
+pub use typed::NameAddr as SipNameAddr;
+pub use typed::Via as SipVia;
+
 mod name_addr;
 mod parsers;
 mod auth_params;
\ No newline at end of file
@@ -1,24 +1,28 @@
 use crate::{
-    common::{bnfcore::is_token_char, errorparse::SipParseError, take_sws_token, nom_wrappers},
+    common::{
+        bnfcore::{is_alpha, is_alphanum, is_token_char},
+        errorparse::SipParseError,
+        nom_wrappers, take_sws_token,
+    },
     headers::{
         header::{HeaderTagType, HeaderTags},
+        Uri,
     },
 };
 
-use nom::{
-    bytes::complete::{take_while1},
-    character::complete,
-    sequence::tuple,
-};
-
-use crate::SipUri;
+use nom::{bytes::complete::take_while1, character::complete};
 
 #[derive(PartialEq, Debug)]
 pub enum NameAddrValueType {
     QuotedDisplayName,
     TokenDisplayName,
-    SipURI,
-    AquoutedSipURI,
+    Uri,
+    AquoutedUri,
+}
+
+/// scheme  =  ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+fn is_scheme_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'+' || c == b'-' || c == b'.'
 }
 
 fn predict_value_type(input: &[u8]) -> NameAddrValueType {
@@ -27,18 +31,19 @@ fn predict_value_type(input: &[u8]) -> NameAddrValueType {
     }
 
     if input[0] == b'<' {
-        return NameAddrValueType::AquoutedSipURI;
+        return NameAddrValueType::AquoutedUri;
     }
 
-    if &input[..3] != b"sip" {
+    if !is_alpha(input[0]) {
         return NameAddrValueType::TokenDisplayName;
     }
 
-    if input[3] == b':' || &input[3..5] == b"s:" {
-        return NameAddrValueType::SipURI; // this is start of URI, display name isn't present
+    let scheme_len = input.iter().take_while(|c| is_scheme_char(**c)).count();
+    if input.get(scheme_len) == Some(&b':') {
+        return NameAddrValueType::Uri; // this is start of URI, display name isn't present
     }
 
-    return NameAddrValueType::TokenDisplayName;
+    NameAddrValueType::TokenDisplayName
 }
 
 fn take_display_name(
@@ -54,16 +59,19 @@ fn take_display_name(
         return Ok((input, display_name));
     }
     sip_parse_error!(
-        666,
+        HeaderValue,
         "Parsing of contact is failed. Something wrong we should never be here"
     )
 }
 
+/// Raw value without trailing whitespaces, tags and URI
+type NameAddrParts<'a> = (&'a [u8], HeaderTags<'a>, Option<Uri<'a>>);
+
 pub fn take<'a>(
     source_input: &'a [u8],
-) -> nom::IResult<&'a [u8], (&[u8], HeaderTags<'a>, Option<SipUri>), SipParseError<'a>> {
+) -> nom::IResult<&'a [u8], NameAddrParts<'a>, SipParseError<'a>> {
     if source_input.len() < 5 {
-        return sip_parse_error!(2, "name-addr header value is too short");
+        return sip_parse_error!(HeaderValue, "name-addr header value is too short");
     }
     let mut tags = HeaderTags::new();
     let next_value_type = predict_value_type(source_input);
@@ -71,14 +79,14 @@ pub fn take<'a>(
         || next_value_type == NameAddrValueType::TokenDisplayName
     {
         let (input, display_name) = take_display_name(source_input, next_value_type)?;
-        tags.insert(HeaderTagType::DisplayName, display_name);
+        tags.insert(HeaderTagType::DisplayName, display_name.into());
         input
     } else {
         source_input
     };
 
     if input.is_empty() {
-        return sip_parse_error!(3, "Contact header value is invalid");
+        return sip_parse_error!(HeaderValue, "Contact header value is invalid");
     }
 
     let (input, is_quoted_uri) = if input[0] == b'<' {
@@ -88,46 +96,28 @@ pub fn take<'a>(
         (input, false)
     };
 
-    if source_input.len() < 5 {
-        return sip_parse_error!(2, "Contact header value is too short");
+    let (rest, uri) = Uri::parse_ext(input, is_quoted_uri)?;
+    if uri.sip_uri().is_none() {
+        tags.insert(
+            HeaderTagType::AbsoluteURI,
+            input[..input.len() - rest.len()].into(),
+        );
     }
 
-    let is_sip_uri = &input[..4] == b"sip:" || &input[..5] == b"sips:";
-    if !is_sip_uri && !is_quoted_uri {
-        return sip_parse_error!(4, "Absolute uri in contact header without <> not supported");
-    }
-
-    if is_sip_uri {
-        let (input, sipuri) = SipUri::parse_ext(input, is_quoted_uri)?;
-        let mut count_wsps_after_raquout = 0;
-        let input = if is_quoted_uri {
-            let (input, wsps_after) = take_sws_token::raquot(input)?;
-            count_wsps_after_raquout = wsps_after.len();
-            input
-        } else {
-            input
-        };
-        return Ok((
-            input,
-            (
-                &source_input[..source_input.len() - input.len() - count_wsps_after_raquout],
-                tags,
-                Some(sipuri),
-            ),
-        ));
-    }
-
-    // this is absolute uri
-    let uri_taker = take_while1(|c| c != b'>');
-    let (input, (uri, spaces_after_raquot)) = tuple((uri_taker, take_sws_token::raquot))(input)?;
-    tags.insert(HeaderTagType::AbsoluteURI, uri);
-
+    let mut count_wsps_after_raquout = 0;
+    let input = if is_quoted_uri {
+        let (input, wsps_after) = take_sws_token::raquot(rest)?;
+        count_wsps_after_raquout = wsps_after.len();
+        input
+    } else {
+        rest
+    };
     Ok((
         input,
         (
-            &source_input[..source_input.len() - input.len() - spaces_after_raquot.len()],
+            &source_input[..source_input.len() - input.len() - count_wsps_after_raquout],
             tags,
-            None,
+            Some(uri),
         ),
     ))
 }
@@ -28,14 +28,20 @@ impl SipHeaderParser for AcceptLanguageParser {
             return Ok((input, hdr_val));
         }
         if left_part.len() < 1 || left_part.len() > 8 {
-            return sip_parse_error!(1, "Invalid length of left part of AcceptLanguage Header");
+            return sip_parse_error!(
+                HeaderValue,
+                "Invalid length of left part of AcceptLanguage Header"
+            );
         }
 
         let (input, _) = nom::character::complete::char('-')(input)?; // skip -
         let (input, right_part) = take_while1(is_alpha)(input)?;
 
         if right_part.len() < 1 || right_part.len() > 8 {
-            return sip_parse_error!(2, "Invalid length of right part of AcceptLanguage Header");
+            return sip_parse_error!(
+                HeaderValue,
+                "Invalid length of right part of AcceptLanguage Header"
+            );
         }
         let offset = left_part.len() + right_part.len() + 1 /*`-`*/;
         let (_, hdr_val) = HeaderValue::new(
@@ -21,7 +21,7 @@ impl SipHeaderParser for AlertInfoParser {
 
 
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::AbsoluteURI, uri);
+        tags.insert(HeaderTagType::AbsoluteURI, uri.into());
 
         // 1 for '>' char
         let (_, hdr_val) = HeaderValue::new(
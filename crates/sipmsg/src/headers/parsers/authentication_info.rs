@@ -1,6 +1,8 @@
 use crate::{
     common::{
-        bnfcore::is_alpha, errorparse::SipParseError, nom_wrappers::take_quoted_string,
+        bnfcore::{is_alpha, is_token_char},
+        errorparse::SipParseError,
+        nom_wrappers::take_quoted_string,
         take_sws_token,
     },
     headers::{
@@ -8,7 +10,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
-use nom::bytes::complete::take_while;
+use nom::bytes::complete::{take_while, take_while1};
 
 pub struct AuthenticationInfoParser;
 
@@ -29,14 +31,21 @@ impl SipHeaderParser for AuthenticationInfoParser {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let (input, info_name) = take_while(is_alpha)(source_input)?;
         if !AuthenticationInfoParser::is_info_name_allowed(info_name) {
-            return sip_parse_error!(1, "AuthentificatiionInfo value name is invalid");
+            return sip_parse_error!(HeaderValue, "AuthentificatiionInfo value name is invalid");
         }
         let (input, (_, _, _)) = take_sws_token::equal(input)?;
-        let (input, (_, value, spaces_after_rdquot)) = take_quoted_string(input).unwrap();
+        // message-qop and nonce-count are tokens
+        let (input, (value, spaces_after_rdquot)) = match take_quoted_string(input) {
+            Ok((input, (_, value, spaces_after_rdquot))) => (input, (value, spaces_after_rdquot)),
+            Err(_) => {
+                let (rest, value) = take_while1(is_token_char)(input)?;
+                (rest, (value, &rest[..0]))
+            }
+        };
 
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::AinfoType, info_name);
-        tags.insert(HeaderTagType::AinfoValue, value);
+        tags.insert(HeaderTagType::AinfoType, info_name.into());
+        tags.insert(HeaderTagType::AinfoValue, value.into());
 
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len() - spaces_after_rdquot.len()],
@@ -67,5 +76,15 @@ mod test {
             val.tags().unwrap()[&HeaderTagType::AinfoValue],
             "47364c23432d2e131a5fb210812c".as_bytes()
         );
+
+        let (input, val) =
+            AuthenticationInfoParser::take_value("nc=00000001, qop=auth".as_bytes()).unwrap();
+        assert_eq!(input, ", qop=auth".as_bytes());
+        assert_eq!(val.vstr, "nc=00000001");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::AinfoValue],
+            "00000001".as_bytes()
+        );
+        assert!(AuthenticationInfoParser::take_value("nc=,".as_bytes()).is_err());
     }
 }
@@ -47,7 +47,7 @@ mod test {
         assert_eq!(val.vstr, "NoOneKnowsThisScheme opaque-data=here");
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::AuthSchema],
-            b"NoOneKnowsThisScheme"
+            &b"NoOneKnowsThisScheme"[..]
         );
         assert_eq!(input, b"\r\n");
     }
@@ -64,22 +64,31 @@ mod test {
         \turi=\"sip:bob@biloxi.com\", qop=auth, nc=00000001,unkownqparam=\"value\", cnonce=\"0a4f113b\", \
         response=\"6629fae49393a05397450978507c4ef1\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"");
         assert_eq!(input, b"\r\n");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Username], b"bob");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Realm], b"biloxi.com");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Username], &b"bob"[..]);
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Realm],
+            &b"biloxi.com"[..]
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::DigestUri],
-            b"sip:bob@biloxi.com"
+            &b"sip:bob@biloxi.com"[..]
+        );
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::QopValue], &b"auth"[..]);
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::NonceCount],
+            &b"00000001"[..]
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Cnonce],
+            &b"0a4f113b"[..]
         );
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::QopValue], b"auth");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::NonceCount], b"00000001");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Cnonce], b"0a4f113b");
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Dresponse],
-            b"6629fae49393a05397450978507c4ef1"
+            &b"6629fae49393a05397450978507c4ef1"[..]
         );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Opaque],
-            b"5ccc069c403ebaf9f0171e9517f40e41"
+            &b"5ccc069c403ebaf9f0171e9517f40e41"[..]
         );
     }
 }
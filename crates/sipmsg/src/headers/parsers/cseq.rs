@@ -19,8 +19,8 @@ impl SipHeaderParser for CSeq {
         let (input, number) = take_while1(is_digit)(source_input)?;
         let (input, _) = take_sws(input)?;
         let (input, method) = take_while1(is_token_char)(input)?;
-        tags.insert(HeaderTagType::Number, number);
-        tags.insert(HeaderTagType::Method, method);
+        tags.insert(HeaderTagType::Number, number.into());
+        tags.insert(HeaderTagType::Method, method.into());
 
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len()],
@@ -1,4 +1,7 @@
-use crate::common::bnfcore::is_wsp;
+use crate::common::{
+    bnfcore::{is_wsp, line_ending_len},
+    nom_wrappers::take_until_line_ending,
+};
 use crate::{
     common::errorparse::SipParseError,
     headers::{
@@ -6,7 +9,6 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
-use nom::bytes::complete::take_until;
 
 pub struct ExtensionParser;
 
@@ -14,10 +16,11 @@ impl SipHeaderParser for ExtensionParser {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let mut taken_bytes = 0;
         loop {
-            let (inp, res_val) = take_until("\r\n")(&source_input[taken_bytes..])?;
+            let (inp, res_val) = take_until_line_ending(&source_input[taken_bytes..])?;
             taken_bytes += res_val.len();
-            if inp.len() > 3 && is_wsp(inp[2]) {
-                taken_bytes += 3;
+            let line_ending = line_ending_len(inp);
+            if inp.len() > line_ending + 1 && is_wsp(inp[line_ending]) {
+                taken_bytes += line_ending + 1;
                 continue;
             }
             break;
@@ -15,8 +15,8 @@ impl SipHeaderParser for MimeVersion {
         let (inp, _) = nom::character::complete::char('.')(inp)?;
         let (inp, minor) = take_while1(is_digit)(inp)?;
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::Major, major);
-        tags.insert(HeaderTagType::Minor, minor);
+        tags.insert(HeaderTagType::Major, major.into());
+        tags.insert(HeaderTagType::Minor, minor.into());
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - inp.len()],
             HeaderValueType::Digit,
@@ -1,6 +1,6 @@
 use crate::{
     common::{
-        bnfcore::{is_crlf, is_digit},
+        bnfcore::{is_digit, line_ending_len},
         errorparse::SipParseError,
         nom_wrappers::take_sws,
     },
@@ -9,6 +9,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 use nom::bytes::complete::take_while1;
 
 pub struct Timestamp;
@@ -17,7 +18,7 @@ impl SipHeaderParser for Timestamp {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let (input, _int_part_time) = take_while1(is_digit)(source_input)?;
         if input.is_empty() {
-            return sip_parse_error!(1, "Invalid Timestamp Header");
+            return sip_parse_error!(HeaderValue, "Invalid Timestamp Header");
         }
         let mut tags = HeaderTags::new();
 
@@ -29,11 +30,11 @@ impl SipHeaderParser for Timestamp {
         };
         tags.insert(
             HeaderTagType::TimveVal,
-            &source_input[..source_input.len() - input.len()],
+            Cow::Borrowed(&source_input[..source_input.len() - input.len()]),
         );
         let (start_possible_delay_val, _) = take_sws(input)?;
         let mut tmp_inp = start_possible_delay_val;
-        if !is_crlf(input) {
+        if line_ending_len(input) == 0 {
             let (input, _) = take_while1(is_digit)(tmp_inp)?;
             if !input.is_empty() && input[0] == b'.' {
                 let (input, _) = take_while1(is_digit)(&input[1..])?;
@@ -43,7 +44,9 @@ impl SipHeaderParser for Timestamp {
             }
             tags.insert(
                 HeaderTagType::Delay,
-                &start_possible_delay_val[..start_possible_delay_val.len() - tmp_inp.len()],
+                Cow::Borrowed(
+                    &start_possible_delay_val[..start_possible_delay_val.len() - tmp_inp.len()],
+                ),
             );
         };
         let (_, hdr_val) = HeaderValue::new(
@@ -72,7 +75,7 @@ mod test {
         let (input, val) = Timestamp::take_value(b"12.34 0.5\r\n").unwrap();
         assert_eq!(input, b"\r\n");
         assert_eq!(val.vstr, "12.34 0.5");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::TimveVal], b"12.34");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Delay], b"0.5");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::TimveVal], &b"12.34"[..]);
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Delay], &b"0.5"[..]);
     }
 }
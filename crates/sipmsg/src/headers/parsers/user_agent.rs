@@ -1,6 +1,6 @@
 use crate::{
     common::{
-        bnfcore::{is_crlf, is_token_char},
+        bnfcore::{is_token_char, line_ending_len},
         errorparse::SipParseError,
         nom_wrappers::take_sws,
         take_sws_token,
@@ -22,7 +22,7 @@ impl SipHeaderParser for UserAgent {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let mut tmp_input = source_input;
         loop {
-            if tmp_input.len() < 2 || is_crlf(tmp_input) {
+            if tmp_input.len() < 2 || line_ending_len(tmp_input) != 0 {
                 break;
             }
 
@@ -1,14 +1,18 @@
 use crate::{
-    common::{bnfcore::is_wsp, errorparse::SipParseError, nom_wrappers::take_sws},
+    common::{
+        bnfcore::{is_wsp, line_ending_len},
+        errorparse::SipParseError,
+        nom_wrappers::{take_sws, take_until_line_ending},
+    },
     headers::header::{HeaderValue, HeaderValueType},
 };
-use nom::bytes::complete::take_until;
 
 pub fn take(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
     let mut tmp_input = source_input;
     loop {
-        let (input, _) = take_until("\r\n")(tmp_input)?;
-        if input.len() > 3 && is_wsp(input[2]) {
+        let (input, _) = take_until_line_ending(tmp_input)?;
+        let line_ending = line_ending_len(input);
+        if input.len() > line_ending + 1 && is_wsp(input[line_ending]) {
             let (input, _) = take_sws(input)?;
             tmp_input = input;
             continue;
@@ -41,12 +41,12 @@ impl SipHeaderParser for Via {
         let (input, _) = take_lws(input)?;
         let (input, (host, port)) = HostPort::take_hostport(input)?;
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::ProtocolName, protocol_name);
-        tags.insert(HeaderTagType::ProtocolVersion, protocol_version);
-        tags.insert(HeaderTagType::ProtocolTransport, protocol_transport);
-        tags.insert(HeaderTagType::Host, host);
+        tags.insert(HeaderTagType::ProtocolName, protocol_name.into());
+        tags.insert(HeaderTagType::ProtocolVersion, protocol_version.into());
+        tags.insert(HeaderTagType::ProtocolTransport, protocol_transport.into());
+        tags.insert(HeaderTagType::Host, host.into());
         if port != None {
-            tags.insert(HeaderTagType::Port, port.unwrap());
+            tags.insert(HeaderTagType::Port, port.unwrap().into());
         }
 
         let (_, hdr_val) = HeaderValue::new(
@@ -70,16 +70,22 @@ mod test {
                 .unwrap();
         assert_eq!(val.vstr, "SIP/2.0/UDP bobspc.biloxi.com:5060");
         assert_eq!(input, b";received=192.0.2.4\r\n");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::ProtocolName], b"SIP");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::ProtocolVersion], b"2.0");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::ProtocolName],
+            &b"SIP"[..]
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::ProtocolVersion],
+            &b"2.0"[..]
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::ProtocolTransport],
-            b"UDP"
+            &b"UDP"[..]
         );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Host],
-            b"bobspc.biloxi.com"
+            &b"bobspc.biloxi.com"[..]
         );
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Port], b"5060");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Port], &b"5060"[..]);
     }
 }
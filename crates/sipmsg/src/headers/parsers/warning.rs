@@ -22,7 +22,7 @@ impl SipHeaderParser for Warning {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let (input, warn_code) = take_while1(is_digit)(source_input)?;
         if warn_code.len() != 3 {
-            return sip_parse_error!(1, "Invalid warning code");
+            return sip_parse_error!(HeaderValue, "Invalid warning code");
         }
         let (input, _) = space1(input)?;
         let (input, warn_agent) = take_while1(is_token_char)(input)?;
@@ -30,9 +30,9 @@ impl SipHeaderParser for Warning {
         let (input, (_, warn_text, _)) = take_quoted_string(input)?;
 
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::WarnCode, warn_code);
-        tags.insert(HeaderTagType::WarnAgent, warn_agent);
-        tags.insert(HeaderTagType::WarnText, warn_text);
+        tags.insert(HeaderTagType::WarnCode, warn_code.into());
+        tags.insert(HeaderTagType::WarnAgent, warn_agent.into());
+        tags.insert(HeaderTagType::WarnText, warn_text.into());
 
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len()],
@@ -54,11 +54,14 @@ mod test {
             Warning::take_value("370 devnull \"Choose a bigger pipe\"\r\n".as_bytes()).unwrap();
         assert_eq!(val.vstr, "370 devnull \"Choose a bigger pipe\"");
         assert_eq!(input, b"\r\n");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnCode], b"370");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnAgent], b"devnull");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnCode], &b"370"[..]);
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::WarnAgent],
+            &b"devnull"[..]
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::WarnText],
-            b"Choose a bigger pipe"
+            &b"Choose a bigger pipe"[..]
         );
 
         let (input, val) = Warning::take_value(
@@ -70,8 +73,11 @@ mod test {
             val.vstr,
             "307 isi.edu \"Session parameter 'foo' not understood\""
         );
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnCode], b"307");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnAgent], b"isi.edu");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::WarnCode], &b"307"[..]);
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::WarnAgent],
+            &b"isi.edu"[..]
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::WarnText],
             "Session parameter 'foo' not understood".as_bytes()
@@ -6,7 +6,6 @@ use crate::headers::{
     },
     traits::{HeaderValueParserFn, SipHeaderParser},
 };
-use unicase::Ascii;
 
 /// Headers that defined in rfc3261
 #[derive(Copy, Clone, PartialEq, Debug, PartialOrd, Ord, Eq)]
@@ -60,10 +59,10 @@ pub enum SipRFCHeader {
 impl SipRFCHeader {
     /// Supports compact forms and case-insensitive
     pub fn from_str(s: &str) -> Option<SipRFCHeader> {
-        let s = Ascii::new(s);
         macro_rules! match_str {
             ($input_str:expr, $enum_result:expr) => {
-                if s == $input_str {
+                // Length is compared first, most of names are rejected without comparing bytes
+                if s.len() == $input_str.len() && s.eq_ignore_ascii_case($input_str) {
                     return Some($enum_result);
                 }
             };
@@ -126,7 +125,7 @@ impl SipRFCHeader {
         None
     }
 
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             &SipRFCHeader::Accept => "Accept",
             &SipRFCHeader::AcceptEncoding => "Accept-Encoding",
@@ -175,6 +174,33 @@ impl SipRFCHeader {
         }
     }
 
+    /// Header can't be repeated and can't contain comma-separated list of values
+    pub fn is_single_valued(&self) -> bool {
+        matches!(
+            self,
+            SipRFCHeader::CallID
+                | SipRFCHeader::ContentDisposition
+                | SipRFCHeader::ContentLength
+                | SipRFCHeader::ContentType
+                | SipRFCHeader::CSeq
+                | SipRFCHeader::Date
+                | SipRFCHeader::Expires
+                | SipRFCHeader::From
+                | SipRFCHeader::MaxForwards
+                | SipRFCHeader::MimeVersion
+                | SipRFCHeader::MinExpires
+                | SipRFCHeader::Organization
+                | SipRFCHeader::Priority
+                | SipRFCHeader::ReplyTo
+                | SipRFCHeader::RetryAfter
+                | SipRFCHeader::Server
+                | SipRFCHeader::Subject
+                | SipRFCHeader::Timestamp
+                | SipRFCHeader::To
+                | SipRFCHeader::UserAgent
+        )
+    }
+
     pub fn get_parser(&self) -> HeaderValueParserFn {
         match self {
             &SipRFCHeader::Accept => AcceptParser::take_value,
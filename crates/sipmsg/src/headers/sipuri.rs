@@ -1,14 +1,23 @@
 use crate::{
-    common::bnfcore::is_unreserved, common::hostport::HostPort,
-    common::nom_wrappers::from_utf8_nom, common::nom_wrappers::take_while_with_escaped,
-    errorparse::SipParseError, headers::GenericParams, userinfo::UserInfo,
+    common::bnfcore::is_hnv_char,
+    common::escape::{escaped_eq, escaped_hash, percent_decode, percent_encode, UriComponent},
+    common::hostport::HostPort,
+    common::nom_wrappers::from_utf8_nom,
+    common::nom_wrappers::take_while_with_escaped,
+    errorparse::SipParseError,
+    headers::UriParams,
+    userinfo::UserInfo,
 };
-use alloc::collections::btree_map::BTreeMap;
+use alloc::{borrow::Cow, collections::btree_map::BTreeMap};
 use nom::bytes::complete::{take, take_till, take_until};
 
-use core::str;
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    str,
+};
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum RequestUriScheme {
     SIP,
     SIPS,
@@ -16,24 +25,18 @@ pub enum RequestUriScheme {
 
 impl RequestUriScheme {
     pub fn from_bytes(s: &[u8]) -> Result<RequestUriScheme, nom::Err<SipParseError>> {
-        match s {
-            b"sip" => Ok(Self::SIP),
-            b"sips" => Ok(Self::SIPS),
-            _ => sip_parse_error!(101, "Can't parse sipuri scheme"),
+        if s.eq_ignore_ascii_case(b"sip") {
+            Ok(Self::SIP)
+        } else if s.eq_ignore_ascii_case(b"sips") {
+            Ok(Self::SIPS)
+        } else {
+            sip_parse_error!(Uri, "Can't parse sipuri scheme")
         }
     }
 }
 
-/// hnv-unreserved  =  "[" / "]" / "/" / "?" / ":" / "+" / "$"
-#[inline]
-fn is_hnv_unreserved_char(c: u8) -> bool {
-    c == b'[' || c == b']' || c == b'/' || c == b'?' || c == b':' || c == b'+' || c == b'$'
-}
-
-#[inline]
-fn is_hnv_char(c: u8) -> bool {
-    is_unreserved(c) || is_hnv_unreserved_char(c)
-}
+/// Headers of SIP URI, `?name=value&name=value`
+pub type UriHeaders<'a> = BTreeMap<Cow<'a, str>, Cow<'a, str>>;
 
 // header          =  hname "=" hvalue
 // hname           =  1*( hnv-unreserved / unreserved / escaped )
@@ -72,17 +75,20 @@ impl<'a> SipUriHeader<'a> {
         ))
     }
 
-    fn parse(input: &'a [u8]) -> nom::IResult<&[u8], BTreeMap<&'a str, &'a str>, SipParseError> {
+    fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], UriHeaders<'a>, SipParseError<'a>> {
         let (input, c) = take(1usize)(input)?;
         if c[0] != b'?' {
-            return sip_parse_error!(1, "The first character of headers must be '?'");
+            return sip_parse_error!(Uri, "The first character of headers must be '?'");
         }
 
         let mut result = BTreeMap::new();
         let mut inp2 = input;
         loop {
             let (input, sip_uri_header) = SipUriHeader::parse_header(inp2)?;
-            result.insert(sip_uri_header.name, sip_uri_header.value);
+            result.insert(
+                Cow::Borrowed(sip_uri_header.name),
+                Cow::Borrowed(sip_uri_header.value),
+            );
             if input.len() == 0 || input[0] != b'&' {
                 inp2 = input;
                 break;
@@ -93,7 +99,71 @@ impl<'a> SipUriHeader<'a> {
 
         Ok((inp2, result))
     }
-    
+}
+
+/// URI parameters that must match if either URI has them
+const STRICT_PARAMS: [&str; 5] = ["user", "ttl", "method", "maddr", "transport"];
+
+fn find_param<'p>(params: Option<&'p UriParams>, name: &str) -> Option<Option<&'p str>> {
+    params?
+        .iter()
+        .find(|(pname, _)| escaped_eq(pname.as_ref(), name, true))
+        .map(|(_, value)| value.as_deref())
+}
+
+fn is_strict_param(name: &str) -> bool {
+    STRICT_PARAMS
+        .iter()
+        .any(|strict| escaped_eq(name, strict, true))
+}
+
+fn param_values_eq(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => escaped_eq(a, b, true),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Parameters present in both URIs must match, parameters present in one URI are ignored
+/// except `STRICT_PARAMS`
+fn params_equivalent(a: Option<&UriParams>, b: Option<&UriParams>) -> bool {
+    let contains_all = |x: Option<&UriParams>, y: Option<&UriParams>| {
+        x.into_iter().flat_map(|p| p.iter()).all(|(name, value)| {
+            match find_param(y, name.as_ref()) {
+                Some(other) => param_values_eq(value.as_deref(), other),
+                None => !is_strict_param(name.as_ref()),
+            }
+        })
+    };
+    contains_all(a, b) && contains_all(b, a)
+}
+
+/// All headers must be present in both URIs. Names are case-insensitive, values are not.
+fn headers_equivalent(a: Option<&UriHeaders>, b: Option<&UriHeaders>) -> bool {
+    let contains_all = |x: Option<&UriHeaders>, y: Option<&UriHeaders>| {
+        x.into_iter().flatten().all(|(name, value)| {
+            y.into_iter()
+                .flatten()
+                .any(|(n, v)| escaped_eq(name, n, true) && escaped_eq(value, v, false))
+        })
+    };
+    contains_all(a, b) && contains_all(b, a)
+}
+
+fn user_info_equivalent(a: Option<&UserInfo>, b: Option<&UserInfo>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            escaped_eq(&a.value, &b.value, false)
+                && match (&a.password, &b.password) {
+                    (Some(x), Some(y)) => escaped_eq(x, y, false),
+                    (None, None) => true,
+                    _ => false,
+                }
+        }
+        (None, None) => true,
+        _ => false,
+    }
 }
 
 // URI  =  SIP-URI / SIPS-URI
@@ -104,37 +174,169 @@ impl<'a> SipUriHeader<'a> {
 // userinfo         =  ( user / telephone-subscriber ) [ ":" password ] "@"
 // hostport         =  host [ ":" port ]
 /// Its general form, in the case of a SIP URI, is: sip:user:password@host:port;uri-parameters?headers
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct SipUri<'a> {
     pub scheme: RequestUriScheme,
     user_info: Option<UserInfo<'a>>,
     pub hostport: HostPort<'a>,
-    // Temporary use parsing from generic-parameters.rs
-    // TODO make according RFC
-    parameters: Option<GenericParams<'a>>,
-    headers: Option<BTreeMap<&'a str, &'a str>>,
+    parameters: Option<UriParams<'a>>,
+    headers: Option<UriHeaders<'a>>,
 }
 
 impl<'a> SipUri<'a> {
+    /// Creates URI without userinfo, parameters and headers.
+    /// IPv6 address can be passed with or without brackets.
+    /// ```rust
+    /// use sipmsg::{SipRequestUriScheme, SipUri};
+    ///
+    /// let mut uri = SipUri::new(SipRequestUriScheme::SIP, "atlanta.com", Some(5060));
+    /// uri.set_user("alice smith", Some("p@ss"));
+    /// uri.set_param("transport", Some("tcp"));
+    /// uri.set_header("subject", "project x");
+    /// assert_eq!(
+    ///     uri.to_string(),
+    ///     "sip:alice%20smith:p%40ss@atlanta.com:5060;transport=tcp?subject=project%20x"
+    /// );
+    /// assert_eq!(uri.user_info().unwrap().decoded_value(), "alice smith");
+    /// ```
+    pub fn new(scheme: RequestUriScheme, host: &str, port: Option<u16>) -> SipUri<'a> {
+        let host = host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(host);
+        SipUri {
+            scheme,
+            user_info: None,
+            hostport: HostPort {
+                host: Cow::Owned(host.into()),
+                port,
+            },
+            parameters: None,
+            headers: None,
+        }
+    }
+
+    /// Sets userinfo, characters that are not allowed in user and password are escaped
+    pub fn set_user(&mut self, user: &str, password: Option<&str>) {
+        self.user_info = Some(UserInfo {
+            value: Cow::Owned(percent_encode(user, UriComponent::User).into_owned()),
+            password: password
+                .map(|p| Cow::Owned(percent_encode(p, UriComponent::Password).into_owned())),
+        });
+    }
+
+    /// Sets parameter, characters that are not allowed in parameter are escaped
+    pub fn set_param(&mut self, name: &str, value: Option<&str>) {
+        let name = percent_encode(name, UriComponent::Param).into_owned();
+        let value = value.map(|v| Cow::Owned(percent_encode(v, UriComponent::Param).into_owned()));
+        self.parameters
+            .get_or_insert_with(UriParams::new)
+            .insert(Cow::Owned(name), value);
+    }
+
+    /// Sets header, characters that are not allowed in hname and hvalue are escaped
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        let name = percent_encode(name, UriComponent::Header).into_owned();
+        let value = percent_encode(value, UriComponent::Header).into_owned();
+        self.headers
+            .get_or_insert_with(BTreeMap::new)
+            .insert(Cow::Owned(name), Cow::Owned(value));
+    }
+
     pub fn user_info(&self) -> Option<&UserInfo<'a>> {
         self.user_info.as_ref()
     }
 
-    pub fn params(&self) -> Option<&GenericParams<'a>> {
+    pub fn params(&self) -> Option<&UriParams<'a>> {
         self.parameters.as_ref()
     }
 
-    pub fn headers(&self) -> Option<&BTreeMap<&'a str, &'a str>> {
+    pub fn headers(&self) -> Option<&UriHeaders<'a>> {
         self.headers.as_ref()
     }
 
+    /// Headers with decoded names and values, see `percent_decode`
+    pub fn decoded_headers(&self) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+        self.headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| (percent_decode(name), percent_decode(value)))
+    }
+
+    /// URI comparison. Scheme, host and parameters are case-insensitive,
+    /// userinfo is case-sensitive, escaped characters other than reserved are decoded.
+    /// The comparison isn't transitive.
+    /// [rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4)
+    /// ```rust
+    /// use sipmsg::SipUri;
+    ///
+    /// let (_, a) = SipUri::parse(b"sip:%61lice@atlanta.com;transport=TCP").unwrap();
+    /// let (_, b) = SipUri::parse(b"sip:alice@AtLanTa.CoM;Transport=tcp").unwrap();
+    /// assert!(a.equivalent(&b));
+    ///
+    /// let (_, c) = SipUri::parse(b"sip:alice@atlanta.com:5060;transport=tcp").unwrap();
+    /// assert!(!a.equivalent(&c));
+    /// ```
+    pub fn equivalent(&self, other: &SipUri) -> bool {
+        self.scheme == other.scheme
+            && user_info_equivalent(self.user_info(), other.user_info())
+            && self.hostport.equivalent(&other.hostport)
+            && params_equivalent(self.params(), other.params())
+            && headers_equivalent(self.headers(), other.headers())
+    }
+
+    /// Hash that is equal for equivalent URIs.
+    /// Parameters that can be ignored by comparison and headers are not hashed.
+    pub fn hash_equivalent<H: Hasher>(&self, state: &mut H) {
+        self.scheme.hash(state);
+        match &self.user_info {
+            Some(user_info) => {
+                state.write_u8(1);
+                escaped_hash(&user_info.value, false, state);
+                match &user_info.password {
+                    Some(password) => {
+                        state.write_u8(1);
+                        escaped_hash(password, false, state);
+                    }
+                    None => state.write_u8(0),
+                }
+            }
+            None => state.write_u8(0),
+        }
+        self.hostport.hash_equivalent(state);
+        for name in STRICT_PARAMS.iter() {
+            match find_param(self.params(), name) {
+                Some(value) => {
+                    state.write_u8(1);
+                    escaped_hash(value.unwrap_or(""), true, state);
+                }
+                None => state.write_u8(0),
+            }
+        }
+    }
+
+    pub fn into_owned(self) -> SipUri<'static> {
+        SipUri {
+            scheme: self.scheme,
+            user_info: self.user_info.map(|u| u.into_owned()),
+            hostport: self.hostport.into_owned(),
+            parameters: self.parameters.map(|p| p.into_owned()),
+            headers: self.headers.map(|headers| {
+                headers
+                    .into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), Cow::Owned(v.into_owned())))
+                    .collect()
+            }),
+        }
+    }
+
     fn try_parse_params(
         input: &'a [u8],
-    ) -> nom::IResult<&[u8], Option<GenericParams<'a>>, SipParseError> {
+    ) -> nom::IResult<&'a [u8], Option<UriParams<'a>>, SipParseError<'a>> {
         if input[0] != b';' {
             return Ok((input, None));
         }
-        match GenericParams::parse(input) {
+        match UriParams::parse(input) {
             Ok((input, params)) => {
                 return Ok((input, Some(params)));
             }
@@ -146,7 +348,7 @@ impl<'a> SipUri<'a> {
 
     fn try_parse_headers(
         input: &'a [u8],
-    ) -> nom::IResult<&[u8], Option<BTreeMap<&'a str, &'a str>>, SipParseError> {
+    ) -> nom::IResult<&'a [u8], Option<UriHeaders<'a>>, SipParseError<'a>> {
         if input[0] != b'?' {
             return Ok((input, None));
         }
@@ -238,9 +440,50 @@ impl<'a> SipUri<'a> {
     }
 }
 
+impl fmt::Display for SipUri<'_> {
+    /// Parameters and headers are written in alphabetical order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.scheme {
+            RequestUriScheme::SIP => f.write_str("sip:")?,
+            RequestUriScheme::SIPS => f.write_str("sips:")?,
+        }
+        if let Some(user_info) = &self.user_info {
+            f.write_str(&user_info.value)?;
+            if let Some(password) = &user_info.password {
+                write!(f, ":{}", password)?;
+            }
+            f.write_str("@")?;
+        }
+        if self.hostport.host.contains(':') {
+            write!(f, "[{}]", self.hostport.host)?;
+        } else {
+            f.write_str(&self.hostport.host)?;
+        }
+        if let Some(port) = self.hostport.port {
+            write!(f, ":{}", port)?;
+        }
+        if let Some(params) = &self.parameters {
+            for (name, value) in params.iter() {
+                match value {
+                    Some(value) => write!(f, ";{}={}", name, value)?,
+                    None => write!(f, ";{}", name)?,
+                }
+            }
+        }
+        if let Some(headers) = &self.headers {
+            for (i, (name, value)) in headers.iter().enumerate() {
+                let separator = if i == 0 { '?' } else { '&' };
+                write!(f, "{}{}={}", separator, name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::{string::ToString, vec::Vec};
 
     #[test]
     fn test_sip_uri_parse() {
@@ -270,12 +513,15 @@ mod tests {
         assert_eq!(rest.len(), 0);
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
-        assert_eq!(sip_uri.user_info().unwrap().password, Some("secretword"));
+        assert_eq!(
+            sip_uri.user_info().unwrap().password.as_deref(),
+            Some("secretword")
+        );
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
         assert_eq!(sip_uri.hostport.port, None);
         assert_eq!(
-            sip_uri.params().unwrap().get(&"transport"),
-            Some(&Some("tcp"))
+            sip_uri.params().unwrap().get("transport"),
+            Some(Some("tcp"))
         );
 
         let (rest, sip_uri) = SipUri::parse_ext(
@@ -286,10 +532,13 @@ mod tests {
         assert_eq!(rest.len(), 0);
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.user_info().unwrap().value, "+1-212-555-1212");
-        assert_eq!(sip_uri.user_info().unwrap().password, Some("1234"));
+        assert_eq!(
+            sip_uri.user_info().unwrap().password.as_deref(),
+            Some("1234")
+        );
         assert_eq!(sip_uri.hostport.host, "gateway.com");
         assert_eq!(sip_uri.hostport.port, None);
-        assert_eq!(sip_uri.params().unwrap().get(&"user"), Some(&Some("phone")));
+        assert_eq!(sip_uri.params().unwrap().get("user"), Some(Some("phone")));
 
         let (rest, sip_uri) = SipUri::parse_ext("sips:1212@gateway.com".as_bytes(), true).unwrap();
         assert_eq!(rest.len(), 0);
@@ -318,11 +567,8 @@ mod tests {
         )
         .unwrap();
         assert_eq!(rest.len(), 0);
-        assert_eq!(
-            sip_uri.headers().unwrap().get(&"subject"),
-            Some(&"project%20x")
-        );
-        assert_eq!(sip_uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+        assert_eq!(sip_uri.headers().unwrap()["subject"], "project%20x");
+        assert_eq!(sip_uri.headers().unwrap()["priority"], "urgent");
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIPS);
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -333,13 +579,12 @@ mod tests {
         )
         .unwrap();
         assert_eq!(rest.len(), 0);
+        assert_eq!(sip_uri.headers().unwrap()["to"], "alice%40atlanta.com");
+        let decoded: Vec<_> = sip_uri.decoded_headers().collect();
+        assert_eq!(decoded, [("to".into(), "alice@atlanta.com".into())]);
         assert_eq!(
-            sip_uri.headers().unwrap().get(&"to"),
-            Some(&"alice%40atlanta.com")
-        );
-        assert_eq!(
-            sip_uri.params().unwrap().get(&"method"),
-            Some(&Some("REGISTER"))
+            sip_uri.params().unwrap().get("method"),
+            Some(Some("REGISTER"))
         );
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -351,11 +596,8 @@ mod tests {
         )
         .unwrap();
         //   assert_eq!(rest.len(), 0);
-        assert_eq!(
-            sip_uri.headers().unwrap().get(&"subject"),
-            Some(&"project%20x")
-        );
-        assert_eq!(sip_uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+        assert_eq!(sip_uri.headers().unwrap()["subject"], "project%20x");
+        assert_eq!(sip_uri.headers().unwrap()["priority"], "urgent");
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIPS);
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -364,4 +606,16 @@ mod tests {
 
         assert_eq!(rest, b" ;transport=tcp");
     }
+
+    #[test]
+    fn sip_uri_params_order() {
+        let (_, mut sip_uri) =
+            SipUri::parse(b"sip:p1.example.com;transport=tcp;lr;maddr=192.0.2.1;Ttl=1").unwrap();
+        sip_uri.set_param("ttl", Some("2"));
+        sip_uri.set_param("branch", None);
+        assert_eq!(
+            sip_uri.to_string(),
+            "sip:p1.example.com;transport=tcp;lr;maddr=192.0.2.1;Ttl=2;branch"
+        );
+    }
 }
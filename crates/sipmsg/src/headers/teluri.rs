@@ -0,0 +1,288 @@
+use crate::{
+    common::{
+        bnfcore::{is_alphanum, is_digit, is_hexdig, is_paramchar, is_reserved, is_unreserved},
+        errorparse::SipParseError,
+        nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+    },
+    headers::UriParams,
+};
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+use nom::bytes::complete::{tag_no_case, take_while1};
+
+/// visual-separator  =  "-" / "." / "(" / ")"
+#[inline]
+fn is_visual_separator(c: u8) -> bool {
+    c == b'-' || c == b'.' || c == b'(' || c == b')'
+}
+
+/// phonedigit  =  DIGIT / [ visual-separator ]
+#[inline]
+fn is_phonedigit(c: u8) -> bool {
+    is_digit(c) || is_visual_separator(c)
+}
+
+/// phonedigit-hex  =  HEXDIG / "*" / "#" / [ visual-separator ]
+#[inline]
+fn is_phonedigit_hex(c: u8) -> bool {
+    is_hexdig(c) || c == b'*' || c == b'#' || is_visual_separator(c)
+}
+
+/// pname  =  1*( alphanum / "-" )
+#[inline]
+fn is_pname_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'-'
+}
+
+/// uric without ";", it separates parameters
+#[inline]
+fn is_isub_char(c: u8) -> bool {
+    (is_reserved(c) || is_unreserved(c)) && c != b';'
+}
+
+/// global-number-digits  =  "+" *phonedigit DIGIT *phonedigit
+fn is_global_number_digits(s: &str) -> bool {
+    let digits = match s.strip_prefix('+') {
+        Some(digits) => digits,
+        None => return false,
+    };
+    digits.bytes().all(is_phonedigit) && digits.bytes().any(is_digit)
+}
+
+/// local-number-digits  =  *phonedigit-hex (HEXDIG / "*" / "#") *phonedigit-hex
+fn is_local_number_digits(s: &str) -> bool {
+    s.bytes().all(is_phonedigit_hex) && s.bytes().any(|c| !is_visual_separator(c))
+}
+
+// domainname  =  *( domainlabel "." ) toplabel [ "." ]
+// domainlabel =  alphanum / alphanum *( alphanum / "-" ) alphanum
+// toplabel    =  ALPHA / ALPHA *( alphanum / "-" ) alphanum
+fn is_domainname(s: &str) -> bool {
+    let s = s.strip_suffix('.').unwrap_or(s);
+    if s.is_empty() {
+        return false;
+    }
+    let is_label = |label: &str| {
+        let bytes = label.as_bytes();
+        !bytes.is_empty()
+            && is_alphanum(bytes[0])
+            && is_alphanum(bytes[bytes.len() - 1])
+            && bytes.iter().all(|c| is_pname_char(*c))
+    };
+    let mut labels = s.split('.');
+    let toplabel = labels.next_back().unwrap();
+    labels.all(is_label) && is_label(toplabel) && toplabel.as_bytes()[0].is_ascii_alphabetic()
+}
+
+// telephone-uri        = "tel:" telephone-subscriber
+// telephone-subscriber = global-number / local-number
+// global-number        = global-number-digits *par
+// local-number         = local-number-digits *par context *par
+// par                  = parameter / extension / isdn-subaddress
+// isdn-subaddress      = ";isub=" 1*uric
+// extension            = ";ext=" 1*phonedigit
+// context              = ";phone-context=" descriptor
+// descriptor           = domainname / global-number-digits
+/// Telephone URI, number is kept with visual separators as it is in URI.
+/// [rfc3966 section-3](https://tools.ietf.org/html/rfc3966#section-3)
+/// ```rust
+/// use sipmsg::TelUri;
+///
+/// let (_, uri) = TelUri::parse(b"tel:+1-201-555-0123;ext=1234").unwrap();
+/// assert!(uri.is_global());
+/// assert_eq!(uri.number(), "+1-201-555-0123");
+/// assert_eq!(uri.ext(), Some("1234"));
+///
+/// let (_, uri) = TelUri::parse(b"tel:7042;phone-context=example.com").unwrap();
+/// assert!(!uri.is_global());
+/// assert_eq!(uri.phone_context(), Some("example.com"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TelUri<'a> {
+    number: Cow<'a, str>,
+    parameters: UriParams<'a>,
+}
+
+impl<'a> TelUri<'a> {
+    /// Number with visual separators, global number starts with `+`
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.number.starts_with('+')
+    }
+
+    /// Parameters including `phone-context`, `ext` and `isub`
+    pub fn params(&self) -> &UriParams<'a> {
+        &self.parameters
+    }
+
+    pub fn phone_context(&self) -> Option<&str> {
+        self.parameters.get("phone-context").flatten()
+    }
+
+    pub fn ext(&self) -> Option<&str> {
+        self.parameters.get("ext").flatten()
+    }
+
+    pub fn isub(&self) -> Option<&str> {
+        self.parameters.get("isub").flatten()
+    }
+
+    pub fn into_owned(self) -> TelUri<'static> {
+        TelUri {
+            number: Cow::Owned(self.number.into_owned()),
+            parameters: self.parameters.into_owned(),
+        }
+    }
+
+    fn take_param(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], (&'a str, Option<&'a str>), SipParseError<'a>> {
+        let (input, pname) = take_while1(is_pname_char)(input)?;
+        let (_, pname) = from_utf8_nom(pname)?;
+        if input.is_empty() || input[0] != b'=' {
+            return Ok((input, (pname, None)));
+        }
+        let is_value_char: fn(u8) -> bool = if pname.eq_ignore_ascii_case("isub") {
+            is_isub_char
+        } else {
+            is_paramchar
+        };
+        let (input, pvalue) = take_while_with_escaped(&input[1..], is_value_char)?;
+        if pvalue.is_empty() {
+            return sip_parse_error!(Uri, "tel URI parameter value is empty");
+        }
+        let (_, pvalue) = from_utf8_nom(pvalue)?;
+        Ok((input, (pname, Some(pvalue))))
+    }
+
+    fn validate(&self) -> Result<(), nom::Err<SipParseError<'a>>> {
+        if self.is_global() {
+            if !is_global_number_digits(&self.number) {
+                return sip_parse_error!(Uri, "Invalid global number of tel URI");
+            }
+        } else if !is_local_number_digits(&self.number) {
+            return sip_parse_error!(Uri, "Invalid local number of tel URI");
+        }
+        match self.parameters.get("phone-context") {
+            Some(Some(context)) if is_domainname(context) || is_global_number_digits(context) => {}
+            Some(_) => return sip_parse_error!(Uri, "Invalid phone-context of tel URI"),
+            None if !self.is_global() => {
+                return sip_parse_error!(Uri, "Local number of tel URI requires phone-context")
+            }
+            None => {}
+        }
+        match self.parameters.get("ext") {
+            Some(Some(ext)) if ext.bytes().all(is_phonedigit) => {}
+            Some(_) => return sip_parse_error!(Uri, "Invalid ext of tel URI"),
+            None => {}
+        }
+        if let Some(None) = self.parameters.get("isub") {
+            return sip_parse_error!(Uri, "Invalid isub of tel URI");
+        }
+        Ok(())
+    }
+
+    /// `parse_with_parameters` is false for URI of name-addr without angle brackets,
+    /// in that case parameters belong to header
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        let (input, _) = tag_no_case("tel:")(input)?;
+        let (input, number) = take_while1(|c| c == b'+' || is_phonedigit_hex(c))(input)?;
+        let (_, number) = from_utf8_nom(number)?;
+        let mut parameters = UriParams::new();
+        let mut input = input;
+        while parse_with_parameters && !input.is_empty() && input[0] == b';' {
+            let (rest, (name, value)) = TelUri::take_param(&input[1..])?;
+            parameters.insert(Cow::Borrowed(name), value.map(Cow::Borrowed));
+            input = rest;
+        }
+        let uri = TelUri {
+            number: Cow::Borrowed(number),
+            parameters,
+        };
+        uri.validate()?;
+        Ok((input, uri))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        TelUri::parse_ext(input, true)
+    }
+}
+
+impl fmt::Display for TelUri<'_> {
+    /// `isub`, `ext` and `phone-context` are written first,
+    /// other parameters are in alphabetical order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tel:{}", self.number)?;
+        const FIRST: [&str; 3] = ["isub", "ext", "phone-context"];
+        for name in FIRST.iter() {
+            if let Some(Some(value)) = self.parameters.get(name) {
+                write!(f, ";{}={}", name, value)?;
+            }
+        }
+        // rfc3966 section 3 requires lexicographical order of other parameters
+        let mut others: Vec<_> = self
+            .parameters
+            .iter()
+            .filter(|(name, _)| !FIRST.iter().any(|first| name.eq_ignore_ascii_case(first)))
+            .collect();
+        others.sort_by_key(|(name, _)| name.to_ascii_lowercase());
+        for (name, value) in others {
+            match value {
+                Some(value) => write!(f, ";{}={}", name, value)?,
+                None => write!(f, ";{}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn tel_uri_parse() {
+        let (rest, uri) = TelUri::parse(b"tel:+1-201-555-0123").unwrap();
+        assert!(rest.is_empty());
+        assert!(uri.is_global());
+        assert_eq!(uri.number(), "+1-201-555-0123");
+        assert!(uri.params().is_empty());
+
+        let (rest, uri) =
+            TelUri::parse(b"tel:863-1234;phone-context=+1-914-555;isub=1411;ext=22>").unwrap();
+        assert_eq!(rest, b">");
+        assert!(!uri.is_global());
+        assert_eq!(uri.number(), "863-1234");
+        assert_eq!(uri.phone_context(), Some("+1-914-555"));
+        assert_eq!(uri.isub(), Some("1411"));
+        assert_eq!(uri.ext(), Some("22"));
+
+        let (_, uri) = TelUri::parse(b"TEL:*21#;Phone-Context=example.com;foo=bar;baz").unwrap();
+        assert_eq!(uri.number(), "*21#");
+        assert_eq!(uri.phone_context(), Some("example.com"));
+        assert_eq!(uri.params().get("foo"), Some(Some("bar")));
+        assert_eq!(uri.params().get("baz"), Some(None));
+        assert_eq!(
+            uri.to_string(),
+            "tel:*21#;phone-context=example.com;baz;foo=bar"
+        );
+
+        let (rest, uri) = TelUri::parse_ext(b"tel:+358-555-1234567;tag=abc", false).unwrap();
+        assert_eq!(rest, b";tag=abc");
+        assert!(uri.params().is_empty());
+
+        assert!(TelUri::parse(b"tel:+-").is_err());
+        assert!(TelUri::parse(b"tel:7042").is_err());
+        assert!(TelUri::parse(b"tel:7042;phone-context=-bad-").is_err());
+        assert!(TelUri::parse(b"tel:+1234;ext=12a").is_err());
+        assert!(TelUri::parse(b"tel:+1234;isub").is_err());
+        assert!(TelUri::parse(b"sip:+1234").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Typed views of parsed header values.
+//!
+//! Views borrow data of `SipHeader`, nothing is copied.
+use crate::{
+    common::sip_method::SipMethod,
+    headers::{
+        header::{HeaderTagType, HeaderValue, HeaderValueType},
+        GenericParams, SipHeader, Uri,
+    },
+};
+use core::str;
+
+fn tag_str<'a>(value: &'a HeaderValue, tag: HeaderTagType) -> Option<&'a str> {
+    let tags = value.tags()?;
+    str::from_utf8(tags.get(&tag)?).ok()
+}
+
+fn param<'a>(header: &'a SipHeader, name: &'a str) -> Option<&'a str> {
+    header.params()?.get(name)?
+}
+
+/// Parses value of parameter, `Err` if parameter is present but the value is invalid
+fn parse_param<T: str::FromStr>(header: &SipHeader, name: &str) -> Result<Option<T>, ()> {
+    match param(header, name) {
+        Some(value) => value.parse().map(Some).map_err(|_| ()),
+        None => Ok(None),
+    }
+}
+
+/// Via header value.
+/// [rfc3261 section-20.42](https://tools.ietf.org/html/rfc3261#section-20.42)
+/// ```rust
+/// use sipmsg::{SipHeader, SipVia};
+///
+/// let hdr = SipHeader::new_owned(
+///     "Via",
+///     "SIP/2.0/UDP 192.0.2.1:5070;branch=z9hG4bKnashds8;received=192.0.2.2;rport",
+/// )
+/// .unwrap();
+/// let via = SipVia::from_header(&hdr).unwrap();
+/// assert_eq!(via.transport, "UDP");
+/// assert_eq!(via.host, "192.0.2.1");
+/// assert_eq!(via.port, Some(5070));
+/// assert_eq!(via.branch, Some("z9hG4bKnashds8"));
+/// assert_eq!(via.received, Some("192.0.2.2"));
+/// assert_eq!(via.rport, Some(None));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Via<'a> {
+    pub protocol_name: &'a str,
+    pub protocol_version: &'a str,
+    pub transport: &'a str,
+    pub host: &'a str,
+    pub port: Option<u16>,
+    pub branch: Option<&'a str>,
+    pub received: Option<&'a str>,
+    /// `Some(None)` if rport is present without value
+    /// [rfc3581](https://tools.ietf.org/html/rfc3581)
+    pub rport: Option<Option<u16>>,
+    pub maddr: Option<&'a str>,
+    pub ttl: Option<u8>,
+    /// All parameters including the parameters above
+    pub params: Option<&'a GenericParams<'a>>,
+}
+
+impl<'a> Via<'a> {
+    /// Returns `None` if header is not Via or its parameters are invalid
+    pub fn from_header(header: &'a SipHeader) -> Option<Via<'a>> {
+        let value = &header.value;
+        if value.vtype != HeaderValueType::Via {
+            return None;
+        }
+        let port = match tag_str(value, HeaderTagType::Port) {
+            Some(port) => Some(port.parse().ok()?),
+            None => None,
+        };
+        let rport = match header.params().and_then(|p| p.get("rport")) {
+            Some(Some(rport)) => Some(Some(rport.parse().ok()?)),
+            Some(None) => Some(None),
+            None => None,
+        };
+        Some(Via {
+            protocol_name: tag_str(value, HeaderTagType::ProtocolName)?,
+            protocol_version: tag_str(value, HeaderTagType::ProtocolVersion)?,
+            transport: tag_str(value, HeaderTagType::ProtocolTransport)?,
+            host: tag_str(value, HeaderTagType::Host)?,
+            port,
+            branch: param(header, "branch"),
+            received: param(header, "received"),
+            rport,
+            maddr: param(header, "maddr"),
+            ttl: parse_param(header, "ttl").ok()?,
+            params: header.params(),
+        })
+    }
+}
+
+/// CSeq header value.
+/// [rfc3261 section-20.16](https://tools.ietf.org/html/rfc3261#section-20.16)
+#[derive(Debug, PartialEq)]
+pub struct CSeq {
+    pub seq: u32,
+    pub method: SipMethod,
+}
+
+impl CSeq {
+    /// Returns `None` if header is not CSeq, or sequence number is greater than 2**31 - 1
+    pub fn from_header(header: &SipHeader) -> Option<CSeq> {
+        let value = &header.value;
+        if value.vtype != HeaderValueType::CSeq {
+            return None;
+        }
+        let seq: u32 = tag_str(value, HeaderTagType::Number)?.parse().ok()?;
+        if seq > i32::MAX as u32 {
+            return None;
+        }
+        Some(CSeq {
+            seq,
+            method: SipMethod::from_str(tag_str(value, HeaderTagType::Method)?)?,
+        })
+    }
+}
+
+/// Call-ID header value.
+/// [rfc3261 section-20.8](https://tools.ietf.org/html/rfc3261#section-20.8)
+#[derive(Debug, PartialEq)]
+pub struct CallId<'a> {
+    /// Whole value. Ex: `f81d4fae-7dec-11d0-a765-00a0c91e6bf6@foo.bar.com`
+    pub value: &'a str,
+    /// Part before `@`
+    pub id: &'a str,
+    /// Part after `@`
+    pub host: Option<&'a str>,
+}
+
+impl<'a> CallId<'a> {
+    pub fn from_header(header: &'a SipHeader) -> Option<CallId<'a>> {
+        let value = &header.value;
+        if value.vtype != HeaderValueType::CallID {
+            return None;
+        }
+        Some(CallId {
+            value: &value.vstr,
+            id: tag_str(value, HeaderTagType::ID)?,
+            host: tag_str(value, HeaderTagType::Host),
+        })
+    }
+}
+
+/// Value of From, To, Contact, Route or Record-Route header.
+/// [rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)
+/// ```rust
+/// use sipmsg::{SipHeader, SipNameAddr};
+///
+/// let hdr = SipHeader::new_owned("From", "\"Alice\" <sip:alice@atlanta.com>;tag=1928301774").unwrap();
+/// let from = SipNameAddr::from_header(&hdr).unwrap();
+/// assert_eq!(from.display_name, Some("Alice"));
+/// assert_eq!(from.uri.sip_uri().unwrap().hostport.host, "atlanta.com");
+/// assert_eq!(from.tag, Some("1928301774"));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct NameAddr<'a> {
+    pub display_name: Option<&'a str>,
+    pub uri: &'a Uri<'a>,
+    pub tag: Option<&'a str>,
+    /// All header parameters including tag
+    pub params: Option<&'a GenericParams<'a>>,
+}
+
+impl<'a> NameAddr<'a> {
+    /// Returns `None` if header is not name-addr, or value is `*`
+    pub fn from_header(header: &'a SipHeader) -> Option<NameAddr<'a>> {
+        let value = &header.value;
+        if value.vtype != HeaderValueType::NameAddr {
+            return None;
+        }
+        Some(NameAddr {
+            display_name: tag_str(value, HeaderTagType::DisplayName),
+            uri: value.uri()?,
+            tag: param(header, "tag"),
+            params: header.params(),
+        })
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    common::{
+        bnfcore::{is_alpha, is_alphanum, is_reserved, is_unreserved},
+        errorparse::{ErrorKind, SipParseError},
+        nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+    },
+    headers::{sipuri::RequestUriScheme, SipUri, TelUri},
+};
+use alloc::borrow::Cow;
+use core::fmt;
+use nom::bytes::complete::take_while1;
+
+/// scheme  =  ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+#[inline]
+fn is_scheme_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'+' || c == b'-' || c == b'.'
+}
+
+/// uric  =  reserved / unreserved / escaped
+#[inline]
+fn is_uric(c: u8) -> bool {
+    is_reserved(c) || is_unreserved(c)
+}
+
+/// uric without ";" and ",", they can't be in URI of name-addr without angle brackets
+#[inline]
+fn is_uric_no_semicolon_comma(c: u8) -> bool {
+    is_uric(c) && c != b';' && c != b','
+}
+
+/// Takes scheme with following `:`
+fn take_scheme<'a>(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a str, SipParseError<'a>> {
+    if input.is_empty() || !is_alpha(input[0]) {
+        return sip_parse_error!(Uri, "URI scheme must start with ALPHA");
+    }
+    let (rest, scheme) = take_while1(is_scheme_char)(input)?;
+    if rest.is_empty() || rest[0] != b':' {
+        return sip_parse_error!(Uri, "URI scheme must be followed by ':'");
+    }
+    let (_, scheme) = from_utf8_nom(scheme)?;
+    Ok((&rest[1..], scheme))
+}
+
+/// URI of any scheme other than sip, sips and tel. The value after `:` is kept as is.
+/// [rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)
+/// ```rust
+/// use sipmsg::AbsoluteUri;
+///
+/// let (_, uri) = AbsoluteUri::parse(b"urn:service:sos").unwrap();
+/// assert_eq!(uri.scheme, "urn");
+/// assert_eq!(uri.value, "service:sos");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AbsoluteUri<'a> {
+    pub scheme: Cow<'a, str>,
+    /// Part after `:`, escaped as it is in URI
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> AbsoluteUri<'a> {
+    pub fn into_owned(self) -> AbsoluteUri<'static> {
+        AbsoluteUri {
+            scheme: Cow::Owned(self.scheme.into_owned()),
+            value: Cow::Owned(self.value.into_owned()),
+        }
+    }
+
+    /// `parse_with_parameters` is false for URI of name-addr without angle brackets,
+    /// in that case parsing is stopped on `;` and `,`
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        let (input, scheme) = take_scheme(input)?;
+        let is_value_char: fn(u8) -> bool = if parse_with_parameters {
+            is_uric
+        } else {
+            is_uric_no_semicolon_comma
+        };
+        let (input, value) = take_while_with_escaped(input, is_value_char)?;
+        if value.is_empty() {
+            return sip_parse_error!(Uri, "Absolute URI value is empty");
+        }
+        let (_, value) = from_utf8_nom(value)?;
+        Ok((
+            input,
+            AbsoluteUri {
+                scheme: Cow::Borrowed(scheme),
+                value: Cow::Borrowed(value),
+            },
+        ))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        AbsoluteUri::parse_ext(input, true)
+    }
+}
+
+impl fmt::Display for AbsoluteUri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.scheme, self.value)
+    }
+}
+
+/// Request-URI or URI of name-addr
+/// ```rust
+/// use sipmsg::Uri;
+///
+/// let (_, uri) = Uri::parse(b"sip:alice@atlanta.com").unwrap();
+/// assert_eq!(uri.sip_uri().unwrap().hostport.host, "atlanta.com");
+///
+/// let (_, uri) = Uri::parse(b"tel:+1-201-555-0123").unwrap();
+/// assert_eq!(uri.tel_uri().unwrap().number(), "+1-201-555-0123");
+///
+/// let (_, uri) = Uri::parse(b"mailto:alice@atlanta.com").unwrap();
+/// assert_eq!(uri.scheme(), "mailto");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum Uri<'a> {
+    Sip(SipUri<'a>),
+    Tel(TelUri<'a>),
+    Absolute(AbsoluteUri<'a>),
+}
+
+impl<'a> Uri<'a> {
+    pub fn sip_uri(&self) -> Option<&SipUri<'a>> {
+        match self {
+            Uri::Sip(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn tel_uri(&self) -> Option<&TelUri<'a>> {
+        match self {
+            Uri::Tel(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn absolute_uri(&self) -> Option<&AbsoluteUri<'a>> {
+        match self {
+            Uri::Absolute(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    /// Scheme in lower case for sip, sips and tel, as is for other URIs
+    pub fn scheme(&self) -> &str {
+        match self {
+            Uri::Sip(uri) => match uri.scheme {
+                RequestUriScheme::SIP => "sip",
+                RequestUriScheme::SIPS => "sips",
+            },
+            Uri::Tel(_) => "tel",
+            Uri::Absolute(uri) => &uri.scheme,
+        }
+    }
+
+    pub fn into_owned(self) -> Uri<'static> {
+        match self {
+            Uri::Sip(uri) => Uri::Sip(uri.into_owned()),
+            Uri::Tel(uri) => Uri::Tel(uri.into_owned()),
+            Uri::Absolute(uri) => Uri::Absolute(uri.into_owned()),
+        }
+    }
+
+    /// `parse_with_parameters` is false for URI of name-addr without angle brackets,
+    /// in that case parameters belong to header
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        Uri::take(input, parse_with_parameters)
+            .map_err(|e| e.map(|e| e.context(ErrorKind::Uri, input, 0)))
+    }
+
+    fn take(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        let (_, scheme) = take_scheme(input)?;
+        if scheme.eq_ignore_ascii_case("sip") || scheme.eq_ignore_ascii_case("sips") {
+            let (input, uri) = SipUri::parse_ext(input, parse_with_parameters)?;
+            Ok((input, Uri::Sip(uri)))
+        } else if scheme.eq_ignore_ascii_case("tel") {
+            let (input, uri) = TelUri::parse_ext(input, parse_with_parameters)?;
+            Ok((input, Uri::Tel(uri)))
+        } else {
+            let (input, uri) = AbsoluteUri::parse_ext(input, parse_with_parameters)?;
+            Ok((input, Uri::Absolute(uri)))
+        }
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        Uri::parse_ext(input, true)
+    }
+}
+
+impl fmt::Display for Uri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Uri::Sip(uri) => uri.fmt(f),
+            Uri::Tel(uri) => uri.fmt(f),
+            Uri::Absolute(uri) => uri.fmt(f),
+        }
+    }
+}
+
+impl<'a> From<SipUri<'a>> for Uri<'a> {
+    fn from(uri: SipUri<'a>) -> Uri<'a> {
+        Uri::Sip(uri)
+    }
+}
+
+impl<'a> From<TelUri<'a>> for Uri<'a> {
+    fn from(uri: TelUri<'a>) -> Uri<'a> {
+        Uri::Tel(uri)
+    }
+}
+
+impl<'a> From<AbsoluteUri<'a>> for Uri<'a> {
+    fn from(uri: AbsoluteUri<'a>) -> Uri<'a> {
+        Uri::Absolute(uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_parse() {
+        let (rest, uri) = Uri::parse(b"SIP:bob@biloxi.com>").unwrap();
+        assert_eq!(rest, b">");
+        assert_eq!(uri.scheme(), "sip");
+        assert_eq!(uri.sip_uri().unwrap().hostport.host, "biloxi.com");
+
+        let (rest, uri) = Uri::parse_ext(b"tel:+1-201-555-0123;tag=x", false).unwrap();
+        assert_eq!(rest, b";tag=x");
+        assert_eq!(uri.tel_uri().unwrap().number(), "+1-201-555-0123");
+
+        let (rest, uri) = Uri::parse(b"http://www.example.com/a%20b?c=d;e>").unwrap();
+        assert_eq!(rest, b">");
+        let absolute = uri.absolute_uri().unwrap();
+        assert_eq!(absolute.scheme, "http");
+        assert_eq!(absolute.value, "//www.example.com/a%20b?c=d;e");
+
+        let (rest, uri) = Uri::parse_ext(b"urn:service:sos;tag=1, <sip:a@b>", false).unwrap();
+        assert_eq!(rest, b";tag=1, <sip:a@b>");
+        assert_eq!(uri.absolute_uri().unwrap().value, "service:sos");
+
+        assert!(Uri::parse(b"1tel:+1234").is_err());
+        assert!(Uri::parse(b"urn").is_err());
+        assert!(Uri::parse(b"urn:").is_err());
+    }
+}
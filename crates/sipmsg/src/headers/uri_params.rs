@@ -0,0 +1,203 @@
+use crate::common::{
+    bnfcore::is_paramchar,
+    errorparse::SipParseError,
+    escape::percent_decode,
+    nom_wrappers::{from_utf8_nom, take_while_with_escaped},
+    sip_method::SipMethod,
+};
+use alloc::{borrow::Cow, vec::Vec};
+use unicase::Ascii;
+
+type Param<'a> = (Ascii<Cow<'a, str>>, Option<Cow<'a, str>>);
+
+// uri-parameters    =  *( ";" uri-parameter)
+// uri-parameter     =  transport-param / user-param / method-param
+//                      / ttl-param / maddr-param / lr-param / other-param
+// other-param       =  pname [ "=" pvalue ]
+// pname             =  1*paramchar
+// pvalue            =  1*paramchar
+/// Parameters of SIP URI in the order they appear in URI.
+/// Values are kept escaped as they are in URI.
+/// [rfc3261 section-19.1.1](https://tools.ietf.org/html/rfc3261#section-19.1.1)
+/// ```rust
+/// use sipmsg::{SipMethod, SipUri};
+///
+/// let (_, uri) = SipUri::parse(b"sip:alice@atlanta.com;maddr=[2001:db8::1];ttl=15;lr;x=%22y%22").unwrap();
+/// let params = uri.params().unwrap();
+/// assert_eq!(params.maddr(), Some("[2001:db8::1]"));
+/// assert_eq!(params.ttl(), Some(15));
+/// assert!(params.lr());
+/// assert_eq!(params.transport(), None);
+/// assert_eq!(params.get("x"), Some(Some("%22y%22")));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct UriParams<'a> {
+    params: Vec<Param<'a>>,
+}
+
+impl<'a> UriParams<'a> {
+    pub(crate) fn new() -> UriParams<'a> {
+        UriParams { params: Vec::new() }
+    }
+
+    /// Value of parameter with the same name is replaced in place,
+    /// new parameter is appended
+    pub(crate) fn insert(&mut self, name: Cow<'a, str>, value: Option<Cow<'a, str>>) {
+        match self
+            .params
+            .iter_mut()
+            .find(|(pname, _)| pname.eq_ignore_ascii_case(&name))
+        {
+            Some((_, pvalue)) => *pvalue = value,
+            None => self.params.push((Ascii::new(name), value)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Returns `Some(None)` for parameter without value
+    pub fn get(&self, key: &str) -> Option<Option<&str>> {
+        self.params
+            .iter()
+            .find(|(pname, _)| pname.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_deref())
+    }
+
+    /// Value with decoded escapes, see `percent_decode`
+    pub fn get_decoded(&self, key: &str) -> Option<Option<Cow<'_, str>>> {
+        Some(self.get(key)?.map(percent_decode))
+    }
+
+    /// Names in URI order
+    pub fn keys(&self) -> impl Iterator<Item = &Ascii<Cow<'a, str>>> + '_ {
+        self.params.iter().map(|(name, _)| name)
+    }
+
+    /// Parameters in URI order
+    pub fn iter(&self) -> impl Iterator<Item = (&Ascii<Cow<'a, str>>, &Option<Cow<'a, str>>)> + '_ {
+        self.params.iter().map(|(name, value)| (name, value))
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn value(&self, key: &'static str) -> Option<&str> {
+        self.get(key).flatten()
+    }
+
+    /// `transport=` ( "udp" / "tcp" / "sctp" / "tls" / other-transport)
+    pub fn transport(&self) -> Option<&str> {
+        self.value("transport")
+    }
+
+    /// `user=` ( "phone" / "ip" / other-user)
+    pub fn user(&self) -> Option<&str> {
+        self.value("user")
+    }
+
+    /// `method=` Method
+    pub fn method(&self) -> Option<SipMethod> {
+        SipMethod::from_str(self.value("method")?)
+    }
+
+    /// `ttl=` 1*3DIGIT, `None` if value is not in 0..=255
+    pub fn ttl(&self) -> Option<u8> {
+        self.value("ttl")?.parse().ok()
+    }
+
+    /// `maddr=` host
+    pub fn maddr(&self) -> Option<&str> {
+        self.value("maddr")
+    }
+
+    /// `lr` parameter is present
+    pub fn lr(&self) -> bool {
+        self.contains("lr")
+    }
+
+    pub fn into_owned(self) -> UriParams<'static> {
+        UriParams {
+            params: self
+                .params
+                .into_iter()
+                .map(|(k, v)| {
+                    (
+                        Ascii::new(Cow::Owned(k.into_inner().into_owned())),
+                        v.map(|v| Cow::Owned(v.into_owned())),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn take_param(
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], (&'a str, Option<&'a str>), SipParseError<'a>> {
+        let (input, pname) = take_while_with_escaped(input, is_paramchar)?;
+        if pname.is_empty() {
+            return sip_parse_error!(Uri, "URI parameter name is empty");
+        }
+        let (_, pname) = from_utf8_nom(pname)?;
+        if input.is_empty() || input[0] != b'=' {
+            return Ok((input, (pname, None)));
+        }
+        let (input, pvalue) = take_while_with_escaped(&input[1..], is_paramchar)?;
+        if pvalue.is_empty() {
+            return sip_parse_error!(Uri, "URI parameter value is empty");
+        }
+        let (_, pvalue) = from_utf8_nom(pvalue)?;
+        Ok((input, (pname, Some(pvalue))))
+    }
+
+    /// Parses parameters that start with `;`
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], UriParams<'a>, SipParseError<'a>> {
+        let mut params = UriParams::new();
+        let mut input = input;
+        while !input.is_empty() && input[0] == b';' {
+            let (rest, (name, value)) = UriParams::take_param(&input[1..])?;
+            params.insert(Cow::Borrowed(name), value.map(Cow::Borrowed));
+            input = rest;
+        }
+        Ok((input, params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_params_parse() {
+        let (input, params) = UriParams::parse(
+            b";transport=TCP;User=phone;method=INVITE;lr;maddr=239.255.255.1;ttl=256;a%20b=%5B1%5D>",
+        )
+        .unwrap();
+        assert_eq!(input, b">");
+        assert_eq!(params.transport(), Some("TCP"));
+        assert_eq!(params.user(), Some("phone"));
+        assert_eq!(params.method(), Some(SipMethod::INVITE));
+        assert!(params.lr());
+        assert_eq!(params.maddr(), Some("239.255.255.1"));
+        assert_eq!(params.ttl(), None);
+        assert_eq!(params.get("ttl"), Some(Some("256")));
+        assert_eq!(params.get("a%20b"), Some(Some("%5B1%5D")));
+        assert_eq!(params.get_decoded("a%20b"), Some(Some("[1]".into())));
+        assert_eq!(params.get_decoded("lr"), Some(None));
+        let keys: Vec<&str> = params.keys().map(|k| k.as_ref()).collect();
+        assert_eq!(
+            keys,
+            ["transport", "User", "method", "lr", "maddr", "ttl", "a%20b"]
+        );
+
+        let (input, params) = UriParams::parse(b";lr?to=bob").unwrap();
+        assert_eq!(input, b"?to=bob");
+        assert_eq!(params.get("lr"), Some(None));
+        assert!(!params.contains("transport"));
+
+        assert!(UriParams::parse(b";=value").is_err());
+        assert!(UriParams::parse(b";name=").is_err());
+    }
+}
@@ -26,49 +26,49 @@
 //! CSeq: 986759 INVITE\r\n\r\nbody_stuff"
 //! .as_bytes();
 //!
-//! // First parameter not realized yet.
-//! // It should consist be residue if Content-Length is less then actual body length.
+//! // First parameter is the rest of input after the body.
+//! // The body is framed by Content-Length, if it is absent the body is the whole rest of input.
 //! let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
 //! let request = sip_msg.request().unwrap();
 //! assert_eq!(request.rl.method, SipMethod::INVITE);
 //! assert_eq!(request.rl.sip_version, SipVersion(2, 0));
 //!
 //! // RURI
-//! assert_eq!(request.rl.uri.scheme, SipRequestUriScheme::SIP);
-//! assert_eq!(request.rl.uri.user_info().unwrap().value, "bob");
-//! assert_eq!(request.rl.uri.hostport.host, "biloxi.com");
-//! assert_eq!(request.rl.uri.params().unwrap().get(&"user"), Some(&Some("phone")));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"to"), Some(&"alice%40atlanta.com"));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().scheme, SipRequestUriScheme::SIP);
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().user_info().unwrap().value, "bob");
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().hostport.host, "biloxi.com");
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().params().unwrap().get(&"user"), Some(Some("phone")));
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().headers().unwrap()["to"], "alice%40atlanta.com");
+//! assert_eq!(request.rl.uri.sip_uri().unwrap().headers().unwrap()["priority"], "urgent");
 //!
 //! let call_id_header = request.headers.get_rfc_s(SipRFCHeader::CallID).unwrap();
 //! assert_eq!(call_id_header.value.vstr, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6@foo.bar.com");
 //! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::ID],
 //!           "f81d4fae-7dec-11d0-a765-00a0c91e6bf6".as_bytes());
-//! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::Host], b"foo.bar.com");
+//! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::Host], &b"foo.bar.com"[..]);
 //!
 //! // Via Header
 //! let via_headers = request.headers.get_rfc(SipRFCHeader::Via).unwrap();
 //! assert_eq!(via_headers[0].value.vstr, "SIP/2.0/UDP pc33.atlanta.com");
 //! assert_eq!(
 //!     via_headers[0].params().unwrap().get(&"branch"),
-//!     Some(&Some("z9hG4bKkjshdyff"))
+//!     Some(Some("z9hG4bKkjshdyff"))
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-//!     b"SIP"
+//!     &b"SIP"[..]
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-//!     b"2.0"
+//!     &b"2.0"[..]
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-//!     b"UDP"
+//!     &b"UDP"[..]
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-//!     b"pc33.atlanta.com"
+//!     &b"pc33.atlanta.com"[..]
 //! );
 //! assert_eq!(via_headers[1].value.vstr, "SIP/2.0/UDP 192.168.1.111");
 //! assert_eq!(
@@ -80,7 +80,7 @@
 //! let contact_header = request.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
 //! assert_eq!(
 //!            contact_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-//!            b"Caller"
+//!            &b"Caller"[..]
 //! );
 //! assert_eq!(
 //!            contact_header.value.sip_uri().unwrap().user_info().unwrap().value,
@@ -93,7 +93,7 @@
 //!
 //! assert_eq!(
 //!    contact_header.value.sip_uri().unwrap().params().unwrap().get(&"transport"),
-//!    Some(&Some("tcp"))
+//!    Some(Some("tcp"))
 //! );
 //! assert_eq!(
 //!    contact_header.value.sip_uri().unwrap().params().unwrap().get(&"non-exists-param"),
@@ -106,17 +106,26 @@
 //! assert_eq!(extention_header.value.vstr, "extention header value;param=123;without_value");
 //!
 //! // Body
-//! assert_eq!(request.body.unwrap(), b"body_stuff");
+//! assert_eq!(request.body.as_deref().unwrap(), b"body_stuff");
 //! ```
 //!
 extern crate alloc;
 extern crate nom;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 pub mod common;
+pub use common::date_time::DateTime as SipDateTime;
 pub use common::errorparse;
+pub use common::errorparse::ErrorKind as SipParseErrorKind;
+pub use common::escape::UriComponent as SipUriComponent;
+pub use common::escape::{percent_decode, percent_decode_bytes, percent_encode};
 pub use common::sip_method::SipMethod;
 
+mod parse_options;
+pub use parse_options::ParseOptions as SipParseOptions;
+
 mod message;
 pub use message::get_message_type as get_sip_message_type;
 pub use message::MessageType as SipMessageType;
@@ -130,6 +139,7 @@ pub use request::Request as SipRequest;
 pub use request::RequestLine as SipRequestLine;
 
 mod response;
+pub use response::OtherStatusCode as SipResponseOtherStatusCode;
 pub use response::Response as SipResponse;
 pub use response::StatusCode as SipResponseStatusCode;
 pub use response::StatusLine as SipResponseStatusLine;
@@ -139,5 +149,28 @@ pub use headers::sipuri::RequestUriScheme as SipRequestUriScheme;
 pub use headers::*;
 
 mod serializer;
+pub use serializer::FmtSink as SipFmtSink;
+pub use serializer::SerializeError as SipSerializeError;
+pub use serializer::SerializeSink as SipSerializeSink;
+pub use serializer::Serializer as SipSerializer;
+pub use serializer::SliceSink as SipSliceSink;
+
+mod decoder;
+pub use decoder::StreamDecoder as SipStreamDecoder;
+pub use decoder::StreamItem as SipStreamItem;
+
+mod builder;
+pub use builder::BuildError as SipBuildError;
+pub use builder::RequestBuilder as SipRequestBuilder;
+pub use builder::ResponseBuilder as SipResponseBuilder;
+
+mod digest;
+pub use digest::Algorithm as SipDigestAlgorithm;
+pub use digest::Challenge as SipDigestChallenge;
+pub use digest::Credentials as SipDigestCredentials;
+pub use digest::Qop as SipDigestQop;
+
+mod validate;
+pub use validate::Violation as SipViolation;
 
 pub use unicase::Ascii as SipAscii;
@@ -1,12 +1,23 @@
-use crate::common::errorparse::SipParseError;
-use crate::{SipRequest, SipResponse};
-use nom;
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{locate, ErrorKind, SipParseError},
+};
+use crate::{SipHeaders, SipParseOptions, SipRFCHeader, SipRequest, SipResponse};
+use core::str;
+use nom::{
+    bytes::complete::{tag, tag_no_case},
+    character::complete,
+    sequence::tuple,
+};
 
 /// SIP-Version
 /// ex. `SIP/2.0 -> SipVersion(2, 0)`
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct SipVersion(pub u8, pub u8);
 
+/// Parsed message borrows the input buffer.
+/// `into_owned` converts it to `SipMessage<'static>` that can be stored or sent to another thread.
+#[derive(Clone)]
 pub enum SipMessage<'a> {
     Request(SipRequest<'a>),
     Response(SipResponse<'a>),
@@ -29,17 +40,150 @@ impl<'a> SipMessage<'a> {
         }
     }
 
+    /// Copies all borrowed data, so message doesn't depend on the lifetime of input buffer
+    /// ```rust
+    /// use sipmsg::SipMessage;
+    ///
+    /// let owned: SipMessage<'static> = {
+    ///     let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nCall-ID: a84b4c76e66710\r\n\r\nbody".to_vec();
+    ///     let (_, msg) = SipMessage::parse(&buf).unwrap();
+    ///     msg.into_owned()
+    /// };
+    /// assert_eq!(owned.request().unwrap().rl.uri.sip_uri().unwrap().hostport.host, "biloxi.com");
+    /// assert_eq!(owned.request().unwrap().body.as_deref(), Some(&b"body"[..]));
+    /// ```
+    pub fn into_owned(self) -> SipMessage<'static> {
+        match self {
+            SipMessage::Request(r) => SipMessage::Request(r.into_owned()),
+            SipMessage::Response(r) => SipMessage::Response(r.into_owned()),
+        }
+    }
+
     pub fn parse(raw_message: &'a [u8]) -> nom::IResult<&[u8], SipMessage<'a>, SipParseError> {
+        SipMessage::parse_ext(raw_message, &SipParseOptions::default())
+    }
+
+    /// Parses message with tolerances and limits of `options`.
+    /// Input is complete, e.g. datagram, so body shorter than Content-Length is an error.
+    pub fn parse_ext(
+        raw_message: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], SipMessage<'a>, SipParseError<'a>> {
+        SipMessage::parse_framed(raw_message, options, false)
+    }
+
+    /// Parses message from stream buffer, returns `nom::Err::Incomplete`
+    /// if body is shorter than Content-Length
+    pub(crate) fn parse_stream(
+        raw_message: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], SipMessage<'a>, SipParseError<'a>> {
+        SipMessage::parse_framed(raw_message, options, true)
+    }
+
+    fn parse_framed(
+        raw_message: &'a [u8],
+        options: &SipParseOptions,
+        stream: bool,
+    ) -> nom::IResult<&'a [u8], SipMessage<'a>, SipParseError<'a>> {
         match get_message_type(raw_message) {
             MessageType::Request => {
-                let (inp, request) = SipRequest::parse(raw_message)?;
-                return Ok((inp, SipMessage::Request(request)));
+                let result = SipRequest::take(raw_message, options, stream);
+                let (inp, request) = locate(raw_message, result)?;
+                Ok((inp, SipMessage::Request(request)))
             }
             MessageType::Response => {
-                let (inp, response) = SipResponse::parse(raw_message)?;
-                return Ok((inp, SipMessage::Response(response)));
+                let result = SipResponse::take(raw_message, options, stream);
+                let (inp, response) = locate(raw_message, result)?;
+                Ok((inp, SipMessage::Response(response)))
+            }
+            MessageType::Unknown => locate(
+                raw_message,
+                sip_parse_error!(
+                    UnknownMessage,
+                    "Message is invalid. Can't predict type of message"
+                ),
+            ),
+        }
+    }
+}
+
+/// Splits input that follows the empty line into the message body and the rest of buffer.
+/// The length of body is taken from Content-Length (or compact form `l`).
+/// If Content-Length is absent the body is the whole rest of input
+/// (datagram transports, see [rfc3261 section-18.3](https://tools.ietf.org/html/rfc3261#section-18.3)).
+/// If Content-Length is greater than the available bytes, returns `nom::Err::Incomplete`
+/// for `stream` input and `TruncatedBody` error at the start of body for complete input.
+pub(crate) fn take_body<'a>(
+    input: &'a [u8],
+    headers: &SipHeaders<'a>,
+    options: &SipParseOptions,
+    stream: bool,
+) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+    take_content_length(input, headers, options, stream).map_err(|e| {
+        e.map(|e| {
+            e.context(ErrorKind::ContentLength, input, input.len())
+                .header("Content-Length")
+        })
+    })
+}
+
+fn take_content_length<'a>(
+    input: &'a [u8],
+    headers: &SipHeaders<'a>,
+    options: &SipParseOptions,
+    stream: bool,
+) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+    let content_length = match headers.get_rfc(SipRFCHeader::ContentLength) {
+        Some(hdrs) => {
+            let same_values = hdrs.iter().all(|h| h.value.vstr == hdrs[0].value.vstr);
+            if hdrs.len() != 1 && !(options.allow_duplicate_headers && same_values) {
+                return sip_parse_error!(
+                    ContentLength,
+                    "Content-Length header must be present only one time"
+                );
+            }
+            match str::parse::<usize>(&hdrs[0].value.vstr) {
+                Ok(len) => len,
+                Err(_) => return sip_parse_error!(ContentLength, "Invalid Content-Length value"),
             }
-            MessageType::Unknown => sip_parse_error!(1, "Message is invalid. Can't predict type of message"),
+        }
+        None => input.len(),
+    };
+
+    if content_length > input.len() {
+        if !stream {
+            let e = SipParseError::new(
+                ErrorKind::TruncatedBody,
+                Some("Body is shorter than Content-Length"),
+            );
+            return Err(nom::Err::Error(e.at(input)));
+        }
+        return Err(nom::Err::Incomplete(nom::Needed::new(
+            content_length - input.len(),
+        )));
+    }
+    Ok((&input[content_length..], &input[..content_length]))
+}
+
+/// SIP-Version = "SIP" "/" 1*DIGIT "." 1*DIGIT
+pub(crate) fn take_sip_version<'a>(
+    input: &'a [u8],
+    options: &SipParseOptions,
+) -> nom::IResult<&'a [u8], SipVersion, SipParseError<'a>> {
+    let (input, _) = if options.allow_lowercase_version {
+        tag_no_case("SIP/")(input)?
+    } else {
+        tag("SIP/")(input)?
+    };
+    let (rest, (major, _, minor)) =
+        tuple((complete::digit1, complete::char('.'), complete::digit1))(input)?;
+    let parse_digits = |digits: &[u8]| str::from_utf8(digits).ok()?.parse::<u8>().ok();
+    match (parse_digits(major), parse_digits(minor)) {
+        (Some(major), Some(minor)) => Ok((rest, SipVersion(major, minor))),
+        _ => {
+            let e = SipParseError::new(ErrorKind::Syntax, Some("Invalid SIP version"));
+            Err(nom::Err::Error(e.at(input)))
         }
     }
 }
@@ -51,25 +195,8 @@ pub enum MessageType {
     Unknown,
 }
 
-const SIP: &'static [u8] = &['S' as u8, 'I' as u8, 'P' as u8]; // SIP
-                                                               // First 3 letters of Request Method
-const ACK: &'static [u8] = &['A' as u8, 'C' as u8, 'K' as u8]; // ACK
-const BYE: &'static [u8] = &['B' as u8, 'Y' as u8, 'E' as u8]; // BYE
-const REG: &'static [u8] = &['R' as u8, 'E' as u8, 'G' as u8]; // REGISTER
-const CAN: &'static [u8] = &['C' as u8, 'A' as u8, 'N' as u8]; // CANCEL
-const INF: &'static [u8] = &['I' as u8, 'N' as u8, 'F' as u8]; // INFO
-const INV: &'static [u8] = &['I' as u8, 'N' as u8, 'V' as u8]; // INVITE
-const MES: &'static [u8] = &['M' as u8, 'E' as u8, 'S' as u8]; // MESSAGE
-const NOT: &'static [u8] = &['N' as u8, 'O' as u8, 'T' as u8]; // NOTIFY
-const OPT: &'static [u8] = &['O' as u8, 'P' as u8, 'T' as u8]; // OPTIONS
-const PRA: &'static [u8] = &['P' as u8, 'R' as u8, 'A' as u8]; // PRACK
-const PUB: &'static [u8] = &['P' as u8, 'U' as u8, 'B' as u8]; // PUBLISH
-const REF: &'static [u8] = &['R' as u8, 'E' as u8, 'F' as u8]; // REFER
-const SUB: &'static [u8] = &['S' as u8, 'U' as u8, 'B' as u8]; // SUBSCRIBE
-const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
-
 /// Fast determinates message type and minimal validate for further transmission to suitable parser.
-/// Does not validate full first line, just first 3 bytes.
+/// Does not validate full first line, just the method (or `SIP/` of status line) and the following byte.
 /// ```rust
 /// assert_eq!(
 ///     sipmsg::get_sip_message_type(
@@ -79,26 +206,15 @@ const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
 /// );
 /// ```
 pub fn get_message_type(mt: &[u8]) -> MessageType {
-    if mt.len() < 3 {
-        MessageType::Unknown
-    } else {
-        match &mt[0..3] {
-            SIP => MessageType::Response,
-            ACK => MessageType::Request,
-            BYE => MessageType::Request,
-            REG => MessageType::Request,
-            CAN => MessageType::Request,
-            INF => MessageType::Request,
-            INV => MessageType::Request,
-            MES => MessageType::Request,
-            NOT => MessageType::Request,
-            OPT => MessageType::Request,
-            PRA => MessageType::Request,
-            PUB => MessageType::Request,
-            REF => MessageType::Request,
-            SUB => MessageType::Request,
-            UPD => MessageType::Request,
-            _ => MessageType::Unknown,
+    // Status-Line  =  SIP-Version SP Status-Code SP Reason-Phrase CRLF
+    // Request-Line =  Method SP Request-URI SP SIP-Version CRLF
+    // Method       =  token
+    let token_len = mt.iter().take_while(|c| is_token_char(**c)).count();
+    match (&mt[..token_len], mt.get(token_len)) {
+        (version, Some(b'/')) | (version, None) if version.eq_ignore_ascii_case(b"SIP") => {
+            MessageType::Response
         }
+        (method, Some(b' ')) if !method.is_empty() => MessageType::Request,
+        _ => MessageType::Unknown,
     }
 }
@@ -0,0 +1,189 @@
+use crate::common::{
+    bnfcore::{is_cr, is_crlf, is_lf, is_wsp},
+    errorparse::{ErrorKind, SipParseError},
+};
+
+/// Tolerances and limits of message parser.
+///
+/// `ParseOptions::strict()` follows the grammar of rfc3261.
+/// `ParseOptions::default()` is used by `parse` methods, it is `strict()` that
+/// accepts repeated single-valued headers as the parser always did.
+/// `ParseOptions::lenient()` accepts common mistakes of broken endpoints.
+/// Limits are disabled in both and should be set when input is not trusted.
+/// ```rust
+/// use sipmsg::{SipMessage, SipParseOptions};
+///
+/// let msg = b"SIP/2.0 200 OK\r\nCall-ID: a84b4c76e66710\r\nCSeq: 1 INVITE\r\nCSeq: 1 INVITE\r\n\r\n";
+/// assert!(SipMessage::parse(msg).is_ok());
+/// assert!(SipMessage::parse_ext(msg, &SipParseOptions::strict()).is_err());
+///
+/// let msg = b"sip/2.0 200 OK \nCall-ID: a84b4c76e66710\nCSeq: 1 INVITE\nCSeq: 1 INVITE\n\n";
+/// assert!(SipMessage::parse(msg).is_err());
+///
+/// let options = SipParseOptions {
+///     max_headers: Some(64),
+///     max_line_length: Some(1024),
+///     ..SipParseOptions::lenient()
+/// };
+/// let (_, msg) = SipMessage::parse_ext(msg, &options).unwrap();
+/// assert_eq!(msg.response().unwrap().sl.reason_phrase, "OK");
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ParseOptions {
+    /// Accept LF without CR as line ending
+    pub allow_bare_lf: bool,
+    /// Accept whitespace at the end of request line and status line.
+    /// Trailing whitespace is removed from reason phrase and reason phrase may be absent.
+    pub allow_trailing_whitespace: bool,
+    /// Accept SIP-Version in any case, e.g. `sip/2.0`
+    pub allow_lowercase_version: bool,
+    /// Accept repeated headers that have only one value (From, To, CSeq, etc.),
+    /// accessors return the first one. Repeated Content-Length must have the same value.
+    pub allow_duplicate_headers: bool,
+    /// Accept invalid UTF-8 in header values (e.g. in display names) and reason phrase.
+    /// Invalid sequences are replaced with U+FFFD in string values, raw values are kept as is.
+    pub allow_invalid_utf8: bool,
+    /// Maximum count of header lines
+    pub max_headers: Option<usize>,
+    /// Maximum length of start line or header line (including folded lines) without line ending
+    pub max_line_length: Option<usize>,
+    /// Only split headers into lines at parse time, values are parsed on the first access.
+    /// See [`SipHeaders`](crate::SipHeaders) for details.
+    pub lazy_headers: bool,
+    /// Skip header lines with invalid values instead of failing, skipped lines are listed by
+    /// [`SipHeaders::invalid_headers`](crate::SipHeaders::invalid_headers).
+    /// Invalid Content-Length is not skipped. With `lazy_headers` values of invalid lines
+    /// are skipped on access and `SipHeaders::parse_all` fails only if this option is not set.
+    pub allow_invalid_headers: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            allow_duplicate_headers: true,
+            ..ParseOptions::strict()
+        }
+    }
+}
+
+impl ParseOptions {
+    pub const fn strict() -> ParseOptions {
+        ParseOptions {
+            allow_bare_lf: false,
+            allow_trailing_whitespace: false,
+            allow_lowercase_version: false,
+            allow_duplicate_headers: false,
+            allow_invalid_utf8: false,
+            max_headers: None,
+            max_line_length: None,
+            lazy_headers: false,
+            allow_invalid_headers: false,
+        }
+    }
+
+    pub const fn lenient() -> ParseOptions {
+        ParseOptions {
+            allow_bare_lf: true,
+            allow_trailing_whitespace: true,
+            allow_lowercase_version: true,
+            allow_duplicate_headers: true,
+            allow_invalid_utf8: true,
+            max_headers: None,
+            max_line_length: None,
+            lazy_headers: false,
+            allow_invalid_headers: false,
+        }
+    }
+
+    /// Returns true if input starts with a line ending
+    pub(crate) fn is_line_ending(&self, input: &[u8]) -> bool {
+        is_crlf(input) || (self.allow_bare_lf && !input.is_empty() && is_lf(input[0]))
+    }
+
+    /// Takes line ending at the beginning of input
+    pub(crate) fn take_line_ending<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+        if is_crlf(input) {
+            Ok((&input[2..], &input[..2]))
+        } else if self.is_line_ending(input) {
+            Ok((&input[1..], &input[..1]))
+        } else {
+            let e = SipParseError::new(ErrorKind::Syntax, Some("Line ending is expected"));
+            Err(nom::Err::Error(e.at(input)))
+        }
+    }
+
+    /// Finds the line that starts `input`, if `folded` is set lines that start
+    /// with whitespace continue the line. Checks line endings and length of line.
+    /// Returns the line without the last line ending and length of input after the line ending.
+    /// If line ending is absent the whole input is the line.
+    pub(crate) fn take_line<'a>(
+        &self,
+        input: &'a [u8],
+        folded: bool,
+    ) -> Result<(&'a [u8], usize), nom::Err<SipParseError<'a>>> {
+        let mut idx = 0;
+        let (line_len, rest_len) = loop {
+            let lf = match input[idx..].iter().position(|c| is_lf(*c)) {
+                Some(pos) => idx + pos,
+                None => break (input.len(), 0),
+            };
+            if !self.allow_bare_lf && (lf == 0 || !is_cr(input[lf - 1])) {
+                let e = SipParseError::new(ErrorKind::Syntax, Some("Bare LF is not allowed"));
+                return Err(nom::Err::Error(e.at(&input[lf..])));
+            }
+            if folded && lf + 1 < input.len() && is_wsp(input[lf + 1]) {
+                idx = lf + 1;
+                continue;
+            }
+            if lf > 0 && is_cr(input[lf - 1]) {
+                break (lf - 1, input.len() - lf - 1);
+            }
+            break (lf, input.len() - lf - 1);
+        };
+        if let Some(max_line_length) = self.max_line_length {
+            if line_len > max_line_length {
+                let e = SipParseError::new(ErrorKind::LineTooLong, None);
+                return Err(nom::Err::Error(e.at(&input[max_line_length..])));
+            }
+        }
+        Ok((&input[..line_len], rest_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_line() {
+        let strict = ParseOptions::strict();
+        let lenient = ParseOptions::lenient();
+        assert_eq!(
+            strict.take_line(b"a: b\r\n c\r\nd", true).unwrap(),
+            (&b"a: b\r\n c"[..], 1)
+        );
+        assert_eq!(
+            strict.take_line(b"a: b\r\n c\r\nd", false).unwrap(),
+            (&b"a: b"[..], 5)
+        );
+        assert_eq!(strict.take_line(b"a: b", true).unwrap(), (&b"a: b"[..], 0));
+        assert!(strict.take_line(b"a: b\nd", true).is_err());
+        assert_eq!(
+            lenient.take_line(b"a: b\n c\nd", true).unwrap(),
+            (&b"a: b\n c"[..], 1)
+        );
+
+        let limited = ParseOptions {
+            max_line_length: Some(4),
+            ..strict
+        };
+        assert!(limited.take_line(b"a: b\r\n", true).is_ok());
+        match limited.take_line(b"a: bc\r\n", true) {
+            Err(nom::Err::Error(e)) => assert_eq!(e.kind, ErrorKind::LineTooLong),
+            _ => panic!(),
+        }
+    }
+}
@@ -1,25 +1,33 @@
-use crate::common::{errorparse::SipParseError, sip_method::*};
-use crate::{headers::*, message::*};
-use nom::{
-    bytes::complete::{tag, take_while1},
-    character::{complete, is_alphabetic},
-    sequence::tuple,
+use crate::builder::ResponseBuilder;
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{locate, ErrorKind, SipParseError},
+    sip_method::*,
 };
+use crate::validate::{validate_headers, Violation, MANDATORY_REQUEST_HEADERS};
+use crate::{headers::*, message::*, response::StatusCode, SipParseOptions};
+use nom::{bytes::complete::take_while1, character::complete, sequence::tuple};
 
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 use core::{str, u8};
 
 /// [rfc3261 section-7.1](https://tools.ietf.org/html/rfc3261#section-7.1)
+#[derive(Clone)]
 pub struct Request<'a> {
     /// The request line. Example: `OPTIONS sip:user@example.com SIP/2.0`
     pub rl: RequestLine<'a>,
     /// The request headers.
     pub headers: SipHeaders<'a>,
     /// The body of message
-    pub body: Option<&'a [u8]>,
+    pub body: Option<Cow<'a, [u8]>>,
 }
 
 impl<'a> Request<'a> {
-    fn new(rl: RequestLine<'a>, headers: SipHeaders<'a>, body: Option<&'a [u8]>) -> Request<'a> {
+    pub(crate) fn new(
+        rl: RequestLine<'a>,
+        headers: SipHeaders<'a>,
+        body: Option<Cow<'a, [u8]>>,
+    ) -> Request<'a> {
         Request {
             rl: rl,
             headers: headers,
@@ -27,67 +35,305 @@ impl<'a> Request<'a> {
         }
     }
 
+    /// Parses one request. The body is framed by Content-Length and
+    /// the first value of result is the rest of input after the body.
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Request, SipParseError> {
-        let (input, rl) = RequestLine::parse(buf_input)?;
+        Request::parse_ext(buf_input, &SipParseOptions::default())
+    }
+
+    /// Parses request with tolerances and limits of `options`
+    pub fn parse_ext(
+        buf_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
+        locate(buf_input, Request::take(buf_input, options, false))
+    }
+
+    /// `stream` is true if input may be not complete, see `take_body`
+    pub(crate) fn take(
+        buf_input: &'a [u8],
+        options: &SipParseOptions,
+        stream: bool,
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
+        let (input, rl) = RequestLine::take(buf_input, options)?;
+
+        let (input, mut headers) = SipHeaders::take(input, options)?;
+        headers.locate_invalid_headers(buf_input);
+        let (input, _) = options.take_line_ending(input)?;
+        let (input, body) = take_body(input, &headers, options, stream)?;
+        Ok((input, Request::new(rl, headers, Some(Cow::Borrowed(body)))))
+    }
+
+    /// Copies all borrowed data, so request doesn't depend on the lifetime of input buffer
+    pub fn into_owned(self) -> Request<'static> {
+        Request {
+            rl: self.rl.into_owned(),
+            headers: self.headers.into_owned(),
+            body: self.body.map(|body| Cow::Owned(body.into_owned())),
+        }
+    }
+
+    /// Checks rules of rfc3261 that are not checked by the parser:
+    /// To, From, Call-ID, CSeq and Max-Forwards are present only one time,
+    /// at least one Via is present, CSeq number is less than 2**31 and its method is equal
+    /// to the method of request line, Max-Forwards is in range 0-255
+    /// and Content-Length (if present) is equal to the length of body.
+    /// [rfc3261 section-8.1.1](https://tools.ietf.org/html/rfc3261#section-8.1.1)
+    ///
+    /// Returns empty list if the request is valid.
+    /// ```rust
+    /// use sipmsg::{SipRFCHeader, SipRequest, SipViolation};
+    ///
+    /// let options = "OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+    /// Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+    /// To: Bob <sip:bob@biloxi.com>\r\n\
+    /// From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+    /// Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+    /// CSeq: 314159 INVITE\r\n\
+    /// Content-Length: 0\r\n\r\n";
+    /// let (_, request) = SipRequest::parse(options.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     request.validate(),
+    ///     vec![
+    ///         SipViolation::MissingHeader(SipRFCHeader::MaxForwards),
+    ///         SipViolation::CSeqMethodMismatch,
+    ///     ]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = validate_headers(
+            &self.headers,
+            MANDATORY_REQUEST_HEADERS,
+            self.body.as_deref(),
+        );
+        let cseq_method = self
+            .headers
+            .get_rfc_s(SipRFCHeader::CSeq)
+            .and_then(|cseq| cseq.value.tags())
+            .and_then(|tags| tags.get(&SipHeaderTagType::Method));
+        if let Some(cseq_method) = cseq_method {
+            if cseq_method.as_ref() != self.rl.method.as_str().as_bytes() {
+                violations.push(Violation::CSeqMethodMismatch);
+            }
+        }
+        violations
+    }
+
+    /// Creates response to the request.
+    /// [rfc3261 section-8.2.6](https://tools.ietf.org/html/rfc3261#section-8.2.6)
+    ///
+    /// Via headers (in the same order), From, To, Call-ID and CSeq are copied from request.
+    /// `to_tag` is added to To header if it has no tag and status code is not 100 Trying.
+    /// Timestamp is copied to 100 Trying response.
+    /// Other headers (Contact, Allow, Supported, Warning, ...) can be added to the returned builder.
+    /// ```rust
+    /// use sipmsg::{SipMessage, SipMethod, SipRFCHeader, SipResponseStatusCode};
+    ///
+    /// let invite = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+    /// Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+    /// Max-Forwards: 70\r\n\
+    /// To: Bob <sip:bob@biloxi.com>\r\n\
+    /// From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+    /// Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+    /// CSeq: 314159 INVITE\r\n\
+    /// Content-Length: 0\r\n\r\n";
+    /// let (_, msg) = SipMessage::parse(invite.as_bytes()).unwrap();
+    /// let response = msg
+    ///     .request()
+    ///     .unwrap()
+    ///     .make_response(SipResponseStatusCode::Ringing, None, "a6c85cf")
+    ///     .build()
+    ///     .unwrap();
+    /// let to = response.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+    /// assert_eq!(to.params().unwrap().get("tag"), Some(Some("a6c85cf")));
+    /// assert_eq!(response.sl.reason_phrase, "Ringing");
+    /// ```
+    pub fn make_response(
+        &self,
+        status_code: StatusCode,
+        reason_phrase: Option<&'a str>,
+        to_tag: &str,
+    ) -> ResponseBuilder<'a> {
+        let mut builder = ResponseBuilder::new(status_code);
+        if let Some(reason_phrase) = reason_phrase {
+            builder = builder.reason_phrase(reason_phrase);
+        }
+
+        for hdr in self.headers.iter() {
+            let rfc_header = match SipRFCHeader::from_str(&hdr.name) {
+                Some(rfc_header) => rfc_header,
+                None => continue,
+            };
+            match rfc_header {
+                SipRFCHeader::Via
+                | SipRFCHeader::From
+                | SipRFCHeader::CallID
+                | SipRFCHeader::CSeq => builder = builder.header(hdr.clone()),
+                SipRFCHeader::Timestamp if status_code == StatusCode::Trying => {
+                    builder = builder.header(hdr.clone())
+                }
+                SipRFCHeader::To => {
+                    let has_tag = matches!(hdr.params(), Some(p) if p.contains("tag"));
+                    if has_tag || status_code == StatusCode::Trying {
+                        builder = builder.header(hdr.clone());
+                        continue;
+                    }
+                    let value = format!(
+                        "{};tag={}",
+                        String::from_utf8_lossy(&hdr.raw_value_param),
+                        to_tag
+                    );
+                    builder = builder.rfc_header(SipRFCHeader::To, &value);
+                }
+                _ => {}
+            }
+        }
+        builder
+    }
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
-        Ok((buf_input, Request::new(rl, headers, Some(body))))
+    /// Creates 400 Bad Request response to the request that was parsed with
+    /// `SipParseOptions::allow_invalid_headers`.
+    /// [rfc3261 section-8.2](https://tools.ietf.org/html/rfc3261#section-8.2),
+    /// [rfc3261 section-21.4.1](https://tools.ietf.org/html/rfc3261#section-21.4.1)
+    ///
+    /// Reason phrase names the first invalid header, Warning with code 399 describes
+    /// every invalid header. `warn_agent` is the host name of server (or its pseudonym).
+    /// Headers are copied as by `make_response`, if one of them is invalid
+    /// the builder returns `BuildError::MissingHeader`, such request can't be answered.
+    /// ```rust
+    /// use sipmsg::{SipParseOptions, SipRFCHeader, SipRequest};
+    ///
+    /// let invite = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+    /// Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+    /// Max-Forwards: 70\r\n\
+    /// To: Bob <sip:bob@biloxi.com>\r\n\
+    /// From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+    /// Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+    /// CSeq: 314159 INVITE\r\n\
+    /// Contact: <sip:alice@pc33.atlanta.com\r\n\
+    /// Content-Length: 0\r\n\r\n";
+    /// assert!(SipRequest::parse(invite.as_bytes()).is_err());
+    ///
+    /// let options = SipParseOptions {
+    ///     allow_invalid_headers: true,
+    ///     ..SipParseOptions::default()
+    /// };
+    /// let (_, request) = SipRequest::parse_ext(invite.as_bytes(), &options).unwrap();
+    /// let invalid = &request.headers.invalid_headers()[0];
+    /// assert_eq!(invalid.raw, &b"Contact: <sip:alice@pc33.atlanta.com"[..]);
+    ///
+    /// let response = request.make_bad_request("biloxi.com", "a6c85cf").build().unwrap();
+    /// assert_eq!(response.sl.reason_phrase, "Invalid Contact Header");
+    /// let warning = response.headers.get_rfc_s(SipRFCHeader::Warning).unwrap();
+    /// assert_eq!(
+    ///     warning.value.vstr,
+    ///     "399 biloxi.com \"bad header value of Contact at line 8 (offset 293)\""
+    /// );
+    /// ```
+    pub fn make_bad_request(&self, warn_agent: &str, to_tag: &str) -> ResponseBuilder<'a> {
+        let mut builder = self.make_response(StatusCode::BadRequest, None, to_tag);
+        let invalid_headers = self.headers.invalid_headers();
+        if let Some(invalid) = invalid_headers.first() {
+            builder = match &invalid.name {
+                Some(name) => builder.reason_phrase(format!("Invalid {} Header", name)),
+                None => builder.reason_phrase("Invalid Header"),
+            };
+        }
+        for invalid in invalid_headers {
+            builder = builder.warning(399, warn_agent, &format!("{}", invalid.error));
+        }
+        builder
     }
 }
 
 /// Ex: `INVITE sip:user@example.com SIP/2.0`
 /// The Request line and u8 buffer shoud have the same life time
+#[derive(Clone)]
 pub struct RequestLine<'a> {
     pub method: SipMethod,
-    pub uri: SipUri<'a>,
+    pub uri: Uri<'a>,
     pub sip_version: SipVersion,
     // Byte representation of request line that includes \r\n
-    pub raw: &'a [u8],
+    pub raw: Cow<'a, [u8]>,
 }
 
 impl<'a> RequestLine<'a> {
+    pub fn into_owned(self) -> RequestLine<'static> {
+        RequestLine {
+            method: self.method,
+            uri: self.uri.into_owned(),
+            sip_version: self.sip_version,
+            raw: Cow::Owned(self.raw.into_owned()),
+        }
+    }
+
     fn parse_method(method: &[u8]) -> Option<SipMethod> {
         match str::from_utf8(method) {
             Ok(s) => SipMethod::from_str(s),
             Err(_) => None,
         }
     }
-    pub fn parse(source_input: &[u8]) -> nom::IResult<&[u8], RequestLine, SipParseError> {
-        let method = take_while1(is_alphabetic);
-        let uri = take_while1(|c| c != b' ' as u8);
-        let (input, (method, _, uri, _, _, major_version, _, minor_version, _)) =
-            tuple((
-                method,
-                complete::space1,
-                uri,
-                complete::space1,
-                tag("SIP/"),
-                complete::digit1,
-                complete::char('.'),
-                complete::digit1,
-                complete::crlf,
-            ))(source_input)?;
-
-        let (_, sip_uri) = SipUri::parse(uri)?;
-
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
+    pub fn parse(
+        source_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], RequestLine<'a>, SipParseError<'a>> {
+        RequestLine::parse_ext(source_input, &SipParseOptions::default())
+    }
+
+    /// Parses request line with tolerances and limits of `options`
+    pub fn parse_ext(
+        source_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], RequestLine<'a>, SipParseError<'a>> {
+        locate(source_input, RequestLine::take(source_input, options))
+    }
+
+    fn take(
+        source_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], RequestLine<'a>, SipParseError<'a>> {
+        let (_, line_rest_len) = options
+            .take_line(source_input, false)
+            .map_err(|e| e.map(|e| e.context(ErrorKind::RequestLine, source_input, 0)))?;
+        let line_error = |e: nom::Err<SipParseError<'a>>| {
+            e.map(|e| e.context(ErrorKind::RequestLine, source_input, line_rest_len))
+        };
+        let (uri_input, (method, _)) =
+            tuple((take_while1(is_token_char), complete::space1))(source_input)
+                .map_err(line_error)?;
+        let (input, (uri, _, sip_version)) =
+            tuple((take_while1(|c| c != b' '), complete::space1, |i| {
+                take_sip_version(i, options)
+            }))(uri_input)
+            .map_err(line_error)?;
+        let input = if options.allow_trailing_whitespace {
+            complete::space0(input).map_err(line_error)?.0
+        } else {
+            input
+        };
+        let (input, _) = options.take_line_ending(input).map_err(line_error)?;
+
+        let uri_rest_len = uri_input.len() - uri.len();
+        let (_, uri) = Uri::parse(uri).map_err(|e| {
+            e.map(|e| {
+                e.in_part(uri_rest_len)
+                    .context(ErrorKind::Uri, uri_input, uri_rest_len)
+            })
+        })?;
 
         match RequestLine::parse_method(method) {
             Some(m) => Ok((
                 input,
                 RequestLine {
                     method: m,
-                    uri: sip_uri,
+                    uri,
                     sip_version: sip_version,
-                    raw: &source_input[..source_input.len() - input.len()],
+                    raw: Cow::Borrowed(&source_input[..source_input.len() - input.len()]),
                 },
             )),
-            None => return sip_parse_error!(1, "Error cast from_utf8"),
+            None => Err(line_error(nom::Err::Error(SipParseError::new(
+                ErrorKind::RequestLine,
+                Some("Error cast from_utf8"),
+            )))),
         }
     }
 }
@@ -1,14 +1,22 @@
-use crate::common::{errorparse::SipParseError, nom_wrappers::from_utf8_nom};
+use crate::common::{
+    bnfcore::{is_cr, is_lf, is_wsp},
+    errorparse::{locate, ErrorKind, SipParseError},
+    nom_wrappers::from_utf8_nom,
+};
 use crate::headers::*;
-use crate::message::SipVersion;
+use crate::message::{take_body, take_sip_version, SipVersion};
+use crate::validate::{validate_headers, Violation, MANDATORY_RESPONSE_HEADERS};
+use crate::SipParseOptions;
 
+use alloc::{borrow::Cow, string::String, vec::Vec};
 use core::str;
 use nom::{
-    bytes::complete::{tag, take, take_until},
-    character::complete,
+    bytes::complete::{take_till, take_while_m_n},
+    character::{complete, is_digit},
     sequence::tuple,
 };
 
+#[derive(Clone)]
 pub struct Response<'a> {
     /// Status line. Ex: `SIP/2.0 401 Unauthorized`
     pub sl: StatusLine<'a>,
@@ -16,47 +24,92 @@ pub struct Response<'a> {
     /// The response headers.
     pub headers: SipHeaders<'a>,
     /// Body
-    pub body: Option<&'a [u8]>,
+    pub body: Option<Cow<'a, [u8]>>,
 }
 
 /// Ex: `SIP/2.0 401 Unauthorized`
+#[derive(Clone)]
 pub struct StatusLine<'a> {
     pub sip_version: SipVersion,
     pub status_code: StatusCode,
-    pub reason_phrase: &'a str,
+    pub reason_phrase: Cow<'a, str>,
     // Byte representation of request line that includes \r\n
-    pub raw: &'a [u8],
+    pub raw: Cow<'a, [u8]>,
 }
 
 impl<'a> StatusLine<'a> {
+    pub fn into_owned(self) -> StatusLine<'static> {
+        StatusLine {
+            sip_version: self.sip_version,
+            status_code: self.status_code,
+            reason_phrase: Cow::Owned(self.reason_phrase.into_owned()),
+            raw: Cow::Owned(self.raw.into_owned()),
+        }
+    }
+
     pub fn parse(source_input: &'a [u8]) -> nom::IResult<&[u8], StatusLine<'a>, SipParseError> {
-        let (input, (_, major_version, _, minor_version, _, status_code, _, reason_phrase, _)) =
-            tuple((
-                tag("SIP/"),
-                complete::digit1,
-                complete::char('.'),
-                complete::digit1,
-                complete::space1,
-                take(3usize),
-                complete::space1,
-                take_until("\r\n"),
-                take(2usize), // skip /r/n
-            ))(source_input)?;
-
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
-
-        let status_code = StatusCode::from_bytes_str(status_code);
-        let (_, reason_phrase_str) = from_utf8_nom(reason_phrase)?;
+        StatusLine::parse_ext(source_input, &SipParseOptions::default())
+    }
+
+    /// Parses status line with tolerances and limits of `options`
+    pub fn parse_ext(
+        source_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], StatusLine<'a>, SipParseError<'a>> {
+        locate(source_input, StatusLine::take(source_input, options))
+    }
+
+    fn take(
+        source_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], StatusLine<'a>, SipParseError<'a>> {
+        let (_, line_rest_len) = options
+            .take_line(source_input, false)
+            .map_err(|e| e.map(|e| e.context(ErrorKind::StatusLine, source_input, 0)))?;
+        let line_error = |e: nom::Err<SipParseError<'a>>| {
+            e.map(|e| e.context(ErrorKind::StatusLine, source_input, line_rest_len))
+        };
+        let (input, (sip_version, _, status_code)) = tuple((
+            |i| take_sip_version(i, options),
+            complete::space1,
+            take_while_m_n(3, 3, is_digit),
+        ))(source_input)
+        .map_err(line_error)?;
+        let (input, _) = if options.allow_trailing_whitespace {
+            complete::space0(input).map_err(line_error)?
+        } else {
+            complete::space1(input).map_err(line_error)?
+        };
+        let (input, reason_phrase) =
+            take_till(|c| is_cr(c) || is_lf(c))(input).map_err(line_error)?;
+        let (input, _) = options.take_line_ending(input).map_err(line_error)?;
+
+        let reason_phrase = if options.allow_trailing_whitespace {
+            let len = reason_phrase.len()
+                - reason_phrase
+                    .iter()
+                    .rev()
+                    .take_while(|c| is_wsp(**c))
+                    .count();
+            &reason_phrase[..len]
+        } else {
+            reason_phrase
+        };
+        let reason_phrase = if options.allow_invalid_utf8 {
+            String::from_utf8_lossy(reason_phrase)
+        } else {
+            Cow::Borrowed(from_utf8_nom(reason_phrase).map_err(line_error)?.1)
+        };
+        let status_code = StatusCode::from_bytes_str(status_code).ok_or_else(|| {
+            line_error(nom::Err::Error(SipParseError::new(ErrorKind::Syntax, None)))
+        })?;
         Ok((
             input,
             StatusLine {
                 sip_version: sip_version,
                 status_code: status_code,
-                reason_phrase: reason_phrase_str,
-                raw: &source_input[..source_input.len() - input.len()],
+                reason_phrase,
+                raw: Cow::Borrowed(&source_input[..source_input.len() - input.len()]),
             },
         ))
     }
@@ -64,7 +117,11 @@ impl<'a> StatusLine<'a> {
 
 /// [rfc3261 section-7.2](https://tools.ietf.org/html/rfc3261#section-7.2)
 impl<'a> Response<'a> {
-    fn new(sl: StatusLine<'a>, headers: SipHeaders<'a>, body: Option<&'a [u8]>) -> Response<'a> {
+    pub(crate) fn new(
+        sl: StatusLine<'a>,
+        headers: SipHeaders<'a>,
+        body: Option<Cow<'a, [u8]>>,
+    ) -> Response<'a> {
         Response {
             sl: sl,
             headers: headers,
@@ -72,250 +129,462 @@ impl<'a> Response<'a> {
         }
     }
 
+    /// Parses one response. The body is framed by Content-Length and
+    /// the first value of result is the rest of input after the body.
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Response<'a>, SipParseError> {
-        let (input, rl) = StatusLine::parse(buf_input)?;
+        Response::parse_ext(buf_input, &SipParseOptions::default())
+    }
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
+    /// Parses response with tolerances and limits of `options`
+    pub fn parse_ext(
+        buf_input: &'a [u8],
+        options: &SipParseOptions,
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
+        locate(buf_input, Response::take(buf_input, options, false))
+    }
+
+    /// `stream` is true if input may be not complete, see `take_body`
+    pub(crate) fn take(
+        buf_input: &'a [u8],
+        options: &SipParseOptions,
+        stream: bool,
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
+        let (input, rl) = StatusLine::take(buf_input, options)?;
+
+        let (input, mut headers) = SipHeaders::take(input, options)?;
+        headers.locate_invalid_headers(buf_input);
+        let (input, _) = options.take_line_ending(input)?;
+        let (input, body) = take_body(input, &headers, options, stream)?;
+        Ok((input, Response::new(rl, headers, Some(Cow::Borrowed(body)))))
+    }
+
+    /// Copies all borrowed data, so response doesn't depend on the lifetime of input buffer
+    pub fn into_owned(self) -> Response<'static> {
+        Response {
+            sl: self.sl.into_owned(),
+            headers: self.headers.into_owned(),
+            body: self.body.map(|body| Cow::Owned(body.into_owned())),
+        }
+    }
 
-        Ok((buf_input, Response::new(rl, headers, Some(body))))
+    /// Checks rules of rfc3261 that are not checked by the parser:
+    /// To, From, Call-ID and CSeq are present only one time, at least one Via is present,
+    /// CSeq number is less than 2**31 and Content-Length (if present) is equal to the length of body.
+    /// Returns empty list if the response is valid.
+    pub fn validate(&self) -> Vec<Violation> {
+        validate_headers(
+            &self.headers,
+            MANDATORY_RESPONSE_HEADERS,
+            self.body.as_deref(),
+        )
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+/// Status code of response.
+/// Codes and reason phrases of [IANA registry](https://www.iana.org/assignments/sip-parameters/sip-parameters.xhtml#sip-parameters-7)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum StatusCode {
     // Provisional 1xx
-    Trying = 100,
-    Ringing = 180,
-    CallIsBeingForwarded = 181,
-    Queued = 182,
-    SessionProgress = 183,
+    Trying,
+    Ringing,
+    CallIsBeingForwarded,
+    Queued,
+    SessionProgress,
+    EarlyDialogTerminated,
 
     // Successful 2xx
-    OK = 200,
+    OK,
+    Accepted,
+    NoNotification,
 
     // Redirection 3xx
-    MultipleChoices = 300,
-    MovedPermanently = 301,
-    MovedTemporarily = 302,
-    UseProxy = 305,
-    AlternativeService = 380,
+    MultipleChoices,
+    MovedPermanently,
+    MovedTemporarily,
+    UseProxy,
+    AlternativeService,
 
     // Request Failure 4xx
-    BadRequest = 400,
-    Unauthorized = 401,
-    PaymentRequired = 402,
-    Forbidden = 403,
-    NotFound = 404,
-    MethodNotAllowed = 405,
-    NotAcceptableResourceContent = 406,
-    ProxyAuthenticationRequired = 407,
-    RequestTimeout = 408,
-    Gone = 410,
-    RequestEntityTooLarge = 413,
-    RequestUriTooLong = 414,
-    UnsupportedMediaType = 415,
-    UnsupportedUriScheme = 416,
-    BadExtension = 420,
-    ExtensionRequired = 421,
-    IntervalTooBrief = 423,
-    TemporarilyUnavailable = 480,
-    CallOrTransactionDoesNotExist = 481,
-    LoopDetected = 482,
-    TooManyHops = 483,
-    AddressIncomplete = 484,
-    Ambiguous = 485,
-    BusyHere = 486,
-    RequestTerminated = 487,
-    NotAcceptableHere = 488,
-    RequestPending = 491,
-    Undecipherable = 493,
+    BadRequest,
+    Unauthorized,
+    PaymentRequired,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    NotAcceptableResourceContent,
+    ProxyAuthenticationRequired,
+    RequestTimeout,
+    Gone,
+    ConditionalRequestFailed,
+    RequestEntityTooLarge,
+    RequestUriTooLong,
+    UnsupportedMediaType,
+    UnsupportedUriScheme,
+    UnknownResourcePriority,
+    BadExtension,
+    ExtensionRequired,
+    SessionIntervalTooSmall,
+    IntervalTooBrief,
+    BadLocationInformation,
+    BadAlertMessage,
+    UseIdentityHeader,
+    ProvideReferrerIdentity,
+    FlowFailed,
+    AnonymityDisallowed,
+    BadIdentityInfo,
+    UnsupportedCredential,
+    InvalidIdentityHeader,
+    FirstHopLacksOutboundSupport,
+    MaxBreadthExceeded,
+    BadInfoPackage,
+    ConsentNeeded,
+    TemporarilyUnavailable,
+    CallOrTransactionDoesNotExist,
+    LoopDetected,
+    TooManyHops,
+    AddressIncomplete,
+    Ambiguous,
+    BusyHere,
+    RequestTerminated,
+    NotAcceptableHere,
+    BadEvent,
+    RequestPending,
+    Undecipherable,
+    SecurityAgreementRequired,
 
     // Server Failure 5xx
-    ServerInternalError = 500,
-    NotImplemented = 501,
-    BadGateway = 502,
-    ServiceUnavailable = 503,
-    ServerTimeout = 504,
-    VersionNotSupported = 505,
-    MessageTooLarge = 513,
+    ServerInternalError,
+    NotImplemented,
+    BadGateway,
+    ServiceUnavailable,
+    ServerTimeout,
+    VersionNotSupported,
+    MessageTooLarge,
+    PushNotificationServiceNotSupported,
+    PreconditionFailure,
 
     // Global Failures 6xx
-    BusyEverywhere = 600,
-    Decline = 603,
-    DoesNotExistAnywhere = 604,
-    NotAcceptable = 606,
+    BusyEverywhere,
+    Decline,
+    DoesNotExistAnywhere,
+    NotAcceptable,
+    Unwanted,
+    Rejected,
 
-    // Unknown
-    Unknown = 999,
+    /// Code in range 100-699 that is not in the registry
+    Other(OtherStatusCode),
 }
 
-const BS_100: &'static [u8] = "100".as_bytes();
-const BS_180: &'static [u8] = "180".as_bytes();
-const BS_181: &'static [u8] = "181".as_bytes();
-const BS_182: &'static [u8] = "182".as_bytes();
-const BS_183: &'static [u8] = "183".as_bytes();
-const BS_200: &'static [u8] = "200".as_bytes();
-const BS_300: &'static [u8] = "300".as_bytes();
-const BS_301: &'static [u8] = "301".as_bytes();
-const BS_302: &'static [u8] = "302".as_bytes();
-const BS_305: &'static [u8] = "305".as_bytes();
-const BS_380: &'static [u8] = "380".as_bytes();
-const BS_400: &'static [u8] = "400".as_bytes();
-const BS_401: &'static [u8] = "401".as_bytes();
-const BS_402: &'static [u8] = "402".as_bytes();
-const BS_403: &'static [u8] = "403".as_bytes();
-const BS_404: &'static [u8] = "404".as_bytes();
-const BS_405: &'static [u8] = "405".as_bytes();
-const BS_406: &'static [u8] = "406".as_bytes();
-const BS_407: &'static [u8] = "407".as_bytes();
-const BS_408: &'static [u8] = "408".as_bytes();
-const BS_410: &'static [u8] = "410".as_bytes();
-const BS_413: &'static [u8] = "413".as_bytes();
-const BS_414: &'static [u8] = "414".as_bytes();
-const BS_415: &'static [u8] = "415".as_bytes();
-const BS_416: &'static [u8] = "416".as_bytes();
-const BS_420: &'static [u8] = "420".as_bytes();
-const BS_421: &'static [u8] = "421".as_bytes();
-const BS_423: &'static [u8] = "423".as_bytes();
-const BS_480: &'static [u8] = "480".as_bytes();
-const BS_481: &'static [u8] = "481".as_bytes();
-const BS_482: &'static [u8] = "482".as_bytes();
-const BS_483: &'static [u8] = "483".as_bytes();
-const BS_484: &'static [u8] = "484".as_bytes();
-const BS_485: &'static [u8] = "485".as_bytes();
-const BS_486: &'static [u8] = "486".as_bytes();
-const BS_487: &'static [u8] = "487".as_bytes();
-const BS_488: &'static [u8] = "488".as_bytes();
-const BS_491: &'static [u8] = "491".as_bytes();
-const BS_493: &'static [u8] = "493".as_bytes();
-const BS_500: &'static [u8] = "500".as_bytes();
-const BS_501: &'static [u8] = "501".as_bytes();
-const BS_502: &'static [u8] = "502".as_bytes();
-const BS_503: &'static [u8] = "503".as_bytes();
-const BS_504: &'static [u8] = "504".as_bytes();
-const BS_505: &'static [u8] = "505".as_bytes();
-const BS_513: &'static [u8] = "513".as_bytes();
-const BS_600: &'static [u8] = "600".as_bytes();
-const BS_603: &'static [u8] = "603".as_bytes();
-const BS_604: &'static [u8] = "604".as_bytes();
-const BS_606: &'static [u8] = "606".as_bytes();
+/// Number of status code that is not in the registry.
+/// Created only by `StatusCode::from_code`, so the code is in range 100-699
+/// and it is not equal to code of other variant.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct OtherStatusCode(u16);
+
+impl OtherStatusCode {
+    pub fn code(&self) -> u16 {
+        self.0
+    }
+}
 
 impl StatusCode {
-    pub fn from_str(s: &str) -> StatusCode {
+    /// `None` is returned if `s` is not a number in range 100-699
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<StatusCode> {
         StatusCode::from_bytes_str(s.as_bytes())
     }
 
-    pub fn from_bytes_str(s: &[u8]) -> StatusCode {
-        match s {
-            BS_100 => StatusCode::Trying,
-            BS_180 => StatusCode::Ringing,
-            BS_181 => StatusCode::CallIsBeingForwarded,
-            BS_182 => StatusCode::Queued,
-            BS_183 => StatusCode::SessionProgress,
-            BS_200 => StatusCode::OK,
-            BS_300 => StatusCode::MultipleChoices,
-            BS_301 => StatusCode::MovedPermanently,
-            BS_302 => StatusCode::MovedTemporarily,
-            BS_305 => StatusCode::UseProxy,
-            BS_380 => StatusCode::AlternativeService,
-            BS_400 => StatusCode::BadRequest,
-            BS_401 => StatusCode::Unauthorized,
-            BS_402 => StatusCode::PaymentRequired,
-            BS_403 => StatusCode::Forbidden,
-            BS_404 => StatusCode::NotFound,
-            BS_405 => StatusCode::MethodNotAllowed,
-            BS_406 => StatusCode::NotAcceptableResourceContent,
-            BS_407 => StatusCode::ProxyAuthenticationRequired,
-            BS_408 => StatusCode::RequestTimeout,
-            BS_410 => StatusCode::Gone,
-            BS_413 => StatusCode::RequestEntityTooLarge,
-            BS_414 => StatusCode::RequestUriTooLong,
-            BS_415 => StatusCode::UnsupportedMediaType,
-            BS_416 => StatusCode::UnsupportedUriScheme,
-            BS_420 => StatusCode::BadExtension,
-            BS_421 => StatusCode::ExtensionRequired,
-            BS_423 => StatusCode::IntervalTooBrief,
-            BS_480 => StatusCode::TemporarilyUnavailable,
-            BS_481 => StatusCode::CallOrTransactionDoesNotExist,
-            BS_482 => StatusCode::LoopDetected,
-            BS_483 => StatusCode::TooManyHops,
-            BS_484 => StatusCode::AddressIncomplete,
-            BS_485 => StatusCode::Ambiguous,
-            BS_486 => StatusCode::BusyHere,
-            BS_487 => StatusCode::RequestTerminated,
-            BS_488 => StatusCode::NotAcceptableHere,
-            BS_491 => StatusCode::RequestPending,
-            BS_493 => StatusCode::Undecipherable,
-            BS_500 => StatusCode::ServerInternalError,
-            BS_501 => StatusCode::NotImplemented,
-            BS_502 => StatusCode::BadGateway,
-            BS_503 => StatusCode::ServiceUnavailable,
-            BS_504 => StatusCode::ServerTimeout,
-            BS_505 => StatusCode::VersionNotSupported,
-            BS_513 => StatusCode::MessageTooLarge,
-            BS_600 => StatusCode::BusyEverywhere,
-            BS_603 => StatusCode::Decline,
-            BS_604 => StatusCode::DoesNotExistAnywhere,
-            BS_606 => StatusCode::NotAcceptable,
-            _ => StatusCode::Unknown,
+    /// `None` is returned if `s` is not a number in range 100-699
+    pub fn from_bytes_str(s: &[u8]) -> Option<StatusCode> {
+        let code = str::from_utf8(s).ok()?.parse::<u16>().ok()?;
+        StatusCode::from_code(code)
+    }
+
+    /// `None` is returned if `code` is out of range 100-699
+    /// [rfc3261 section-21](https://tools.ietf.org/html/rfc3261#section-21)
+    pub fn from_code(code: u16) -> Option<StatusCode> {
+        if !(100..700).contains(&code) {
+            return None;
+        }
+        let status_code = match code {
+            100 => StatusCode::Trying,
+            180 => StatusCode::Ringing,
+            181 => StatusCode::CallIsBeingForwarded,
+            182 => StatusCode::Queued,
+            183 => StatusCode::SessionProgress,
+            199 => StatusCode::EarlyDialogTerminated,
+            200 => StatusCode::OK,
+            202 => StatusCode::Accepted,
+            204 => StatusCode::NoNotification,
+            300 => StatusCode::MultipleChoices,
+            301 => StatusCode::MovedPermanently,
+            302 => StatusCode::MovedTemporarily,
+            305 => StatusCode::UseProxy,
+            380 => StatusCode::AlternativeService,
+            400 => StatusCode::BadRequest,
+            401 => StatusCode::Unauthorized,
+            402 => StatusCode::PaymentRequired,
+            403 => StatusCode::Forbidden,
+            404 => StatusCode::NotFound,
+            405 => StatusCode::MethodNotAllowed,
+            406 => StatusCode::NotAcceptableResourceContent,
+            407 => StatusCode::ProxyAuthenticationRequired,
+            408 => StatusCode::RequestTimeout,
+            410 => StatusCode::Gone,
+            412 => StatusCode::ConditionalRequestFailed,
+            413 => StatusCode::RequestEntityTooLarge,
+            414 => StatusCode::RequestUriTooLong,
+            415 => StatusCode::UnsupportedMediaType,
+            416 => StatusCode::UnsupportedUriScheme,
+            417 => StatusCode::UnknownResourcePriority,
+            420 => StatusCode::BadExtension,
+            421 => StatusCode::ExtensionRequired,
+            422 => StatusCode::SessionIntervalTooSmall,
+            423 => StatusCode::IntervalTooBrief,
+            424 => StatusCode::BadLocationInformation,
+            425 => StatusCode::BadAlertMessage,
+            428 => StatusCode::UseIdentityHeader,
+            429 => StatusCode::ProvideReferrerIdentity,
+            430 => StatusCode::FlowFailed,
+            433 => StatusCode::AnonymityDisallowed,
+            436 => StatusCode::BadIdentityInfo,
+            437 => StatusCode::UnsupportedCredential,
+            438 => StatusCode::InvalidIdentityHeader,
+            439 => StatusCode::FirstHopLacksOutboundSupport,
+            440 => StatusCode::MaxBreadthExceeded,
+            469 => StatusCode::BadInfoPackage,
+            470 => StatusCode::ConsentNeeded,
+            480 => StatusCode::TemporarilyUnavailable,
+            481 => StatusCode::CallOrTransactionDoesNotExist,
+            482 => StatusCode::LoopDetected,
+            483 => StatusCode::TooManyHops,
+            484 => StatusCode::AddressIncomplete,
+            485 => StatusCode::Ambiguous,
+            486 => StatusCode::BusyHere,
+            487 => StatusCode::RequestTerminated,
+            488 => StatusCode::NotAcceptableHere,
+            489 => StatusCode::BadEvent,
+            491 => StatusCode::RequestPending,
+            493 => StatusCode::Undecipherable,
+            494 => StatusCode::SecurityAgreementRequired,
+            500 => StatusCode::ServerInternalError,
+            501 => StatusCode::NotImplemented,
+            502 => StatusCode::BadGateway,
+            503 => StatusCode::ServiceUnavailable,
+            504 => StatusCode::ServerTimeout,
+            505 => StatusCode::VersionNotSupported,
+            513 => StatusCode::MessageTooLarge,
+            555 => StatusCode::PushNotificationServiceNotSupported,
+            580 => StatusCode::PreconditionFailure,
+            600 => StatusCode::BusyEverywhere,
+            603 => StatusCode::Decline,
+            604 => StatusCode::DoesNotExistAnywhere,
+            606 => StatusCode::NotAcceptable,
+            607 => StatusCode::Unwanted,
+            608 => StatusCode::Rejected,
+            _ => StatusCode::Other(OtherStatusCode(code)),
+        };
+        Some(status_code)
+    }
+
+    /// Numeric value of code
+    pub fn code(&self) -> u16 {
+        match *self {
+            StatusCode::Trying => 100,
+            StatusCode::Ringing => 180,
+            StatusCode::CallIsBeingForwarded => 181,
+            StatusCode::Queued => 182,
+            StatusCode::SessionProgress => 183,
+            StatusCode::EarlyDialogTerminated => 199,
+            StatusCode::OK => 200,
+            StatusCode::Accepted => 202,
+            StatusCode::NoNotification => 204,
+            StatusCode::MultipleChoices => 300,
+            StatusCode::MovedPermanently => 301,
+            StatusCode::MovedTemporarily => 302,
+            StatusCode::UseProxy => 305,
+            StatusCode::AlternativeService => 380,
+            StatusCode::BadRequest => 400,
+            StatusCode::Unauthorized => 401,
+            StatusCode::PaymentRequired => 402,
+            StatusCode::Forbidden => 403,
+            StatusCode::NotFound => 404,
+            StatusCode::MethodNotAllowed => 405,
+            StatusCode::NotAcceptableResourceContent => 406,
+            StatusCode::ProxyAuthenticationRequired => 407,
+            StatusCode::RequestTimeout => 408,
+            StatusCode::Gone => 410,
+            StatusCode::ConditionalRequestFailed => 412,
+            StatusCode::RequestEntityTooLarge => 413,
+            StatusCode::RequestUriTooLong => 414,
+            StatusCode::UnsupportedMediaType => 415,
+            StatusCode::UnsupportedUriScheme => 416,
+            StatusCode::UnknownResourcePriority => 417,
+            StatusCode::BadExtension => 420,
+            StatusCode::ExtensionRequired => 421,
+            StatusCode::SessionIntervalTooSmall => 422,
+            StatusCode::IntervalTooBrief => 423,
+            StatusCode::BadLocationInformation => 424,
+            StatusCode::BadAlertMessage => 425,
+            StatusCode::UseIdentityHeader => 428,
+            StatusCode::ProvideReferrerIdentity => 429,
+            StatusCode::FlowFailed => 430,
+            StatusCode::AnonymityDisallowed => 433,
+            StatusCode::BadIdentityInfo => 436,
+            StatusCode::UnsupportedCredential => 437,
+            StatusCode::InvalidIdentityHeader => 438,
+            StatusCode::FirstHopLacksOutboundSupport => 439,
+            StatusCode::MaxBreadthExceeded => 440,
+            StatusCode::BadInfoPackage => 469,
+            StatusCode::ConsentNeeded => 470,
+            StatusCode::TemporarilyUnavailable => 480,
+            StatusCode::CallOrTransactionDoesNotExist => 481,
+            StatusCode::LoopDetected => 482,
+            StatusCode::TooManyHops => 483,
+            StatusCode::AddressIncomplete => 484,
+            StatusCode::Ambiguous => 485,
+            StatusCode::BusyHere => 486,
+            StatusCode::RequestTerminated => 487,
+            StatusCode::NotAcceptableHere => 488,
+            StatusCode::BadEvent => 489,
+            StatusCode::RequestPending => 491,
+            StatusCode::Undecipherable => 493,
+            StatusCode::SecurityAgreementRequired => 494,
+            StatusCode::ServerInternalError => 500,
+            StatusCode::NotImplemented => 501,
+            StatusCode::BadGateway => 502,
+            StatusCode::ServiceUnavailable => 503,
+            StatusCode::ServerTimeout => 504,
+            StatusCode::VersionNotSupported => 505,
+            StatusCode::MessageTooLarge => 513,
+            StatusCode::PushNotificationServiceNotSupported => 555,
+            StatusCode::PreconditionFailure => 580,
+            StatusCode::BusyEverywhere => 600,
+            StatusCode::Decline => 603,
+            StatusCode::DoesNotExistAnywhere => 604,
+            StatusCode::NotAcceptable => 606,
+            StatusCode::Unwanted => 607,
+            StatusCode::Rejected => 608,
+            StatusCode::Other(other) => other.code(),
         }
     }
 
-    pub fn reason_phrase(&self) -> &str {
+    /// Default reason phrase. Empty string for codes that are not in the registry.
+    pub fn reason_phrase(&self) -> &'static str {
         match self {
-            &StatusCode::Trying => "Trying",
-            &StatusCode::Ringing => "Ringing",
-            &StatusCode::CallIsBeingForwarded => "Call Is Being Forwarded",
-            &StatusCode::Queued => "Queued",
-            &StatusCode::SessionProgress => "Session Progress",
-            &StatusCode::OK => "OK",
-            &StatusCode::MultipleChoices => "Multiple Choices",
-            &StatusCode::MovedPermanently => "Moved Permanently",
-            &StatusCode::MovedTemporarily => "Moved Temporarily",
-            &StatusCode::UseProxy => "Use Proxy",
-            &StatusCode::AlternativeService => "Alternative Service",
-            &StatusCode::BadRequest => "Bad Request",
-            &StatusCode::Unauthorized => "Unauthorized",
-            &StatusCode::PaymentRequired => "Payment Required",
-            &StatusCode::Forbidden => "Forbidden",
-            &StatusCode::NotFound => "Not Found",
-            &StatusCode::MethodNotAllowed => "Method Not Allowed",
-            &StatusCode::NotAcceptableResourceContent => "Not Acceptable",
-            &StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
-            &StatusCode::RequestTimeout => "Request Timeout",
-            &StatusCode::Gone => "Gone",
-            &StatusCode::RequestEntityTooLarge => "Request Entity Too Large",
-            &StatusCode::RequestUriTooLong => "Request-URI Too Long",
-            &StatusCode::UnsupportedMediaType => "Unsupported Media Type",
-            &StatusCode::UnsupportedUriScheme => "Unsupported URI Scheme",
-            &StatusCode::BadExtension => "Bad Extension",
-            &StatusCode::ExtensionRequired => "Extension Required",
-            &StatusCode::IntervalTooBrief => "Interval Too Brief",
-            &StatusCode::TemporarilyUnavailable => "Temporarily Unavailable",
-            &StatusCode::CallOrTransactionDoesNotExist => "Call/Transaction Does Not Exist",
-            &StatusCode::LoopDetected => "Loop Detected",
-            &StatusCode::TooManyHops => "Too Many Hops",
-            &StatusCode::AddressIncomplete => "Address Incomplete",
-            &StatusCode::Ambiguous => "Ambiguous",
-            &StatusCode::BusyHere => "Busy Here",
-            &StatusCode::RequestTerminated => "Request Terminated",
-            &StatusCode::NotAcceptableHere => "Not Acceptable Here",
-            &StatusCode::RequestPending => "Request Pending",
-            &StatusCode::Undecipherable => "Undecipherable",
-            &StatusCode::ServerInternalError => "Server Internal Error",
-            &StatusCode::NotImplemented => "Not Implemented",
-            &StatusCode::BadGateway => "Bad Gateway",
-            &StatusCode::ServiceUnavailable => "Service Unavailable",
-            &StatusCode::ServerTimeout => "Server Time-out",
-            &StatusCode::VersionNotSupported => "Version Not Supported",
-            &StatusCode::MessageTooLarge => "Message Too Large",
-            &StatusCode::BusyEverywhere => "Busy Everywhere",
-            &StatusCode::Decline => "Decline",
-            &StatusCode::DoesNotExistAnywhere => "Does Not Exist Anywhere",
-            &StatusCode::NotAcceptable => "Not Acceptable",
-            &StatusCode::Unknown => "Unknown",
+            StatusCode::Trying => "Trying",
+            StatusCode::Ringing => "Ringing",
+            StatusCode::CallIsBeingForwarded => "Call Is Being Forwarded",
+            StatusCode::Queued => "Queued",
+            StatusCode::SessionProgress => "Session Progress",
+            StatusCode::EarlyDialogTerminated => "Early Dialog Terminated",
+            StatusCode::OK => "OK",
+            StatusCode::Accepted => "Accepted",
+            StatusCode::NoNotification => "No Notification",
+            StatusCode::MultipleChoices => "Multiple Choices",
+            StatusCode::MovedPermanently => "Moved Permanently",
+            StatusCode::MovedTemporarily => "Moved Temporarily",
+            StatusCode::UseProxy => "Use Proxy",
+            StatusCode::AlternativeService => "Alternative Service",
+            StatusCode::BadRequest => "Bad Request",
+            StatusCode::Unauthorized => "Unauthorized",
+            StatusCode::PaymentRequired => "Payment Required",
+            StatusCode::Forbidden => "Forbidden",
+            StatusCode::NotFound => "Not Found",
+            StatusCode::MethodNotAllowed => "Method Not Allowed",
+            StatusCode::NotAcceptableResourceContent => "Not Acceptable",
+            StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
+            StatusCode::RequestTimeout => "Request Timeout",
+            StatusCode::Gone => "Gone",
+            StatusCode::ConditionalRequestFailed => "Conditional Request Failed",
+            StatusCode::RequestEntityTooLarge => "Request Entity Too Large",
+            StatusCode::RequestUriTooLong => "Request-URI Too Long",
+            StatusCode::UnsupportedMediaType => "Unsupported Media Type",
+            StatusCode::UnsupportedUriScheme => "Unsupported URI Scheme",
+            StatusCode::UnknownResourcePriority => "Unknown Resource-Priority",
+            StatusCode::BadExtension => "Bad Extension",
+            StatusCode::ExtensionRequired => "Extension Required",
+            StatusCode::SessionIntervalTooSmall => "Session Interval Too Small",
+            StatusCode::IntervalTooBrief => "Interval Too Brief",
+            StatusCode::BadLocationInformation => "Bad Location Information",
+            StatusCode::BadAlertMessage => "Bad Alert Message",
+            StatusCode::UseIdentityHeader => "Use Identity Header",
+            StatusCode::ProvideReferrerIdentity => "Provide Referrer Identity",
+            StatusCode::FlowFailed => "Flow Failed",
+            StatusCode::AnonymityDisallowed => "Anonymity Disallowed",
+            StatusCode::BadIdentityInfo => "Bad Identity Info",
+            StatusCode::UnsupportedCredential => "Unsupported Credential",
+            StatusCode::InvalidIdentityHeader => "Invalid Identity Header",
+            StatusCode::FirstHopLacksOutboundSupport => "First Hop Lacks Outbound Support",
+            StatusCode::MaxBreadthExceeded => "Max-Breadth Exceeded",
+            StatusCode::BadInfoPackage => "Bad Info Package",
+            StatusCode::ConsentNeeded => "Consent Needed",
+            StatusCode::TemporarilyUnavailable => "Temporarily Unavailable",
+            StatusCode::CallOrTransactionDoesNotExist => "Call/Transaction Does Not Exist",
+            StatusCode::LoopDetected => "Loop Detected",
+            StatusCode::TooManyHops => "Too Many Hops",
+            StatusCode::AddressIncomplete => "Address Incomplete",
+            StatusCode::Ambiguous => "Ambiguous",
+            StatusCode::BusyHere => "Busy Here",
+            StatusCode::RequestTerminated => "Request Terminated",
+            StatusCode::NotAcceptableHere => "Not Acceptable Here",
+            StatusCode::BadEvent => "Bad Event",
+            StatusCode::RequestPending => "Request Pending",
+            StatusCode::Undecipherable => "Undecipherable",
+            StatusCode::SecurityAgreementRequired => "Security Agreement Required",
+            StatusCode::ServerInternalError => "Server Internal Error",
+            StatusCode::NotImplemented => "Not Implemented",
+            StatusCode::BadGateway => "Bad Gateway",
+            StatusCode::ServiceUnavailable => "Service Unavailable",
+            StatusCode::ServerTimeout => "Server Time-out",
+            StatusCode::VersionNotSupported => "Version Not Supported",
+            StatusCode::MessageTooLarge => "Message Too Large",
+            StatusCode::PushNotificationServiceNotSupported => {
+                "Push Notification Service Not Supported"
+            }
+            StatusCode::PreconditionFailure => "Precondition Failure",
+            StatusCode::BusyEverywhere => "Busy Everywhere",
+            StatusCode::Decline => "Decline",
+            StatusCode::DoesNotExistAnywhere => "Does Not Exist Anywhere",
+            StatusCode::NotAcceptable => "Not Acceptable",
+            StatusCode::Unwanted => "Unwanted",
+            StatusCode::Rejected => "Rejected",
+            StatusCode::Other(_) => "",
         }
     }
+
+    /// 1xx
+    pub fn is_provisional(&self) -> bool {
+        (100..200).contains(&self.code())
+    }
+
+    /// 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.code())
+    }
+
+    /// 3xx
+    pub fn is_redirect(&self) -> bool {
+        (300..400).contains(&self.code())
+    }
+
+    /// 4xx
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.code())
+    }
+
+    /// 5xx
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.code())
+    }
+
+    /// 6xx
+    pub fn is_global_failure(&self) -> bool {
+        (600..700).contains(&self.code())
+    }
+
+    /// Any response except 1xx
+    pub fn is_final(&self) -> bool {
+        self.code() >= 200
+    }
 }
@@ -1,127 +1,259 @@
+use crate::headers::HeaderKey;
 use crate::{SipHeader, SipHeaders, SipMessage, SipRFCHeader, SipRequest, SipResponse};
+use alloc::vec::Vec;
+use core::{fmt, str};
 
-struct SipMsgSerializer {
-    internal_buffer: [u8; 5000],
+/// Default maximum size of serialized message.
+/// It is the largest message that fits into one UDP datagram.
+const MAX_SIP_MESSAGE_SIZE: usize = 65535;
+
+#[derive(Debug, PartialEq)]
+pub enum SerializeError {
+    /// Serialized message is longer than maximum size of `Serializer`
+    MessageTooLong,
+    /// Caller-provided buffer is too small for the message
+    BufferOverflow,
+    /// Message contains bytes that are not valid UTF-8, so it can't be written to `core::fmt::Write`
+    InvalidUtf8,
+    /// `core::fmt::Write` sink returned an error
+    Fmt,
 }
 
-/*
-https://tools.ietf.org/html/rfc3261#section-7.3.1
-The relative order of header fields with different field names is not
-   significant.  However, it is RECOMMENDED that header fields which are
-   needed for proxy processing (Via, Route, Record-Route, Proxy-Require,
-   Max-Forwards, and Proxy-Authorization, for example)
-*/
-
-// First element - highest priority
-static PRIORITY_HEADERS: &'static [SipRFCHeader] = &[
-    SipRFCHeader::Via,
-    SipRFCHeader::Route,
-    SipRFCHeader::RecordRoute,
-    SipRFCHeader::MaxForwards,
-    SipRFCHeader::ProxyRequire,
-    SipRFCHeader::ProxyAuthorization,
-];
-
-/// The default maximum SIP message size on Cisco UCM Release 8.6.1
-/// and earlier versions is 5000 bytes. The default on Cisco UCM Release 8.6.2
-/// and later versions is 11000 bytes.
-const MAX_SIP_MESSAGE_SIZE: usize = 5000;
-
-impl SipMsgSerializer {
-    pub fn new() -> SipMsgSerializer {
-        let ib: [u8; MAX_SIP_MESSAGE_SIZE] = [0; MAX_SIP_MESSAGE_SIZE];
-        SipMsgSerializer {
-            internal_buffer: ib,
-        }
+/// Destination of serialized bytes
+pub trait SerializeSink {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError>;
+}
+
+impl SerializeSink for Vec<u8> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        self.extend_from_slice(data);
+        Ok(())
     }
+}
 
-    pub fn serialize_msg(&mut self, msg: &SipMessage) -> &[u8] {
-        match msg {
-            SipMessage::Request(r) => return self.serialize_req(r),
-            SipMessage::Response(r) => return self.serialize_resp(r),
-        };
+/// Writes into caller-provided buffer, returns `SerializeError::BufferOverflow` if it is full
+pub struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceSink<'a> {
+    pub fn new(buf: &'a mut [u8]) -> SliceSink<'a> {
+        SliceSink { buf, len: 0 }
+    }
+
+    /// Count of written bytes
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    pub fn serialize_req(&mut self, req: &SipRequest) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &req.rl.raw);
-        self.serialize(buf_offset, &req.headers, req.body)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    pub fn serialize_resp(&mut self, resp: &SipResponse) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &resp.sl.raw);
-        self.serialize(buf_offset, &resp.headers, resp.body)
+    /// Written bytes
+    pub fn data(&self) -> &[u8] {
+        &self.buf[..self.len]
     }
+}
+
+impl SerializeSink for SliceSink<'_> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        let new_len = self.len + data.len();
+        if new_len > self.buf.len() {
+            return Err(SerializeError::BufferOverflow);
+        }
+        self.buf[self.len..new_len].copy_from_slice(data);
+        self.len = new_len;
+        Ok(())
+    }
+}
+
+/// Adapter for `core::fmt::Write` (`String`, `fmt::Formatter`...)
+pub struct FmtSink<W: fmt::Write>(pub W);
+
+impl<W: fmt::Write> SerializeSink for FmtSink<W> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        let s = str::from_utf8(data).map_err(|_| SerializeError::InvalidUtf8)?;
+        self.0.write_str(s).map_err(|_| SerializeError::Fmt)
+    }
+}
+
+/// Writes SIP messages to `SerializeSink`.
+///
+/// Headers are written in wire order, lines that were not modified are copied byte-for-byte.
+/// Content-Length is always computed from the body. It is kept as is if it matches the body,
+/// otherwise it is rewritten. If it is absent it is added as the last header.
+/// On error the sink may contain part of message.
+/// ```rust
+/// use sipmsg::{SipMessage, SipSerializer, SipSliceSink};
+///
+/// let raw_msg = b"SIP/2.0 200 OK\r\nl: 3\r\nCall-ID:  a84b4c76e66710\r\n\r\nabc";
+/// let (_, msg) = SipMessage::parse(raw_msg).unwrap();
+/// let mut buf = [0u8; 100];
+/// let mut sink = SipSliceSink::new(&mut buf);
+/// SipSerializer::new().serialize_msg(&msg, &mut sink).unwrap();
+/// assert_eq!(sink.data(), &raw_msg[..]);
+/// ```
+pub struct Serializer {
+    max_message_size: usize,
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Serializer::new()
+    }
+}
 
-    fn append_data_to_ib(&mut self, offset: usize, src: &[u8]) -> usize /* count written data */ {
-        let new_offset = offset + src.len();
-        if new_offset > MAX_SIP_MESSAGE_SIZE {
-            panic!("Internal buffer overflow. Sip message is too long");
+/// Counts written bytes and limits them by the maximum size
+struct LimitedSink<'a, S: SerializeSink> {
+    sink: &'a mut S,
+    written: usize,
+    max_size: usize,
+}
+
+impl<S: SerializeSink> LimitedSink<'_, S> {
+    fn write(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        if self.written + data.len() > self.max_size {
+            return Err(SerializeError::MessageTooLong);
         }
-        &self.internal_buffer[offset..new_offset].copy_from_slice(src);
-        return new_offset;
+        self.sink.write_bytes(data)?;
+        self.written += data.len();
+        Ok(())
+    }
+}
+
+impl Serializer {
+    pub fn new() -> Serializer {
+        Serializer::with_max_message_size(MAX_SIP_MESSAGE_SIZE)
+    }
+
+    pub fn with_max_message_size(max_message_size: usize) -> Serializer {
+        Serializer { max_message_size }
     }
-    /// Serialize headers and body
-    fn serialize(&mut self, buf_offset: usize, headers: &SipHeaders, body: Option<&[u8]>) -> &[u8] {
-        let mut new_offset = buf_offset;
-        new_offset = self.serialize_headers(&headers, new_offset);
-        if body != None {
-            new_offset = self.append_data_to_ib(new_offset, body.unwrap());
+
+    /// Returns count of written bytes
+    pub fn serialize_msg<S: SerializeSink>(
+        &self,
+        msg: &SipMessage,
+        sink: &mut S,
+    ) -> Result<usize, SerializeError> {
+        match msg {
+            SipMessage::Request(r) => self.serialize_req(r, sink),
+            SipMessage::Response(r) => self.serialize_resp(r, sink),
         }
-        &self.internal_buffer[..new_offset]
     }
 
-    fn serialize_header(&mut self, hdr: &SipHeader, buf_offset: usize) -> usize {
-        let mut new_offset = self.append_data_to_ib(buf_offset, hdr.name.as_ref().as_bytes());
-        new_offset = self.append_data_to_ib(new_offset, b": ");
-        self.append_data_to_ib(new_offset, hdr.raw_value_param)
+    /// Returns count of written bytes
+    pub fn serialize_req<S: SerializeSink>(
+        &self,
+        req: &SipRequest,
+        sink: &mut S,
+    ) -> Result<usize, SerializeError> {
+        self.serialize(&req.rl.raw, &req.headers, req.body.as_deref(), sink)
+    }
+
+    /// Returns count of written bytes
+    pub fn serialize_resp<S: SerializeSink>(
+        &self,
+        resp: &SipResponse,
+        sink: &mut S,
+    ) -> Result<usize, SerializeError> {
+        self.serialize(&resp.sl.raw, &resp.headers, resp.body.as_deref(), sink)
+    }
+
+    /// Serialize message into the new vector
+    pub fn msg_to_vec(&self, msg: &SipMessage) -> Result<Vec<u8>, SerializeError> {
+        let mut v = Vec::new();
+        self.serialize_msg(msg, &mut v)?;
+        Ok(v)
     }
 
-    fn serialize_headers(&mut self, sip_headers: &SipHeaders, buf_offset: usize) -> usize {
-        let mut new_offset = buf_offset;
-        // STEP 1
-        // Serialize RFC priority headers
-        for hdr in PRIORITY_HEADERS {
-            let hdrs = sip_headers.get_rfc(*hdr);
-            if hdrs == None {
+    fn serialize<S: SerializeSink>(
+        &self,
+        first_line: &[u8],
+        headers: &SipHeaders,
+        body: Option<&[u8]>,
+        sink: &mut S,
+    ) -> Result<usize, SerializeError> {
+        let mut sink = LimitedSink {
+            sink,
+            written: 0,
+            max_size: self.max_message_size,
+        };
+        sink.write(first_line)?;
+
+        let body = body.unwrap_or(b"");
+        let mut content_length_written = false;
+        for line in headers.lines() {
+            if line.key == HeaderKey::Rfc(SipRFCHeader::ContentLength) {
+                // Only one Content-Length is written
+                if content_length_written {
+                    continue;
+                }
+                content_length_written = true;
+                let is_valid = matches!(
+                    headers.line_values(line).next(),
+                    Some(hdr) if str::parse::<usize>(&hdr.value.vstr) == Ok(body.len())
+                );
+                match &line.raw {
+                    Some(raw) if is_valid => {
+                        sink.write(raw)?;
+                        sink.write(b"\r\n")?;
+                    }
+                    _ => write_content_length(body.len(), &mut sink)?,
+                }
                 continue;
             }
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
-            }
-        }
-        // STEP 2
-        // Serialize other RFC headers
-        let other_rfs_headers_iter = sip_headers
-            .get_rfc_headers_keys()
-            .filter(|x| !PRIORITY_HEADERS.contains(x));
-        for hdrs_iter in other_rfs_headers_iter {
-            let hdrs = sip_headers.get_rfc(*hdrs_iter);
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
-            }
-        }
-        // STEP 3
-        // Serialize extention headers
-        let ext_headers_iter = sip_headers.get_ext_headers_keys();
-        match ext_headers_iter {
-            Some(ext_hdrs) => {
-                for header_name in ext_hdrs {
-                    // One header name can contain multiple header value
-                    let hdrs = sip_headers.get_ext(header_name).unwrap();
-                    for hdr in hdrs {
-                        new_offset = self.serialize_header(hdr, new_offset);
-                        new_offset = self.append_data_to_ib(new_offset, b"\r\n");
+            match &line.raw {
+                Some(raw) => {
+                    sink.write(raw)?;
+                    sink.write(b"\r\n")?;
+                }
+                None => {
+                    for hdr in headers.line_values(line) {
+                        serialize_header(hdr, &mut sink)?;
                     }
                 }
             }
-            None => { /* There is no ext. headers. Do nothing */ }
         }
+        if !content_length_written {
+            write_content_length(body.len(), &mut sink)?;
+        }
+        // Mark end of headers by double "\r\n\r\n"
+        sink.write(b"\r\n")?;
+        sink.write(body)?;
+        Ok(sink.written)
+    }
+}
 
-        // Mark and headers by double "\r\n\r\n"
-        self.append_data_to_ib(new_offset, b"\r\n")
+fn write_content_length<S: SerializeSink>(
+    mut value: usize,
+    sink: &mut LimitedSink<S>,
+) -> Result<(), SerializeError> {
+    let mut digits = [0u8; 20];
+    let mut pos = digits.len();
+    loop {
+        pos -= 1;
+        digits[pos] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
     }
+    sink.write(b"Content-Length: ")?;
+    sink.write(&digits[pos..])?;
+    sink.write(b"\r\n")
+}
+
+fn serialize_header<S: SerializeSink>(
+    hdr: &SipHeader,
+    sink: &mut LimitedSink<S>,
+) -> Result<(), SerializeError> {
+    sink.write(hdr.name.as_ref().as_bytes())?;
+    sink.write(b": ")?;
+    sink.write(&hdr.raw_value_param)?;
+    sink.write(b"\r\n")
 }
 
 #[cfg(test)]
@@ -139,10 +271,12 @@ mod tests {
         Content-Length: 0\r\n\r\n"
             .as_bytes();
         let (_, resp) = SipResponse::parse(resp_msg_buf).unwrap();
-        let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_resp(&resp);
+        let mut serialized_buf = Vec::new();
+        Serializer::new()
+            .serialize_resp(&resp, &mut serialized_buf)
+            .unwrap();
 
-        let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
+        let (_, msg2) = SipMessage::parse(&serialized_buf).unwrap();
         let new_resp = msg2.response().unwrap();
         assert_eq!(new_resp.sl.raw, "SIP/2.0 180 Ringing\r\n".as_bytes());
         assert_eq!(
@@ -217,9 +351,8 @@ mod tests {
         Content-Length: 4\r\n\r\nbody".as_bytes();
 
         let (_, msg) = SipMessage::parse(invite_msg_buf).unwrap();
-        let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_msg(&msg);
-        let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
+        let serialized_buf = Serializer::new().msg_to_vec(&msg).unwrap();
+        let (_, msg2) = SipMessage::parse(&serialized_buf).unwrap();
         let new_req = msg2.request().unwrap();
         assert_eq!(
             new_req.rl.raw,
@@ -315,11 +448,11 @@ mod tests {
 
         assert_eq!(
             new_req.headers.get_rfc(SipRFCHeader::Supported).unwrap()[0].raw_value_param,
-            b"replaces"
+            &b"replaces"[..]
         );
         assert_eq!(
             new_req.headers.get_rfc(SipRFCHeader::Supported).unwrap()[1].raw_value_param,
-            b"100rel"
+            &b"100rel"[..]
         );
 
         assert_eq!(
@@ -328,7 +461,7 @@ mod tests {
                 .get_rfc_s(SipRFCHeader::ContentType)
                 .unwrap()
                 .raw_value_param,
-            b"application/sdp"
+            &b"application/sdp"[..]
         );
 
         assert_eq!(
@@ -337,16 +470,151 @@ mod tests {
                 .get_rfc_s(SipRFCHeader::ContentLength)
                 .unwrap()
                 .raw_value_param,
-            b"4"
+            &b"4"[..]
         );
 
         let extension_headers = new_req.headers.get_ext("ExtensionHeader").unwrap();
-        assert_eq!(extension_headers[0].raw_value_param, b"value1;param");
+        assert_eq!(extension_headers[0].raw_value_param, &b"value1;param"[..]);
         assert_eq!(
             extension_headers[1].raw_value_param,
-            b"value2;param1=value1"
+            &b"value2;param1=value1"[..]
         );
 
-        assert_eq!(new_req.body.unwrap(), b"body");
+        assert_eq!(new_req.body.as_deref().unwrap(), b"body");
+    }
+
+    #[test]
+    fn test_serializator_limits() {
+        let msg_buf = "SIP/2.0 200 OK\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        Content-Length: 1\r\n\r\n\
+        body"
+            .as_bytes();
+        let (_, msg) = SipMessage::parse(msg_buf).unwrap();
+        let expected = "SIP/2.0 200 OK\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        Content-Length: 1\r\n\r\n\
+        b"
+        .as_bytes();
+
+        let mut buf = [0u8; 100];
+        let mut sink = SliceSink::new(&mut buf);
+        assert_eq!(
+            Serializer::new().serialize_msg(&msg, &mut sink),
+            Ok(expected.len())
+        );
+        assert_eq!(sink.data(), expected);
+
+        let mut buf = [0u8; 20];
+        let mut sink = SliceSink::new(&mut buf);
+        assert_eq!(
+            Serializer::new().serialize_msg(&msg, &mut sink),
+            Err(SerializeError::BufferOverflow)
+        );
+
+        assert_eq!(
+            Serializer::with_max_message_size(expected.len() - 1).msg_to_vec(&msg),
+            Err(SerializeError::MessageTooLong)
+        );
+        assert_eq!(
+            Serializer::with_max_message_size(expected.len()).msg_to_vec(&msg),
+            Ok(expected.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_serializator_fmt() {
+        let (_, msg) =
+            SipMessage::parse(b"OPTIONS sip:user@example.com SIP/2.0\r\nCall-ID: a84b\r\n\r\n")
+                .unwrap();
+        let mut sink = FmtSink(alloc::string::String::new());
+        Serializer::new().serialize_msg(&msg, &mut sink).unwrap();
+        assert_eq!(
+            sink.0,
+            "OPTIONS sip:user@example.com SIP/2.0\r\nCall-ID: a84b\r\nContent-Length: 0\r\n\r\n"
+        );
+
+        let (_, msg) =
+            SipMessage::parse(b"OPTIONS sip:user@example.com SIP/2.0\r\nCall-ID: a84b\r\n\r\n\xff")
+                .unwrap();
+        let mut sink = FmtSink(alloc::string::String::new());
+        assert_eq!(
+            Serializer::new().serialize_msg(&msg, &mut sink),
+            Err(SerializeError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn test_serializator_wire_order() {
+        let invite_msg_buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        v: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        X-Custom:   value1\r\n\
+        Via: SIP/2.0/UDP 10.135.0.13:5060;branch=3dfdfd2asdasxccc ,\r\n SIP/2.0/UDP 10.135.0.14\r\n\
+        To: Bob <sip:bob@biloxi.com>\r\n\
+        x-custom: value2\r\n\
+        From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+        l: 4\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        CSeq: 314159 INVITE\r\n\r\nbody"
+            .as_bytes();
+
+        let (_, mut msg) = SipRequest::parse(invite_msg_buf).unwrap();
+        let mut serialized_buf = Vec::new();
+        Serializer::new()
+            .serialize_req(&msg, &mut serialized_buf)
+            .unwrap();
+        assert_eq!(serialized_buf, invite_msg_buf);
+
+        // Content-Length is rewritten in place
+        msg.body = Some(b"new body".into());
+        serialized_buf.clear();
+        Serializer::new()
+            .serialize_req(&msg, &mut serialized_buf)
+            .unwrap();
+        let (_, new_msg) = SipRequest::parse(&serialized_buf).unwrap();
+        assert_eq!(new_msg.body.as_deref().unwrap(), b"new body");
+        let names: Vec<&str> = new_msg.headers.iter().map(|h| h.name.as_ref()).collect();
+        assert_eq!(
+            names,
+            [
+                "v",
+                "Max-Forwards",
+                "X-Custom",
+                "Via",
+                "Via",
+                "To",
+                "x-custom",
+                "From",
+                "Content-Length",
+                "Call-ID",
+                "CSeq"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serializator_edited_headers() {
+        let msg_buf = "SIP/2.0 200 OK\r\n\
+        Via: SIP/2.0/UDP proxy.example.com;branch=z9hG4bK1, SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK2\r\n\
+        Call-ID:   a84b4c76e66710\r\n\
+        Content-Length: 0\r\n\r\n"
+            .as_bytes();
+        let (_, mut resp) = SipResponse::parse(msg_buf).unwrap();
+        resp.headers.pop_front_rfc(SipRFCHeader::Via);
+        resp.headers
+            .push_back(SipHeader::new_owned("Server", "sipcore").unwrap());
+
+        assert_eq!(
+            Serializer::new()
+                .msg_to_vec(&SipMessage::Response(resp))
+                .unwrap(),
+            "SIP/2.0 200 OK\r\n\
+            Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK2\r\n\
+            Call-ID:   a84b4c76e66710\r\n\
+            Content-Length: 0\r\n\
+            Server: sipcore\r\n\r\n"
+                .as_bytes()
+        );
     }
 }
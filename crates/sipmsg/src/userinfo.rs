@@ -1,16 +1,18 @@
 use crate::common::{
     bnfcore::*,
     errorparse::SipParseError,
+    escape::percent_decode,
     nom_wrappers::{from_utf8_nom, take_while_with_escaped},
 };
+use alloc::borrow::Cow;
 use core::str;
 
 /// userinfo =  ( user / telephone-subscriber ) [ ":" password ] "@"
 /// user     =  1*( unreserved / escaped / user-unreserved )
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct UserInfo<'a> {
-    pub value: &'a str, // ( user / telephone-subscriber )
-    pub password: Option<&'a str>,
+    pub value: Cow<'a, str>, // ( user / telephone-subscriber )
+    pub password: Option<Cow<'a, str>>,
     // TODO add boolean or enum about detect is it user or telefon-subscriber
 }
 
@@ -20,6 +22,23 @@ fn is_userinfo_char(c: u8) -> bool {
 }
 
 impl<'a> UserInfo<'a> {
+    pub fn into_owned(self) -> UserInfo<'static> {
+        UserInfo {
+            value: Cow::Owned(self.value.into_owned()),
+            password: self.password.map(|p| Cow::Owned(p.into_owned())),
+        }
+    }
+
+    /// User with decoded escapes, see `percent_decode`
+    pub fn decoded_value(&self) -> Cow<'_, str> {
+        percent_decode(&self.value)
+    }
+
+    /// Password with decoded escapes, see `percent_decode`
+    pub fn decoded_password(&self) -> Option<Cow<'_, str>> {
+        self.password.as_deref().map(percent_decode)
+    }
+
     fn take_user(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError> {
         take_while_with_escaped(input, is_userinfo_char)
     }
@@ -30,32 +49,32 @@ impl<'a> UserInfo<'a> {
 
     pub fn from_bytes(input: &'a [u8]) -> Result<UserInfo, nom::Err<SipParseError>> {
         if input.len() <= 1 {
-            return sip_parse_error!(1);
+            return sip_parse_error!(Uri);
         }
 
-        if !is_userinfo_char(input[0]) {
-            return sip_parse_error!(2);
+        if !is_userinfo_char(input[0]) && !is_escaped(input) {
+            return sip_parse_error!(Uri);
         }
 
         let (input, user) = UserInfo::take_user(input)?;
         if input.len() == 0 || (input.len() == 1 && input[0] == b'@') {
             let (_, user_str) = from_utf8_nom(user)?;
             return Ok(UserInfo {
-                value: user_str,
+                value: Cow::Borrowed(user_str),
                 password: None,
             });
         } else {
             if input[0] != b':' || input.len() == 2 {
                 // input.len() == 2 it is ":@" ( emptypass )
-                return sip_parse_error!(3, "Empty password");
+                return sip_parse_error!(Uri, "Empty password");
             }
 
             let (_, pswd) = UserInfo::take_password(&input[1..])?;
             let (_, user_str) = from_utf8_nom(user)?;
             let (_, pswd_str) = from_utf8_nom(pswd)?;
             return Ok(UserInfo {
-                value: user_str,
-                password: Some(pswd_str),
+                value: Cow::Borrowed(user_str),
+                password: Some(Cow::Borrowed(pswd_str)),
             });
         }
     }
@@ -96,7 +115,7 @@ mod tests {
         match UserInfo::from_bytes(input.as_bytes()) {
             Ok(userinfo) => {
                 assert_eq!(userinfo.value, expexted_value);
-                assert_eq!(userinfo.password, expected_password);
+                assert_eq!(userinfo.password.as_deref(), expected_password);
             }
             Err(_) => panic!(),
         }
@@ -108,6 +127,11 @@ mod tests {
         test_case_from_bytes("+1-212-555-1212:1234@", "+1-212-555-1212", Some("1234"));
         test_case_from_bytes("a:b@", "a", Some("b"));
         test_case_from_bytes("a@", "a", None);
+        test_case_from_bytes("%61lice@", "%61lice", None);
+
+        let userinfo = UserInfo::from_bytes(b"%61lice:p%40ss@").unwrap();
+        assert_eq!(userinfo.decoded_value(), "alice");
+        assert_eq!(userinfo.decoded_password().as_deref(), Some("p@ss"));
 
         parse_should_fail("alice:@");
         parse_should_fail(":@");
@@ -0,0 +1,111 @@
+use crate::{
+    headers::{SipHeaders, SipRFCHeader},
+    SipHeaderTagType,
+};
+use alloc::vec::Vec;
+use core::str;
+
+/// Violation of structural rules of rfc3261 that the parser doesn't check.
+/// Returned by `SipRequest::validate` and `SipResponse::validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// Mandatory header is absent
+    MissingHeader(SipRFCHeader),
+    /// Header must be present only one time
+    DuplicateHeader(SipRFCHeader),
+    /// Value of header is out of range. Ex: `Max-Forwards: 300`
+    InvalidHeader(SipRFCHeader),
+    /// Sequence number of CSeq is not less than 2**31
+    /// [rfc3261 section-8.1.1.5](https://tools.ietf.org/html/rfc3261#section-8.1.1.5)
+    CSeqNumberTooLarge,
+    /// Method of CSeq header is not equal to the method of request
+    CSeqMethodMismatch,
+    /// Content-Length is not equal to the length of body
+    ContentLengthMismatch {
+        content_length: usize,
+        body_length: usize,
+    },
+}
+
+/// Headers that must be present in request
+/// [rfc3261 section-8.1.1](https://tools.ietf.org/html/rfc3261#section-8.1.1)
+pub(crate) static MANDATORY_REQUEST_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::To,
+    SipRFCHeader::From,
+    SipRFCHeader::CSeq,
+    SipRFCHeader::CallID,
+    SipRFCHeader::MaxForwards,
+    SipRFCHeader::Via,
+];
+
+/// Headers that must be present in response
+/// [rfc3261 section-8.2.6.2](https://tools.ietf.org/html/rfc3261#section-8.2.6.2)
+pub(crate) static MANDATORY_RESPONSE_HEADERS: &[SipRFCHeader] = &[
+    SipRFCHeader::To,
+    SipRFCHeader::From,
+    SipRFCHeader::CSeq,
+    SipRFCHeader::CallID,
+    SipRFCHeader::Via,
+];
+
+/// Checks headers that are common for requests and responses
+pub(crate) fn validate_headers(
+    headers: &SipHeaders,
+    mandatory_headers: &[SipRFCHeader],
+    body: Option<&[u8]>,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for hdr in mandatory_headers {
+        match headers.get_rfc(*hdr) {
+            None => violations.push(Violation::MissingHeader(*hdr)),
+            // Via is the only header of them that can have several values
+            Some(values) if values.len() > 1 && *hdr != SipRFCHeader::Via => {
+                violations.push(Violation::DuplicateHeader(*hdr))
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(cseq) = headers.get_rfc_s(SipRFCHeader::CSeq) {
+        let number = cseq
+            .value
+            .tags()
+            .and_then(|tags| str::from_utf8(tags.get(&SipHeaderTagType::Number)?).ok());
+        // Number consists of digits, so it can't be parsed only if it is too large
+        if let Some(number) = number {
+            if !matches!(number.parse::<u32>(), Ok(seq) if seq <= i32::MAX as u32) {
+                violations.push(Violation::CSeqNumberTooLarge);
+            }
+        }
+    }
+
+    // Max-Forwards = 1*DIGIT, the value is in range 0-255
+    // [rfc3261 section-20.22](https://tools.ietf.org/html/rfc3261#section-20.22)
+    if let Some(max_forwards) = headers.get_rfc_s(SipRFCHeader::MaxForwards) {
+        if max_forwards.value.vstr.parse::<u8>().is_err() {
+            violations.push(Violation::InvalidHeader(SipRFCHeader::MaxForwards));
+        }
+    }
+
+    // Without Content-Length the body is the rest of datagram
+    match headers.get_rfc(SipRFCHeader::ContentLength) {
+        Some(values) if values.len() > 1 => {
+            violations.push(Violation::DuplicateHeader(SipRFCHeader::ContentLength))
+        }
+        Some(values) => {
+            let body_length = body.map_or(0, |body| body.len());
+            match values[0].value.vstr.parse::<usize>() {
+                Ok(content_length) if content_length != body_length => {
+                    violations.push(Violation::ContentLengthMismatch {
+                        content_length,
+                        body_length,
+                    })
+                }
+                Ok(_) => {}
+                Err(_) => violations.push(Violation::InvalidHeader(SipRFCHeader::ContentLength)),
+            }
+        }
+        None => {}
+    }
+    violations
+}
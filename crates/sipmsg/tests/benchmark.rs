@@ -1,25 +1,25 @@
 use sipmsg::*;
 use std::time::Instant;
 
-//#[test]
-fn parse_invite() {
-    let raw_message = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+const INVITE: &[u8] = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
 Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
-To: Bob <bob@biloxi.com>\r\n\
-From: Alice <alice@atlanta.com>;tag=1928301774\r\n\
+To: Bob <sip:bob@biloxi.com>\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
 Call-ID: a84b4c76e66710\r\n\
 CSeq: 314159 INVITE\r\n\
 Max-Forwards: 70\r\n\
 Date: Thu, 21 Feb 2002 13:02:03 GMT\r\n\
 Contact: <sip:alice@pc33.atlanta.com>\r\n\
 Content-Type: application/sdp\r\n\
-Content-Length: 0\r\n\r\n"
-        .as_bytes();
-    let size_of_message = raw_message.len();
+Content-Length: 0\r\n\r\n";
+
+/// Parses INVITE and reads the topmost Via during one second.
+/// Returns count of parsed messages.
+fn parse_invite_loop(options: &SipParseOptions) -> usize {
     let mut counter = 0;
     let now = Instant::now();
     loop {
-        let (_, parsed_req) = SipRequest::parse(raw_message).unwrap();
+        let (_, parsed_req) = SipRequest::parse_ext(INVITE, options).unwrap();
         assert_eq!(
             parsed_req
                 .headers
@@ -36,21 +36,47 @@ Content-Length: 0\r\n\r\n"
                 .unwrap()
                 .params()
                 .unwrap()
-                .get(&"branch"),
-            Some(&Some("z9hG4bKnashds8"))
+                .get("branch"),
+            Some(Some("z9hG4bKnashds8"))
         );
         counter += 1;
         if now.elapsed().as_secs() == 1 {
             break;
         }
     }
-    // uncomment #[test]
-    // cargo test --release -- --nocapture parse_invite
+    counter
+}
+
+#[test]
+#[ignore]
+fn parse_invite() {
+    let counter = parse_invite_loop(&SipParseOptions::default());
+    // cargo test --release -- --ignored --nocapture parse_invite
     // tested by Intel(R) Core(TM) i7-6700HQ CPU @ 2.60GHz
     // 63 mbytes per second, count sip messages: 184942
     println!(
         "{} mbytes per second, count sip messages: {}",
-        (size_of_message * counter) / 1024 / 1024,
+        (INVITE.len() * counter) / 1024 / 1024,
         counter
     );
 }
+
+#[test]
+#[ignore]
+fn parse_invite_lazy() {
+    let options = SipParseOptions {
+        lazy_headers: true,
+        ..SipParseOptions::default()
+    };
+    let counter = parse_invite_loop(&options);
+    let eager_counter = parse_invite_loop(&SipParseOptions::default());
+    // cargo test --release -- --ignored --nocapture parse_invite_lazy
+    // Only Via and Content-Length values are parsed, values borrow the input.
+    // Speed depends on the machine, the ratio to eager parsing is measured in the same run.
+    println!(
+        "lazy: {} mbytes per second, count sip messages: {}, {:.1}x of eager parsing",
+        (INVITE.len() * counter) / 1024 / 1024,
+        counter,
+        counter as f64 / eager_counter as f64
+    );
+}
@@ -0,0 +1,144 @@
+use sipmsg::*;
+
+const INVITE: &str = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+To: Bob <sip:bob@biloxi.com>\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 314159 INVITE\r\n\
+Max-Forwards: 70\r\n\
+Content-Length: 10\r\n\r\n\
+0123456789";
+
+const RINGING: &str = "SIP/2.0 180 Ringing\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 314159 INVITE\r\n\
+l: 0\r\n\r\n";
+
+#[test]
+fn decode_byte_by_byte() {
+    let mut decoder = SipStreamDecoder::new();
+    let input = INVITE.as_bytes();
+    for b in &input[..input.len() - 1] {
+        decoder.push(&[*b]);
+        assert!(matches!(
+            decoder.decode().unwrap(),
+            SipStreamItem::NeedMoreData
+        ));
+    }
+    decoder.push(&input[input.len() - 1..]);
+    match decoder.decode().unwrap() {
+        SipStreamItem::Message(msg) => {
+            let req = msg.request().unwrap();
+            assert_eq!(req.rl.method, SipMethod::INVITE);
+            assert_eq!(req.body.as_deref().unwrap(), b"0123456789");
+        }
+        _ => panic!(),
+    }
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::NeedMoreData
+    ));
+    assert_eq!(decoder.buffered_len(), 0);
+}
+
+#[test]
+fn decode_several_messages_and_keep_alives() {
+    let mut decoder = SipStreamDecoder::new();
+    decoder.push(b"\r\n\r\n");
+    decoder.push(INVITE.as_bytes());
+    decoder.push(b"\r\n");
+    decoder.push(RINGING.as_bytes());
+    decoder.push(&RINGING.as_bytes()[..20]);
+
+    assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Ping));
+    match decoder.decode().unwrap() {
+        SipStreamItem::Message(msg) => {
+            assert_eq!(
+                msg.request().unwrap().body.as_deref().unwrap(),
+                b"0123456789"
+            )
+        }
+        _ => panic!(),
+    }
+    assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Pong));
+    match decoder.decode().unwrap() {
+        SipStreamItem::Message(msg) => {
+            let resp = msg.response().unwrap();
+            assert_eq!(resp.sl.status_code, SipResponseStatusCode::Ringing);
+            assert_eq!(resp.body.as_deref().unwrap(), b"");
+        }
+        _ => panic!(),
+    }
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::NeedMoreData
+    ));
+    assert_eq!(decoder.buffered_len(), 20);
+
+    decoder.push(&RINGING.as_bytes()[20..]);
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::Message(_)
+    ));
+}
+
+#[test]
+fn decode_split_keep_alives() {
+    // Ping is split between reads
+    let mut decoder = SipStreamDecoder::new();
+    decoder.push(b"\r\n");
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::NeedMoreData
+    ));
+    decoder.push(b"\r\n");
+    assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Ping));
+    assert_eq!(decoder.buffered_len(), 0);
+
+    // Pong followed by message
+    decoder.push(b"\r\n");
+    decoder.push(RINGING.as_bytes());
+    assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Pong));
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::Message(_)
+    ));
+
+    let mut decoder = SipStreamDecoder::new().expect_pongs();
+    decoder.push(b"\r\n");
+    assert!(matches!(decoder.decode().unwrap(), SipStreamItem::Pong));
+    assert!(matches!(
+        decoder.decode().unwrap(),
+        SipStreamItem::NeedMoreData
+    ));
+    assert_eq!(decoder.buffered_len(), 0);
+}
+
+#[test]
+fn decode_malformed() {
+    let mut decoder = SipStreamDecoder::new();
+    decoder.push(b"HELLO WORLD\r\n\r\n");
+    assert!(decoder.decode().is_err());
+
+    // Content-Length is mandatory for stream transports
+    let mut decoder = SipStreamDecoder::new();
+    decoder.push(b"SIP/2.0 200 OK\r\nCall-ID: a84b4c76e66710\r\n\r\n");
+    assert!(decoder.decode().is_err());
+}
+
+#[test]
+fn decode_too_long_message() {
+    let mut decoder = SipStreamDecoder::with_max_message_size(100);
+    decoder.push(&INVITE.as_bytes()[..101]);
+    assert!(decoder.decode().is_err());
+
+    let mut decoder = SipStreamDecoder::with_max_message_size(250);
+    decoder.push(
+        "SIP/2.0 200 OK\r\nCall-ID: a84b4c76e66710\r\nContent-Length: 1000\r\n\r\n".as_bytes(),
+    );
+    assert!(decoder.decode().is_err());
+}
@@ -0,0 +1,143 @@
+use sipmsg::*;
+
+const REGISTER: &str = "REGISTER sip:biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7\r\n\
+Max-Forwards: 70\r\n\
+To: Bob <sip:bob@biloxi.com>\r\n\
+From: Bob <sip:bob@biloxi.com>;tag=456248\r\n\
+Call-ID: 843817637684230@998sdasdh09\r\n\
+CSeq: 1826 REGISTER\r\n\
+Contact: <sip:bob@192.0.2.4>\r\n\
+Content-Length: 0\r\n\r\n";
+
+#[test]
+fn digest_challenge_and_response() {
+    let (_, request) = SipRequest::parse(REGISTER.as_bytes()).unwrap();
+    for algorithm in &[
+        SipDigestAlgorithm::Md5,
+        SipDigestAlgorithm::Md5Sess,
+        SipDigestAlgorithm::Sha256,
+        SipDigestAlgorithm::Sha256Sess,
+        SipDigestAlgorithm::Sha512_256,
+        SipDigestAlgorithm::Sha512_256Sess,
+    ] {
+        // Server
+        let mut challenge =
+            SipDigestChallenge::new("biloxi.com", "ea9c8e88df84f1cec4341ae6cbe5a359");
+        challenge.algorithm = *algorithm;
+        challenge.opaque = Some("5ccc069c403ebaf9f0171e9517f40e41");
+        challenge.qop = vec![SipDigestQop::Auth, SipDigestQop::AuthInt];
+        let unauthorized = request
+            .make_response(SipResponseStatusCode::Unauthorized, None, "1410948204")
+            .header(challenge.www_authenticate().unwrap())
+            .build()
+            .unwrap();
+        let buf = SipSerializer::new()
+            .msg_to_vec(&SipMessage::Response(unauthorized))
+            .unwrap();
+
+        // Client
+        let (_, unauthorized) = SipResponse::parse(&buf).unwrap();
+        let www_authenticate = unauthorized
+            .headers
+            .get_rfc_s(SipRFCHeader::WWWAuthenticate)
+            .unwrap();
+        let challenge = SipDigestChallenge::from_header(www_authenticate).unwrap();
+        assert_eq!(challenge.algorithm, *algorithm);
+        let credentials = challenge.credentials(&request, "bob", "zanzibar", "0a4f113b", 1);
+        assert_eq!(credentials.qop, Some(SipDigestQop::Auth));
+        assert_eq!(credentials.uri, "sip:biloxi.com");
+        let mut authorized = request.clone();
+        authorized
+            .headers
+            .push_back(credentials.authorization().unwrap());
+        let buf = SipSerializer::new()
+            .msg_to_vec(&SipMessage::Request(authorized))
+            .unwrap();
+
+        // Server
+        let (_, authorized) = SipRequest::parse(&buf).unwrap();
+        let authorization = authorized
+            .headers
+            .get_rfc_s(SipRFCHeader::Authorization)
+            .unwrap();
+        let credentials = SipDigestCredentials::from_header(authorization).unwrap();
+        assert_eq!(credentials.algorithm, *algorithm);
+        assert_eq!(credentials.nc, Some(1));
+        assert_eq!(credentials.opaque, Some("5ccc069c403ebaf9f0171e9517f40e41"));
+        assert!(credentials.verify(&authorized, "zanzibar"));
+        assert!(!credentials.verify(&authorized, "zanzibar "));
+        let ha1 = algorithm.ha1("bob", "biloxi.com", "zanzibar");
+        assert!(credentials.verify_ha1(&authorized, &ha1));
+
+        // Digest depends on method
+        let mut other_method = authorized.clone();
+        other_method.rl.method = SipMethod::INVITE;
+        assert!(!credentials.verify(&other_method, "zanzibar"));
+    }
+}
+
+#[test]
+fn digest_auth_int() {
+    let (_, request) = SipRequest::parse(REGISTER.as_bytes()).unwrap();
+    let mut challenge = SipDigestChallenge::new("biloxi.com", "ea9c8e88df84f1cec4341ae6cbe5a359");
+    challenge.qop = vec![SipDigestQop::AuthInt];
+
+    let mut request = request.clone();
+    request.body = Some(b"body"[..].into());
+    let credentials = challenge.credentials(&request, "bob", "zanzibar", "0a4f113b", 2);
+    assert_eq!(credentials.qop, Some(SipDigestQop::AuthInt));
+    assert!(credentials.verify(&request, "zanzibar"));
+
+    request.body = Some(b"other body"[..].into());
+    assert!(!credentials.verify(&request, "zanzibar"));
+}
+
+#[test]
+fn digest_from_header() {
+    let hdr = SipHeader::new_owned(
+        "WWW-Authenticate",
+        "Digest realm=\"atlanta.com\", domain=\"sip:ss1.carrier.com\", qop=\"auth, auth-int, x\", \
+         nonce=\"f84f1cec41e6cbe5aea9c8e88d359\", opaque=\"\", stale=FALSE, algorithm=SHA-256-sess",
+    )
+    .unwrap();
+    let challenge = SipDigestChallenge::from_header(&hdr).unwrap();
+    assert_eq!(challenge.realm, "atlanta.com");
+    assert_eq!(challenge.nonce, "f84f1cec41e6cbe5aea9c8e88d359");
+    assert_eq!(challenge.opaque, Some(""));
+    assert_eq!(challenge.algorithm, SipDigestAlgorithm::Sha256Sess);
+    assert_eq!(
+        challenge.qop,
+        vec![SipDigestQop::Auth, SipDigestQop::AuthInt]
+    );
+    assert!(!challenge.stale);
+
+    // Unknown algorithm and other schemes are not supported
+    let hdr = SipHeader::new_owned(
+        "WWW-Authenticate",
+        "Digest realm=\"atlanta.com\", nonce=\"f84f1cec41e6cbe5aea9c8e88d359\", algorithm=SHA-1",
+    )
+    .unwrap();
+    assert_eq!(SipDigestChallenge::from_header(&hdr), None);
+    let hdr = SipHeader::new_owned("WWW-Authenticate", "Basic realm=\"atlanta.com\"").unwrap();
+    assert_eq!(SipDigestChallenge::from_header(&hdr), None);
+
+    // Credentials without qop (rfc2069)
+    let hdr = SipHeader::new_owned(
+        "Proxy-Authorization",
+        "Digest username=\"alice\", realm=\"atlanta.com\", nonce=\"c60f3082ee1212b402a21831ae\", \
+         uri=\"sip:bob@biloxi.com\", response=\"245f23415f11432b3434341c022\"",
+    )
+    .unwrap();
+    let credentials = SipDigestCredentials::from_header(&hdr).unwrap();
+    assert_eq!(credentials.username, "alice");
+    assert_eq!(credentials.uri, "sip:bob@biloxi.com");
+    assert_eq!(credentials.algorithm, SipDigestAlgorithm::Md5);
+    assert_eq!(credentials.qop, None);
+    assert_eq!(credentials.nc, None);
+    assert_eq!(
+        credentials.proxy_authorization().unwrap().value.vstr,
+        "Digest username=\"alice\", realm=\"atlanta.com\", nonce=\"c60f3082ee1212b402a21831ae\", \
+         uri=\"sip:bob@biloxi.com\", response=\"245f23415f11432b3434341c022\", algorithm=MD5"
+    );
+}
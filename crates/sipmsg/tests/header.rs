@@ -74,16 +74,10 @@ fn accept_encoding_header() {
         SipHeader::parse("Accept-Encoding:compress;q=0.5, gzip;q=1.0\r\n".as_bytes()).unwrap();
     assert_eq!(hdrs[0].name, "Accept-Encoding");
     assert_eq!(hdrs[0].value.vstr, "compress");
-    assert_eq!(
-        hdrs[0].params().unwrap().get("q").unwrap(),
-        &Some("0.5")
-    );
+    assert_eq!(hdrs[0].params().unwrap().get("q").unwrap(), Some("0.5"));
     assert_eq!(hdrs[1].name, "Accept-Encoding");
     assert_eq!(hdrs[1].value.vstr, "gzip");
-    assert_eq!(
-        hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("1.0")
-    );
+    assert_eq!(hdrs[1].params().unwrap().get("q").unwrap(), Some("1.0"));
     assert_eq!(input.len(), 2);
 
     let (input, (_, hdrs)) =
@@ -92,24 +86,15 @@ fn accept_encoding_header() {
     assert_eq!(hdrs[0].name, "Accept-Encoding");
     assert_eq!(hdrs[0].value.vstr, "gzip");
     assert_eq!(hdrs[0].raw_value_param, "gzip;q=1.0".as_bytes());
-    assert_eq!(
-        hdrs[0].params().unwrap().get("q").unwrap(),
-        &Some("1.0")
-    );
+    assert_eq!(hdrs[0].params().unwrap().get("q").unwrap(), Some("1.0"));
     assert_eq!(hdrs[1].name, "Accept-Encoding");
     assert_eq!(hdrs[1].raw_value_param, "identity; q=0.5".as_bytes());
     assert_eq!(hdrs[1].value.vstr, "identity");
-    assert_eq!(
-        hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("0.5")
-    );
+    assert_eq!(hdrs[1].params().unwrap().get("q").unwrap(), Some("0.5"));
 
     assert_eq!(hdrs[2].name, "Accept-Encoding");
     assert_eq!(hdrs[2].value.vstr, "*");
-    assert_eq!(
-        hdrs[2].params().unwrap().get("q").unwrap(),
-        &Some("0")
-    );
+    assert_eq!(hdrs[2].params().unwrap().get("q").unwrap(), Some("0"));
     assert_eq!(input.len(), 2);
 
     let (input, (_, hdrs)) = SipHeader::parse("Accept-Encoding: gzip \r\n".as_bytes()).unwrap();
@@ -166,16 +151,10 @@ fn accept_language_header() {
     assert_eq!(hdrs[0].name, "Accept-Language");
     assert_eq!(hdrs[0].value.vstr, "da");
     assert_eq!(hdrs[1].value.vstr, "en-gb");
-    assert_eq!(
-        hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("0.8")
-    );
+    assert_eq!(hdrs[1].params().unwrap().get("q").unwrap(), Some("0.8"));
 
     assert_eq!(hdrs[2].value.vstr, "en");
-    assert_eq!(
-        hdrs[2].params().unwrap().get("q").unwrap(),
-        &Some("0.7")
-    );
+    assert_eq!(hdrs[2].params().unwrap().get("q").unwrap(), Some("0.7"));
 
     assert_eq!(input.len(), 2)
 }
@@ -192,23 +171,23 @@ fn authorization_header() {
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Username],
-        b"bob"
+        &b"bob"[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.example.com"
+        &b"atlanta.example.com"[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"ea9c8e88df84f1cec4341ae6cbe5a359"
+        &b"ea9c8e88df84f1cec4341ae6cbe5a359"[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        &b""[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::DigestUri],
-        b"sips:ss2.biloxi.example.com"
+        &b"sips:ss2.biloxi.example.com"[..]
     );
 
     assert_eq!(input, b"\r\n");
@@ -224,11 +203,11 @@ fn call_id_header() {
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"3848276298220188511"
+        &b"3848276298220188511"[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"atlanta.example.com"
+        &b"atlanta.example.com"[..]
     );
     assert_eq!(input, b"\r\n");
 
@@ -237,7 +216,7 @@ fn call_id_header() {
     assert_eq!(hdrs[0].value.vstr, "3848276298220188511");
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"3848276298220188511"
+        &b"3848276298220188511"[..]
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap().get(&SipHeaderTagType::Host),
@@ -263,10 +242,7 @@ fn callinfo_test() {
         "http://wwww.example.com/alice/photo.jpg".as_bytes()
     );
 
-    assert_eq!(
-        hdrs[0].params().unwrap().get("purpose"),
-        Some(&Some("icon"))
-    );
+    assert_eq!(hdrs[0].params().unwrap().get("purpose"), Some(Some("icon")));
 
     assert_eq!(hdrs[1].value.vstr, "<http://www.example.com/alice/>");
     assert_eq!(
@@ -274,10 +250,7 @@ fn callinfo_test() {
         "http://www.example.com/alice/".as_bytes()
     );
 
-    assert_eq!(
-        hdrs[1].params().unwrap().get("purpose"),
-        Some(&Some("info"))
-    );
+    assert_eq!(hdrs[1].params().unwrap().get("purpose"), Some(Some("info")));
 
     assert_eq!(input, b"\r\n");
 }
@@ -294,11 +267,11 @@ fn content_disposition_header() {
     assert_eq!(hdrs[0].raw_value_param, "attachment; filename=smime.p7s; handling=required".as_bytes());
     assert_eq!(
         hdrs[0].params().unwrap().get("filename").unwrap(),
-        &Some("smime.p7s")
+        Some("smime.p7s")
     );
     assert_eq!(
         hdrs[0].params().unwrap().get("handling").unwrap(),
-        &Some("required")
+        Some("required")
     );
     assert_eq!(input.len(), 2)
 }
@@ -59,7 +59,7 @@ fn parse_headers() {
 
     let to_hdr = hdrs.get_rfc_s(SipRFCHeader::To).unwrap();
     assert_eq!(to_hdr.value.vstr, "David <sip:davidko@biloxi.com>");
-    assert_eq!(to_hdr.params().unwrap().get(&"tag"), Some(&Some("99sa0xk")));
+    assert_eq!(to_hdr.params().unwrap().get("tag"), Some(Some("99sa0xk")));
     assert_eq!(
         to_hdr.value.sip_uri().unwrap().scheme,
         sipuri::RequestUriScheme::SIP
@@ -73,11 +73,11 @@ fn parse_headers() {
 
     let from_hdr = hdrs.get_rfc_s(SipRFCHeader::From).unwrap();
     assert_eq!(from_hdr.value.vstr, "caller <sip:caller2@example.com>");
-    assert_eq!(from_hdr.params().unwrap().get(&"tag"), Some(&Some("323")));
+    assert_eq!(from_hdr.params().unwrap().get("tag"), Some(Some("323")));
 
     assert_eq!(
         from_hdr.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"caller"
+        &b"caller"[..]
     );
     assert_eq!(
         from_hdr.value.sip_uri().unwrap().scheme,
@@ -108,11 +108,11 @@ fn parse_headers() {
     assert_eq!(hdrs.get_rfc_s(SipRFCHeader::CSeq).unwrap().params(), None);
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Number],
-        b"60"
+        &b"60"[..]
     );
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Method],
-        b"OPTIONS"
+        &b"OPTIONS"[..]
     );
 
     assert_eq!(hdrs.get_ext_s("ExtensionHeader").unwrap().value.vstr, "value;param=false");
@@ -120,25 +120,25 @@ fn parse_headers() {
     let via_hdr = hdrs.get_rfc_s(SipRFCHeader::Via).unwrap();
     assert_eq!(via_hdr.value.vstr, "SIP/2.0/UDP funky.example.com");
     assert_eq!(
-        via_hdr.params().unwrap().get(&"branch"),
-        Some(&Some("z9hG4bKkdjuw"))
+        via_hdr.params().unwrap().get("branch"),
+        Some(Some("z9hG4bKkdjuw"))
     );
 
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-        b"SIP"
+        &b"SIP"[..]
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-        b"2.0"
+        &b"2.0"[..]
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"UDP"
+        &b"UDP"[..]
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"funky.example.com"
+        &b"funky.example.com"[..]
     );
     let auth_val = &hdrs.get_rfc_s(SipRFCHeader::Authorization).unwrap().value;
     assert_eq!(
@@ -149,15 +149,15 @@ fn parse_headers() {
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Username],
-        b"Alice"
+        &b"Alice"[..]
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        &b"atlanta.com"[..]
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"84a4cc6f3082121f32b42a2187831a9e"
+        &b"84a4cc6f3082121f32b42a2187831a9e"[..]
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Dresponse],
@@ -169,11 +169,11 @@ fn parse_headers() {
     assert_eq!(content_disp_hdr.value.vstr, "attachment");
     assert_eq!(
         content_disp_hdr.params().unwrap().get("filename").unwrap(),
-        &Some("smime.p7s")
+        Some("smime.p7s")
     );
     assert_eq!(
         content_disp_hdr.params().unwrap().get("handling").unwrap(),
-        &Some("required")
+        Some("required")
     );
 
     let content_language = &hdrs.get_rfc_s(SipRFCHeader::ContentLanguage).unwrap();
@@ -189,7 +189,7 @@ fn parse_headers() {
     assert_eq!(content_type.value.vstr, "text/html");
     assert_eq!(
         content_type.params().unwrap().get("charset").unwrap(),
-        &Some("ISO-8859-4")
+        Some("ISO-8859-4")
     );
 
     let date_hdr = &hdrs.get_rfc_s(SipRFCHeader::Date).unwrap();
@@ -212,20 +212,20 @@ fn parse_headers() {
     assert_eq!(in_reply_hdrs[0].value.vstr, "70710@saturn.bell-tel.com");
     assert_eq!(
         in_reply_hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"70710"
+        &b"70710"[..]
     );
     assert_eq!(
         in_reply_hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"saturn.bell-tel.com"
+        &b"saturn.bell-tel.com"[..]
     );
     assert_eq!(in_reply_hdrs[1].value.vstr, "17320@saturn.bell-tel.com");
     assert_eq!(
         in_reply_hdrs[1].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"17320"
+        &b"17320"[..]
     );
     assert_eq!(
         in_reply_hdrs[1].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"saturn.bell-tel.com"
+        &b"saturn.bell-tel.com"[..]
     );
 
     let organization_header = &hdrs.get_rfc_s(SipRFCHeader::Organization).unwrap();
@@ -244,35 +244,35 @@ fn parse_headers() {
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        &b"Digest"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        &b"atlanta.com"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Domain],
-        b"sip:ss1.carrier.com"
+        &b"sip:ss1.carrier.com"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::QopValue],
-        b"auth"
+        &b"auth"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"f84f1cec41e6cbe5aea9c8e88d359"
+        &b"f84f1cec41e6cbe5aea9c8e88d359"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        &b""[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Stale],
-        b"FALSE"
+        &b"FALSE"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Algorithm],
-        b"MD5"
+        &b"MD5"[..]
     );
 
     let proxy_auth = &hdrs.get_rfc_s(SipRFCHeader::ProxyAuthorization).unwrap();
@@ -284,24 +284,24 @@ fn parse_headers() {
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        &b"Digest"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Username],
-        b"Alice"
+        &b"Alice"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"c60f3082ee1212b402a21831ae"
+        &b"c60f3082ee1212b402a21831ae"[..]
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Dresponse],
-        b"245f23415f11432b3434341c022"
+        &b"245f23415f11432b3434341c022"[..]
     );
 
     let proxy_require_hdr = &hdrs.get_rfc_s(SipRFCHeader::ProxyRequire).unwrap();
     assert_eq!(proxy_require_hdr.value.vstr, "foo");
-    assert_eq!(proxy_require_hdr.params().unwrap().get("boo"), Some(&None));
+    assert_eq!(proxy_require_hdr.params().unwrap().get("boo"), Some(None));
 
     let record_route_headers = &hdrs.get_rfc(SipRFCHeader::RecordRoute).unwrap();
 
@@ -330,7 +330,7 @@ fn parse_headers() {
             .params()
             .unwrap()
             .get("lr"),
-        Some(&None)
+        Some(None)
     );
     assert_eq!(
         record_route_headers[1].value.vstr,
@@ -357,7 +357,7 @@ fn parse_headers() {
             .params()
             .unwrap()
             .get("lr"),
-        Some(&None)
+        Some(None)
     );
 
     let route_headers = &hdrs.get_rfc(SipRFCHeader::Route).unwrap();
@@ -406,7 +406,7 @@ fn parse_headers() {
     assert_eq!(reply_to_header.value.vstr, "Bob <sip:bob@biloxi.com>");
     assert_eq!(
         reply_to_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Bob"
+        &b"Bob"[..]
     );
     assert_eq!(
         reply_to_header.value.sip_uri().unwrap().scheme,
@@ -442,8 +442,8 @@ fn parse_headers() {
     );
 
     assert_eq!(
-        retry_after_hdr.params().unwrap().get(&"duration"),
-        Some(&Some("3600"))
+        retry_after_hdr.params().unwrap().get("duration"),
+        Some(Some("3600"))
     );
 
     let server_hdr = &hdrs.get_rfc_s(SipRFCHeader::Server).unwrap();
@@ -472,11 +472,11 @@ fn parse_headers() {
 
     assert_eq!(
         supported_hdr.value.tags().unwrap()[&SipHeaderTagType::Major],
-        b"1"
+        &b"1"[..]
     );
     assert_eq!(
         supported_hdr.value.tags().unwrap()[&SipHeaderTagType::Minor],
-        b"0"
+        &b"0"[..]
     );
 
     let min_exp_hdr = &hdrs.get_rfc_s(SipRFCHeader::MinExpires).unwrap();
@@ -486,7 +486,7 @@ fn parse_headers() {
     assert_eq!(timestamp_hdr.value.vstr, "54");
     assert_eq!(
         timestamp_hdr.value.tags().unwrap()[&SipHeaderTagType::TimveVal],
-        b"54"
+        &b"54"[..]
     );
 
     let warn_hdr = &hdrs.get_rfc_s(SipRFCHeader::Warning).unwrap();
@@ -501,11 +501,11 @@ fn parse_headers() {
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnCode],
-        b"301"
+        &b"301"[..]
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnAgent],
-        b"isi.edu"
+        &b"isi.edu"[..]
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnText],
@@ -522,36 +522,203 @@ fn parse_headers() {
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        &b"Digest"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        &b"atlanta.com"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Domain],
-        b"sip:boxesbybob.com"
+        &b"sip:boxesbybob.com"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::QopValue],
-        b"auth"
+        &b"auth"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"f84f1cec41e6cbe5aea9c8e88d359"
+        &b"f84f1cec41e6cbe5aea9c8e88d359"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        &b""[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Stale],
-        b"FALSE"
+        &b"FALSE"[..]
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Algorithm],
-        b"MD5"
+        &b"MD5"[..]
     );
 
     assert_eq!(input, "\r\nsomebody".as_bytes());
 }
+
+#[test]
+fn typed_headers() {
+    let (_, headers) = SipHeaders::parse(
+        "Via: SIP/2.0/TLS [2001:db8::9]:5061;branch=z9hG4bK1;maddr=224.2.0.1;ttl=16\r\n\
+         v: SIP/2.0/UDP 192.0.2.1;branch=z9hG4bK2;rport=5060;received=192.0.2.3\r\n\
+         t: Bob <sip:bob@biloxi.com>\r\n\
+         From: \"Alice A\" <sips:alice@atlanta.com>;tag=323;x=y\r\n\
+         Call-ID: lwsdisp.1234abcd@funky.example.com\r\n\
+         CSeq: 60 OPTIONS\r\n\
+         Contact: <sip:alice@pc33.atlanta.com>;expires=60, <mailto:alice@atlanta.com>\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let vias: Vec<SipVia> = headers.vias().collect();
+    assert_eq!(vias.len(), 2);
+    assert_eq!(vias[0].protocol_name, "SIP");
+    assert_eq!(vias[0].protocol_version, "2.0");
+    assert_eq!(vias[0].transport, "TLS");
+    assert_eq!(vias[0].host, "2001:db8::9");
+    assert_eq!(vias[0].port, Some(5061));
+    assert_eq!(vias[0].maddr, Some("224.2.0.1"));
+    assert_eq!(vias[0].ttl, Some(16));
+    assert_eq!(vias[0].rport, None);
+    assert_eq!(vias[1].port, None);
+    assert_eq!(vias[1].branch, Some("z9hG4bK2"));
+    assert_eq!(vias[1].rport, Some(Some(5060)));
+    assert_eq!(vias[1].received, Some("192.0.2.3"));
+    assert_eq!(headers.top_via().unwrap(), vias[0]);
+
+    let cseq = headers.cseq().unwrap();
+    assert_eq!(cseq.seq, 60);
+    assert_eq!(cseq.method, SipMethod::OPTIONS);
+
+    let call_id = headers.call_id().unwrap();
+    assert_eq!(call_id.value, "lwsdisp.1234abcd@funky.example.com");
+    assert_eq!(call_id.id, "lwsdisp.1234abcd");
+    assert_eq!(call_id.host, Some("funky.example.com"));
+
+    let from = headers.from_addr().unwrap();
+    assert_eq!(from.display_name, Some("Alice A"));
+    assert_eq!(
+        from.uri.sip_uri().unwrap().scheme,
+        SipRequestUriScheme::SIPS
+    );
+    assert_eq!(from.tag, Some("323"));
+    assert_eq!(from.params.unwrap().get("x"), Some(Some("y")));
+    let to = headers.to_addr().unwrap();
+    assert_eq!(to.display_name, Some("Bob"));
+    assert_eq!(to.uri.sip_uri().unwrap().user_info().unwrap().value, "bob");
+    assert_eq!(to.tag, None);
+
+    let contacts: Vec<SipNameAddr> = headers.contacts().collect();
+    assert_eq!(contacts.len(), 2);
+    assert_eq!(
+        contacts[0].uri.sip_uri().unwrap().hostport.host,
+        "pc33.atlanta.com"
+    );
+    assert_eq!(contacts[0].params.unwrap().get("expires"), Some(Some("60")));
+    assert_eq!(contacts[1].uri.to_string(), "mailto:alice@atlanta.com");
+
+    // Header of other type
+    let cseq_hdr = headers.get_rfc_s(SipRFCHeader::CSeq).unwrap();
+    assert_eq!(SipVia::from_header(cseq_hdr), None);
+    assert_eq!(SipNameAddr::from_header(cseq_hdr), None);
+    let invalid = SipHeader::new_owned("Via", "SIP/2.0/UDP 192.0.2.1;ttl=256").unwrap();
+    assert_eq!(SipVia::from_header(&invalid), None);
+    let invalid = SipHeader::new_owned("CSeq", "4294967295 INVITE").unwrap();
+    assert_eq!(SipCSeq::from_header(&invalid), None);
+}
+
+fn uri_hash(uri: &SipUri) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    uri.hash_equivalent(&mut hasher);
+    hasher.finish()
+}
+
+fn assert_uri_equivalent(a: &str, b: &str, expected: bool) {
+    let (_, a) = SipUri::parse(a.as_bytes()).unwrap();
+    let (_, b) = SipUri::parse(b.as_bytes()).unwrap();
+    assert_eq!(a.equivalent(&b), expected, "{} vs {}", a, b);
+    assert_eq!(b.equivalent(&a), expected, "{} vs {}", b, a);
+    if expected {
+        assert_eq!(uri_hash(&a), uri_hash(&b));
+    }
+}
+
+#[test]
+fn sip_uri_equivalence() {
+    // rfc3261 section-19.1.4
+    assert_uri_equivalent(
+        "sip:%61lice@atlanta.com;transport=TCP",
+        "sip:alice@AtLanTa.CoM;Transport=tcp",
+        true,
+    );
+    assert_uri_equivalent(
+        "sip:carol@chicago.com",
+        "sip:carol@chicago.com;newparam=5",
+        true,
+    );
+    assert_uri_equivalent(
+        "sip:carol@chicago.com",
+        "sip:carol@chicago.com;security=on",
+        true,
+    );
+    assert_uri_equivalent(
+        "sip:carol@chicago.com;newparam=5",
+        "sip:carol@chicago.com;security=on",
+        true,
+    );
+    assert_uri_equivalent(
+        "sip:biloxi.com;transport=tcp;method=REGISTER?to=sip:bob%40biloxi.com",
+        "sip:biloxi.com;method=REGISTER;transport=tcp?to=sip:bob%40biloxi.com",
+        true,
+    );
+    assert_uri_equivalent(
+        "sip:alice@atlanta.com?subject=project%20x&priority=urgent",
+        "sip:alice@atlanta.com?priority=urgent&subject=project%20x",
+        true,
+    );
+
+    assert_uri_equivalent(
+        "SIP:ALICE@AtLanTa.CoM;Transport=udp",
+        "sip:alice@AtLanTa.CoM;Transport=UDP",
+        false,
+    );
+    assert_uri_equivalent("sip:bob@biloxi.com", "sip:bob@biloxi.com:5060", false);
+    assert_uri_equivalent(
+        "sip:bob@biloxi.com",
+        "sip:bob@biloxi.com;transport=udp",
+        false,
+    );
+    assert_uri_equivalent(
+        "sip:bob@biloxi.com",
+        "sip:bob@biloxi.com:6000;transport=tcp",
+        false,
+    );
+    assert_uri_equivalent(
+        "sip:carol@chicago.com",
+        "sip:carol@chicago.com?Subject=next%20meeting",
+        false,
+    );
+    assert_uri_equivalent("sip:bob@phone21.boxesbybob.com", "sip:bob@192.0.2.4", false);
+    assert_uri_equivalent(
+        "sip:carol@chicago.com;security=on",
+        "sip:carol@chicago.com;security=off",
+        false,
+    );
+
+    assert_uri_equivalent("sip:alice@atlanta.com", "sips:alice@atlanta.com", false);
+    assert_uri_equivalent("sip:alice@atlanta.com", "sip:atlanta.com", false);
+    assert_uri_equivalent("sip:alice:pw@atlanta.com", "sip:alice@atlanta.com", false);
+    assert_uri_equivalent("sip:alice@atlanta.com;lr", "sip:alice@atlanta.com;LR", true);
+    assert_uri_equivalent(
+        "sip:alice@atlanta.com;ttl=1",
+        "sip:alice@atlanta.com;maddr=a",
+        false,
+    );
+    assert_uri_equivalent(
+        "sip:alice@atlanta.com;x=%61",
+        "sip:alice@atlanta.com;X=A",
+        true,
+    );
+    assert_uri_equivalent("sip:a%2cb@atlanta.com", "sip:a,b@atlanta.com", false);
+}
@@ -18,7 +18,10 @@ Content-Type: application/sdp\r\n\
 Content-Length: 0\r\n\r\n".as_bytes();
     let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
     let sip_req = sip_msg.request().unwrap();
-    assert_eq!(sip_req.rl.uri.user_info().unwrap().value, "001234567890");
+    assert_eq!(
+        sip_req.rl.uri.sip_uri().unwrap().user_info().unwrap().value,
+        "001234567890"
+    );
 }
 
 #[test]
@@ -43,6 +46,388 @@ fn get_message_type() {
     );
     assert_eq!(
         sipmsg::get_sip_message_type("NEWMETHOD sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Request
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("SIP/2.0 200 OK".as_bytes()),
+        SipMessageType::Response
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("INV".as_bytes()),
+        SipMessageType::Unknown
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type(" INVITE sip:user@example.com SIP/2.0".as_bytes()),
         SipMessageType::Unknown
     );
+    assert_eq!(
+        sipmsg::get_sip_message_type("INV@ITE sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Unknown
+    );
+}
+
+#[test]
+fn parse_extension_method() {
+    let buf = "NEGOTIATE sip:user@example.com SIP/2.0\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 1 NEGOTIATE\r\n\
+Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let request = msg.request().unwrap();
+    let method = SipMethod::Extension("NEGOTIATE".to_string());
+    assert_eq!(request.rl.method, method);
+    assert_eq!(request.rl.method.as_str(), "NEGOTIATE");
+    assert_eq!(request.headers.cseq().unwrap().method, method);
+    assert_eq!(SipSerializer::new().msg_to_vec(&msg).unwrap(), buf);
+}
+
+#[test]
+fn parse_pipelined_messages() {
+    let buf = "OPTIONS sip:user@example.com SIP/2.0\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 1 OPTIONS\r\n\
+Content-Length: 4\r\n\r\n\
+bodySIP/2.0 200 OK\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 1 OPTIONS\r\n\
+l: 0\r\n\r\n\
+INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Content-Length: 3\r\n\r\n\
+abcdef"
+        .as_bytes();
+
+    let (rest, msg) = SipMessage::parse(buf).unwrap();
+    assert_eq!(msg.request().unwrap().rl.method, SipMethod::OPTIONS);
+    assert_eq!(msg.request().unwrap().body.as_deref().unwrap(), b"body");
+
+    let (rest, msg) = SipMessage::parse(rest).unwrap();
+    assert_eq!(
+        msg.response().unwrap().sl.status_code,
+        SipResponseStatusCode::OK
+    );
+    assert_eq!(msg.response().unwrap().body.as_deref().unwrap(), b"");
+
+    let (rest, msg) = SipMessage::parse(rest).unwrap();
+    assert_eq!(msg.request().unwrap().rl.method, SipMethod::INVITE);
+    assert_eq!(msg.request().unwrap().body.as_deref().unwrap(), b"abc");
+    assert_eq!(rest, b"def");
+}
+
+#[test]
+fn parse_message_without_content_length() {
+    let buf = "MESSAGE sip:kumiko@example.org SIP/2.0\r\n\
+Via: SIP/2.0/UDP 192.0.2.1;branch=z9hG4bK776asdhds\r\n\r\n\
+Hello world"
+        .as_bytes();
+    let (rest, msg) = SipMessage::parse(buf).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        msg.request().unwrap().body.as_deref().unwrap(),
+        b"Hello world"
+    );
+}
+
+#[test]
+fn parse_message_truncated_body() {
+    let buf = "SIP/2.0 200 OK\r\n\
+Via: SIP/2.0/TCP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Content-Length: 10\r\n\r\n\
+12345"
+        .as_bytes();
+    match SipMessage::parse(buf) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::TruncatedBody);
+            assert_eq!(e.header, Some("Content-Length"));
+            assert_eq!(e.line, Some(5));
+            assert_eq!(e.offset, Some(97));
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn parse_message_invalid_content_length() {
+    let buf = "SIP/2.0 200 OK\r\n\
+Content-Length: 2\r\n\
+Content-Length: 3\r\n\r\n\
+123"
+    .as_bytes();
+    assert!(SipMessage::parse(buf).is_err());
+}
+
+fn parse_error(buf: &[u8]) -> errorparse::SipParseError<'_> {
+    match SipMessage::parse(buf) {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn parse_message_error_position() {
+    let e = parse_error(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nFrom: <sip:alice@>\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::Uri);
+    assert_eq!(e.header, Some("From"));
+    assert_eq!(e.line, Some(2));
+
+    let e =
+        parse_error(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nCall-ID: a84b\r\nBad Header: x\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::HeaderName);
+    assert_eq!((e.line, e.offset), (Some(3), Some(54)));
+    assert_eq!(e.header, None);
+
+    let e = parse_error(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nCSeq: 1 INVITE x\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::HeaderValue);
+    assert_eq!(e.header, Some("CSeq"));
+    assert_eq!((e.line, e.offset), (Some(2), Some(50)));
+
+    let e = parse_error(b"INVITE sip:bob@[::1 SIP/2.0\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::Uri);
+    assert_eq!((e.line, e.offset), (Some(1), Some(16)));
+
+    let e = parse_error(b"INVITE sip:bob@biloxi.com SIP/2.x\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::RequestLine);
+    assert_eq!(e.offset, Some(32));
+
+    let e = parse_error(b"SIP/2.0 2000 OK\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::StatusLine);
+    assert_eq!(e.offset, Some(11));
+
+    let e = parse_error(b"SIP/2.0 200 OK\r\nContent-Length: x\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::HeaderValue);
+    assert_eq!(e.header, Some("Content-Length"));
+
+    let e = parse_error(b"SIP/2.0 200 OK\r\nl: 0\r\nContent-Length: 1\r\n\r\n1");
+    assert_eq!(e.kind, SipParseErrorKind::ContentLength);
+    assert_eq!((e.line, e.offset), (Some(5), Some(43)));
+
+    let e = parse_error(b"HELLO\r\n\r\n");
+    assert_eq!(e.kind, SipParseErrorKind::UnknownMessage);
+    assert_eq!((e.line, e.offset), (Some(1), Some(0)));
+}
+
+#[test]
+fn owned_message() {
+    let msg_buf = "SIP/2.0 200 OK\r\n\
+                   Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+                   To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+                   From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+                   Call-ID: a84b4c76e66710\r\n\
+                   X-Custom: value\r\n\
+                   CSeq: 314159 INVITE\r\n\
+                   Content-Length: 4\r\n\r\n\
+                   body"
+        .to_string()
+        .into_bytes();
+    let expected = msg_buf.clone();
+    let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+    let owned: SipMessage<'static> = msg.into_owned();
+    drop(msg_buf);
+
+    let serialized = std::thread::spawn(move || {
+        let response = owned.response().unwrap();
+        assert_eq!(response.sl.reason_phrase, "OK");
+        assert_eq!(response.body.as_deref().unwrap(), b"body");
+        assert_eq!(
+            response.headers.get_ext_s("x-custom").unwrap().value.vstr,
+            "value"
+        );
+        SipSerializer::new().msg_to_vec(&owned).unwrap()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(serialized, expected);
+}
+
+#[test]
+fn parse_message_options() {
+    let buf = b"sip/2.0 180 Ringing  \n\
+Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK776asdhds\n\
+From: \"Caf\xe9\" <sip:alice@atlanta.com>;tag=1\n\
+To: <sip:bob@biloxi.com>\n\
+To: <sip:bob@biloxi.com>\n\
+Subject: folded\n subject\n\
+Content-Length: 4\n\n\
+body";
+    assert!(SipMessage::parse(buf).is_err());
+    let (rest, msg) = SipMessage::parse_ext(buf, &SipParseOptions::lenient()).unwrap();
+    assert!(rest.is_empty());
+    let response = msg.response().unwrap();
+    assert_eq!(response.sl.sip_version, SipVersion(2, 0));
+    assert_eq!(response.sl.reason_phrase, "Ringing");
+    assert_eq!(response.headers.iter().count(), 6);
+    let from = response.headers.get_rfc_s(SipRFCHeader::From).unwrap();
+    assert_eq!(from.value.vstr, "\"Caf\u{fffd}\" <sip:alice@atlanta.com>");
+    assert_eq!(
+        response
+            .headers
+            .get_rfc_s(SipRFCHeader::Subject)
+            .unwrap()
+            .value
+            .vstr,
+        "folded\n subject"
+    );
+    assert_eq!(response.body.as_deref().unwrap(), b"body");
+
+    let strict = SipParseOptions::strict();
+    let check_tolerance = |buf: &[u8], options: SipParseOptions| {
+        assert!(SipMessage::parse_ext(buf, &strict).is_err());
+        assert!(SipMessage::parse_ext(buf, &options).is_ok());
+    };
+    check_tolerance(
+        b"OPTIONS sip:bob@biloxi.com SIP/2.0\nCall-ID: a84b\n\n",
+        SipParseOptions {
+            allow_bare_lf: true,
+            ..strict
+        },
+    );
+    check_tolerance(
+        b"OPTIONS sip:bob@biloxi.com SIP/2.0 \r\nCall-ID: a84b\r\n\r\n",
+        SipParseOptions {
+            allow_trailing_whitespace: true,
+            ..strict
+        },
+    );
+    check_tolerance(
+        b"OPTIONS sip:bob@biloxi.com sip/2.0\r\nCall-ID: a84b\r\n\r\n",
+        SipParseOptions {
+            allow_lowercase_version: true,
+            ..strict
+        },
+    );
+    check_tolerance(
+        b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nCall-ID: a84b\r\nCall-ID: a84b\r\n\r\n",
+        SipParseOptions {
+            allow_duplicate_headers: true,
+            ..strict
+        },
+    );
+    // Repeated single-valued headers are rejected only on request
+    let buf = b"SIP/2.0 200 OK\r\nl: 0\r\nContent-Length: 0\r\n\r\n";
+    assert!(SipMessage::parse(buf).is_ok());
+    match SipMessage::parse_ext(buf, &strict) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::DuplicateHeader);
+            assert_eq!(e.header, Some("Content-Length"));
+            assert_eq!((e.line, e.offset), (Some(3), Some(22)));
+        }
+        _ => panic!(),
+    }
+    check_tolerance(
+        b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nTo: \"\xff\" <sip:bob@biloxi.com>\r\n\r\n",
+        SipParseOptions {
+            allow_invalid_utf8: true,
+            ..strict
+        },
+    );
+}
+
+#[test]
+fn parse_message_limits() {
+    let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+Call-ID: a84b4c76e66710\r\n\
+Max-Forwards: 70\r\n\
+CSeq: 1 OPTIONS\r\n\r\n";
+    assert!(SipMessage::parse(buf).is_ok());
+
+    let options = SipParseOptions {
+        max_headers: Some(2),
+        ..SipParseOptions::strict()
+    };
+    match SipMessage::parse_ext(buf, &options) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::TooManyHeaders);
+            assert_eq!(e.line, Some(4));
+        }
+        _ => panic!(),
+    }
+
+    let options = SipParseOptions {
+        max_line_length: Some(20),
+        ..SipParseOptions::strict()
+    };
+    match SipMessage::parse_ext(buf, &options) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::LineTooLong);
+            assert_eq!((e.line, e.offset), (Some(1), Some(20)));
+        }
+        _ => panic!(),
+    }
+    let options = SipParseOptions {
+        max_line_length: Some(34),
+        ..SipParseOptions::strict()
+    };
+    assert!(SipMessage::parse_ext(buf, &options).is_ok());
+}
+
+#[test]
+fn parse_message_lazy_headers() {
+    let buf = b"INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+Contact: <sip:alice@pc33.atlanta.com\r\n\
+Call-ID: a84b4c76e66710\r\n\
+CSeq: 314159 INVITE\r\n\
+Content-Length: 4\r\n\r\nbody";
+    assert!(SipMessage::parse(buf).is_err());
+
+    let options = SipParseOptions {
+        lazy_headers: true,
+        ..SipParseOptions::strict()
+    };
+    let (rest, request) = SipRequest::parse_ext(buf, &options).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(request.body.as_deref(), Some(&b"body"[..]));
+    // Single-valued headers are parsed in strict mode
+    assert_eq!(request.headers.cseq().unwrap().seq, 314159);
+    assert_eq!(
+        request.headers.top_via().unwrap().branch,
+        Some("z9hG4bKnashds8")
+    );
+    assert!(request.headers.get_rfc(SipRFCHeader::Contact).is_none());
+    assert_eq!(request.headers.iter().count(), 4);
+    assert!(!request
+        .validate()
+        .contains(&SipViolation::MissingHeader(SipRFCHeader::Via)));
+    // Invalid line is forwarded as is
+    let msg = SipMessage::Request(request.clone());
+    assert_eq!(SipSerializer::new().msg_to_vec(&msg).unwrap(), &buf[..]);
+
+    let mut headers = request.headers.clone();
+    let e = headers.parse_all().unwrap_err();
+    assert_eq!(e.kind, SipParseErrorKind::HeaderValue);
+    assert_eq!(e.header, Some("Contact"));
+    assert_eq!(headers.iter().count(), 4);
+    assert_eq!(headers.invalid_headers().len(), 1);
+
+    let options = SipParseOptions {
+        allow_invalid_headers: true,
+        ..options
+    };
+    let (_, mut request) = SipRequest::parse_ext(buf, &options).unwrap();
+    assert!(request.headers.parse_all().is_ok());
+    assert_eq!(request.headers.invalid_headers()[0].raw, &buf[92..128]);
+
+    let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nContent-Length: x\r\n\r\n";
+    match SipMessage::parse_ext(buf, &options) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::HeaderValue);
+            assert_eq!(e.line, Some(2));
+        }
+        _ => panic!(),
+    }
+
+    // Lazy and eager parsers reject the same duplicates
+    let buf = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nCSeq: 1 OPTIONS, 2 OPTIONS\r\n\r\n";
+    match SipMessage::parse_ext(buf, &options) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(e.kind, SipParseErrorKind::DuplicateHeader);
+            assert_eq!(e.line, Some(2));
+        }
+        _ => panic!(),
+    }
+    assert!(SipMessage::parse_ext(buf, &SipParseOptions::strict()).is_err());
 }
@@ -20,9 +20,25 @@ fn parse_request() {
 
     assert_eq!(parsed_req.rl.raw, "INVITE sip:bob@biloxi.com SIP/2.0\r\n".as_bytes());
     assert_eq!(parsed_req.rl.method, SipMethod::INVITE);
-    assert_eq!(parsed_req.rl.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(parsed_req.rl.uri.user_info().unwrap().value, "bob");
-    assert_eq!(parsed_req.rl.uri.hostport.host, "biloxi.com");
+    assert_eq!(
+        parsed_req.rl.uri.sip_uri().unwrap().scheme,
+        SipRequestUriScheme::SIP
+    );
+    assert_eq!(
+        parsed_req
+            .rl
+            .uri
+            .sip_uri()
+            .unwrap()
+            .user_info()
+            .unwrap()
+            .value,
+        "bob"
+    );
+    assert_eq!(
+        parsed_req.rl.uri.sip_uri().unwrap().hostport.host,
+        "biloxi.com"
+    );
     assert_eq!(parsed_req.rl.sip_version, SipVersion(2, 0));
 
     assert_eq!(parsed_req.headers.len(), 9);
@@ -43,7 +59,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"branch"),
-        Some(&Some("z9hG4bKkjshdyff"))
+        Some(Some("z9hG4bKkjshdyff"))
     );
     assert_eq!(
         parsed_req
@@ -71,7 +87,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"tag"),
-        Some(&Some("88sja8x"))
+        Some(Some("88sja8x"))
     );
     assert_eq!(
         parsed_req
@@ -81,7 +97,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"onemore"),
-        Some(&None)
+        Some(None)
     );
 
     assert_eq!(
@@ -132,7 +148,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"q"),
-        Some(&Some("0.1"))
+        Some(Some("0.1"))
     );
 
     let callinfo_headers = parsed_req.headers.get_rfc(SipRFCHeader::CallInfo).unwrap();
@@ -148,7 +164,7 @@ fn parse_request() {
 
     assert_eq!(
         callinfo_headers[0].params().unwrap().get("purpose"),
-        Some(&Some("icon"))
+        Some(Some("icon"))
     );
 
     assert_eq!(
@@ -162,13 +178,13 @@ fn parse_request() {
 
     assert_eq!(
         callinfo_headers[1].params().unwrap().get("purpose"),
-        Some(&Some("info"))
+        Some(Some("info"))
     );
 
     let contact_header = parsed_req.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
     assert_eq!(
         contact_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Caller"
+        &b"Caller"[..]
     );
     assert_eq!(
         contact_header
@@ -192,10 +208,10 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"transport"),
-        Some(&Some("tcp"))
+        Some(Some("tcp"))
     );
 
-    assert_eq!(parsed_req.body.unwrap(), "body_stuff".as_bytes())
+    assert_eq!(parsed_req.body.as_deref().unwrap(), "body_stuff".as_bytes())
 }
 
 #[test]
@@ -204,10 +220,10 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::OPTIONS);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip_uri().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "user");
-    assert_eq!(rl.uri.hostport.host, "example.com");
+    assert_eq!(rl.uri.sip_uri().unwrap().user_info().unwrap().value, "user");
+    assert_eq!(rl.uri.sip_uri().unwrap().hostport.host, "example.com");
 
     let res = SipRequestLine::parse(
         "INVITE sips:vivekg@chair-dnrc.example.com;unknownparam SIP/2.0\r\n".as_bytes(),
@@ -215,26 +231,288 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::INVITE);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIPS);
+    assert_eq!(rl.uri.sip_uri().unwrap().scheme, SipRequestUriScheme::SIPS);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(rl.uri.hostport.host, "chair-dnrc.example.com");
-    assert_eq!(rl.uri.params().unwrap().get(&"unknownparam"), Some(&None));
+    assert_eq!(
+        rl.uri.sip_uri().unwrap().user_info().unwrap().value,
+        "vivekg"
+    );
+    assert_eq!(
+        rl.uri.sip_uri().unwrap().hostport.host,
+        "chair-dnrc.example.com"
+    );
+    assert_eq!(
+        rl.uri
+            .sip_uri()
+            .unwrap()
+            .params()
+            .unwrap()
+            .get("unknownparam"),
+        Some(None)
+    );
 
     let res = SipRequestLine::parse("REGISTER sip:[2001:db8::10]:9999 SIP/3.1\r\n".as_bytes());
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::REGISTER);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip_uri().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(3, 1));
-    assert_eq!(rl.uri.hostport.host, "2001:db8::10");
-    assert_eq!(rl.uri.hostport.port.unwrap(), 9999);
+    assert_eq!(rl.uri.sip_uri().unwrap().hostport.host, "2001:db8::10");
+    assert_eq!(rl.uri.sip_uri().unwrap().hostport.port.unwrap(), 9999);
+
+    let res = SipRequestLine::parse("PING sip:example.com SIP/2.0\r\n".as_bytes());
+    let (_, rl) = res.unwrap();
+    assert_eq!(rl.method, SipMethod::Extension("PING".to_string()));
 }
 
 #[test]
 fn get_method_type_fail() {
-    match SipRequestLine::parse("OPTI2ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
+    match SipRequestLine::parse("OPTI@ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
         Ok((_, _)) => panic!(),
         Err(_e) => (),
     }
 }
+
+#[test]
+fn make_response() {
+    let invite = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                  Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+                  Max-Forwards: 70\r\n\
+                  Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+                  To: Bob <sip:bob@biloxi.com>\r\n\
+                  From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+                  Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+                  CSeq: 314159 INVITE\r\n\
+                  Timestamp: 54\r\n\
+                  Contact: <sip:alice@pc33.atlanta.com>\r\n\
+                  Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, request) = SipRequest::parse(invite).unwrap();
+
+    let trying = request
+        .make_response(SipResponseStatusCode::Trying, None, "a6c85cf")
+        .build()
+        .unwrap();
+    assert_eq!(
+        SipSerializer::new()
+            .msg_to_vec(&SipMessage::Response(trying))
+            .unwrap(),
+        "SIP/2.0 100 Trying\r\n\
+         Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+         Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+         To: Bob <sip:bob@biloxi.com>\r\n\
+         From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+         Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+         CSeq: 314159 INVITE\r\n\
+         Timestamp: 54\r\n\
+         Content-Length: 0\r\n\r\n"
+            .as_bytes()
+    );
+
+    let (_, contact) = SipUri::parse(b"sip:bob@192.0.2.4").unwrap();
+    let ok = request
+        .make_response(SipResponseStatusCode::OK, Some("Fine"), "a6c85cf")
+        .contact(&contact)
+        .allow(&[SipMethod::INVITE, SipMethod::ACK, SipMethod::BYE])
+        .supported(&["replaces"])
+        .warning(399, "biloxi.com", "Say \"hi\"")
+        .build()
+        .unwrap();
+    assert_eq!(
+        SipSerializer::new()
+            .msg_to_vec(&SipMessage::Response(ok))
+            .unwrap(),
+        "SIP/2.0 200 Fine\r\n\
+         Via: SIP/2.0/UDP server10.biloxi.com;branch=z9hG4bKnashds8\r\n\
+         Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+         To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+         From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+         Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+         CSeq: 314159 INVITE\r\n\
+         Contact: <sip:bob@192.0.2.4>\r\n\
+         Allow: INVITE\r\n\
+         Allow: ACK\r\n\
+         Allow: BYE\r\n\
+         Supported: replaces\r\n\
+         Warning: 399 biloxi.com \"Say \\\"hi\\\"\"\r\n\
+         Content-Length: 0\r\n\r\n"
+            .as_bytes()
+    );
+
+    assert_eq!(
+        request
+            .make_response(SipResponseStatusCode::BusyHere, None, "a b")
+            .build()
+            .err(),
+        Some(SipBuildError::InvalidHeader(SipRFCHeader::To))
+    );
+
+    // To tag of in-dialog request is kept
+    let bye = "BYE sip:alice@pc33.atlanta.com SIP/2.0\r\n\
+               Via: SIP/2.0/UDP 192.0.2.4;branch=z9hG4bKnashds10\r\n\
+               Max-Forwards: 70\r\n\
+               From: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+               To: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+               Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+               CSeq: 231 BYE\r\n\
+               Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, request) = SipRequest::parse(bye).unwrap();
+    let ok = request
+        .make_response(SipResponseStatusCode::OK, None, "other")
+        .build()
+        .unwrap();
+    let to = ok.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+    assert_eq!(to.params().unwrap().get("tag"), Some(Some("1928301774")));
+}
+
+#[test]
+fn make_response_date() {
+    let register = "REGISTER sip:registrar.biloxi.com SIP/2.0\r\n\
+                    Via: SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7\r\n\
+                    Max-Forwards: 70\r\n\
+                    To: Bob <sip:bob@biloxi.com>\r\n\
+                    From: Bob <sip:bob@biloxi.com>;tag=456248\r\n\
+                    Call-ID: 843817637684230@998sdasdh09\r\n\
+                    CSeq: 1826 REGISTER\r\n\
+                    Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, request) = SipRequest::parse(register).unwrap();
+    let date = SipDateTime::new(2010, 11, 13, 23, 29, 0).unwrap();
+    let ok = request
+        .make_response(SipResponseStatusCode::OK, None, "2493k59kd")
+        .date(date)
+        .build()
+        .unwrap();
+    let serialized = SipSerializer::new()
+        .msg_to_vec(&SipMessage::Response(ok))
+        .unwrap();
+    let line = b"Date: Sat, 13 Nov 2010 23:29:00 GMT\r\n";
+    assert!(serialized.windows(line.len()).any(|w| w == line));
+
+    let (_, response) = SipResponse::parse(&serialized).unwrap();
+    assert_eq!(response.headers.date(), Some(date));
+}
+
+#[test]
+fn parse_request_non_sip_uri() {
+    let invite = "INVITE tel:+1-201-555-0123;ext=22 SIP/2.0\r\n\
+                  Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+                  Max-Forwards: 70\r\n\
+                  To: <tel:7042;phone-context=example.com>\r\n\
+                  From: Emergency <urn:service:sos>;tag=1928301774\r\n\
+                  Contact: <mailto:alice@atlanta.com>, tel:+1-201-555-0100;expires=60\r\n\
+                  Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+                  CSeq: 314159 INVITE\r\n\
+                  Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, request) = SipRequest::parse(invite).unwrap();
+    let tel = request.rl.uri.tel_uri().unwrap();
+    assert!(tel.is_global());
+    assert_eq!(tel.number(), "+1-201-555-0123");
+    assert_eq!(tel.ext(), Some("22"));
+    assert_eq!(request.rl.uri.to_string(), "tel:+1-201-555-0123;ext=22");
+
+    let to = request.headers.to_addr().unwrap();
+    let to_uri = to.uri.tel_uri().unwrap();
+    assert_eq!(to_uri.number(), "7042");
+    assert_eq!(to_uri.phone_context(), Some("example.com"));
+
+    let from = request.headers.from_addr().unwrap();
+    assert_eq!(from.display_name, Some("Emergency"));
+    assert_eq!(from.uri.absolute_uri().unwrap().scheme, "urn");
+    assert_eq!(from.uri.absolute_uri().unwrap().value, "service:sos");
+    assert_eq!(from.tag, Some("1928301774"));
+
+    let contacts: Vec<SipNameAddr> = request.headers.contacts().collect();
+    assert_eq!(contacts.len(), 2);
+    assert_eq!(contacts[0].uri.scheme(), "mailto");
+    assert_eq!(
+        contacts[1].uri.tel_uri().unwrap().number(),
+        "+1-201-555-0100"
+    );
+    assert_eq!(contacts[1].params.unwrap().get("expires"), Some(Some("60")));
+
+    let ok = request
+        .make_response(SipResponseStatusCode::OK, None, "a6c85cf")
+        .build()
+        .unwrap();
+    let to = ok.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+    assert_eq!(to.params().unwrap().get("tag"), Some(Some("a6c85cf")));
+    assert!(to.value.uri().unwrap().tel_uri().is_some());
+}
+
+#[test]
+fn make_bad_request() {
+    let invite = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+                  Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+                  Max-Forwards: seventy\r\n\
+                  To: Bob <sip:bob@biloxi.com>\r\n\
+                  From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+                  Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+                  CSeq: 314159 INVITE\r\n\
+                  CSeq: 314160 INVITE\r\n\
+                  no colon\r\n\
+                  Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let options = SipParseOptions {
+        allow_invalid_headers: true,
+        ..SipParseOptions::strict()
+    };
+    let (rest, request) = SipRequest::parse_ext(invite, &options).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(request.headers.cseq().unwrap().seq, 314159);
+    assert!(request.headers.get_rfc(SipRFCHeader::MaxForwards).is_none());
+
+    let invalid = request.headers.invalid_headers();
+    assert_eq!(invalid.len(), 3);
+    assert_eq!(invalid[0].name, Some(SipAscii::new("max-forwards".into())));
+    assert_eq!(invalid[0].raw, &b"Max-Forwards: seventy"[..]);
+    assert_eq!(invalid[0].error.kind, SipParseErrorKind::HeaderValue);
+    assert_eq!(invalid[0].error.line, Some(3));
+    assert_eq!(invalid[1].error.kind, SipParseErrorKind::DuplicateHeader);
+    assert_eq!(invalid[1].error.line, Some(8));
+    assert_eq!(invalid[2].name, None);
+    assert_eq!(invalid[2].error.kind, SipParseErrorKind::HeaderName);
+
+    let response = request
+        .make_bad_request("biloxi.com", "a6c85cf")
+        .build()
+        .unwrap();
+    assert_eq!(
+        SipSerializer::new()
+            .msg_to_vec(&SipMessage::Response(response))
+            .unwrap(),
+        "SIP/2.0 400 Invalid Max-Forwards Header\r\n\
+         Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+         To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+         From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+         Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+         CSeq: 314159 INVITE\r\n\
+         Warning: 399 biloxi.com \"bad header value of Max-Forwards at line 3 (offset 108)\"\r\n\
+         Warning: 399 biloxi.com \"duplicate header of CSeq at line 8 (offset 262)\"\r\n\
+         Warning: 399 biloxi.com \"bad header name at line 9 (offset 286)\"\r\n\
+         Content-Length: 0\r\n\r\n"
+            .as_bytes()
+    );
+
+    // Content-Length frames the body, it can't be skipped
+    let invalid_length = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\nContent-Length: x\r\n\r\n";
+    assert!(SipRequest::parse_ext(invalid_length, &options).is_err());
+
+    // Response can't be built without valid Call-ID
+    let invalid_call_id = b"OPTIONS sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+To: <sip:bob@biloxi.com>\r\n\
+From: <sip:alice@atlanta.com>;tag=1928301774\r\n\
+Call-ID: a84b4c76e66710 x\r\n\
+CSeq: 1 OPTIONS\r\n\r\n";
+    let (_, request) = SipRequest::parse_ext(invalid_call_id, &options).unwrap();
+    assert_eq!(
+        request
+            .make_bad_request("biloxi.com", "a6c85cf")
+            .build()
+            .err(),
+        Some(SipBuildError::MissingHeader(SipRFCHeader::CallID))
+    );
+}
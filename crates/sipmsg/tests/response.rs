@@ -4,12 +4,12 @@ use sipmsg::*;
 fn status_code_from_bytes_str() {
     assert_eq!(
         SipResponseStatusCode::from_bytes_str("100".as_bytes()),
-        SipResponseStatusCode::Trying
+        Some(SipResponseStatusCode::Trying)
     );
 
     assert_eq!(
         SipResponseStatusCode::from_bytes_str("181".as_bytes()),
-        SipResponseStatusCode::CallIsBeingForwarded
+        Some(SipResponseStatusCode::CallIsBeingForwarded)
     );
 }
 
@@ -17,7 +17,7 @@ fn status_code_from_bytes_str() {
 fn status_code_from_str() {
     assert_eq!(
         SipResponseStatusCode::from_str("500"),
-        SipResponseStatusCode::ServerInternalError
+        Some(SipResponseStatusCode::ServerInternalError)
     );
 }
 
@@ -48,7 +48,7 @@ fn parse_response() {
     Content-Length: 0\r\n\r\n";
     match SipResponse::parse(response_msg.as_bytes()) {
         Ok((_, response)) => {
-            assert_eq!(response.sl.raw, b"SIP/2.0 401 Unauthorized\r\n");
+            assert_eq!(response.sl.raw, &b"SIP/2.0 401 Unauthorized\r\n"[..]);
             assert_eq!(response.sl.sip_version, SipVersion(2, 0));
             assert_eq!(response.sl.status_code, SipResponseStatusCode::Unauthorized);
             assert_eq!(response.sl.reason_phrase, "Unauthorized");
@@ -71,7 +71,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"branch"),
-                Some(&Some("z9hG4bKPj7IVefnk0j6Wn9oUM78ubmcURGDehvKEc"))
+                Some(Some("z9hG4bKPj7IVefnk0j6Wn9oUM78ubmcURGDehvKEc"))
             );
 
             assert_eq!(
@@ -82,7 +82,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"received"),
-                Some(&Some("192.168.178.69"))
+                Some(Some("192.168.178.69"))
             );
 
             assert_eq!(
@@ -93,7 +93,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"rport"),
-                Some(&Some("60686"))
+                Some(Some("60686"))
             );
 
             assert_eq!(
@@ -114,7 +114,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"tag"),
-                Some(&Some("XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b"))
+                Some(Some("XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b"))
             );
 
             assert_eq!(
@@ -135,7 +135,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"tag"),
-                Some(&Some("as68275e50"))
+                Some(Some("as68275e50"))
             );
 
             assert_eq!(
@@ -188,3 +188,80 @@ fn parse_response() {
         Err(_e) => panic!(),
     }
 }
+
+#[test]
+fn status_code_unknown_and_classes() {
+    let (_, sl) = SipResponseStatusLine::parse(b"SIP/2.0 499 Vendor Failure\r\n").unwrap();
+    assert!(matches!(
+        sl.status_code,
+        SipResponseStatusCode::Other(other) if other.code() == 499
+    ));
+    assert_eq!(sl.status_code.code(), 499);
+    assert_eq!(sl.status_code.reason_phrase(), "");
+    assert!(sl.status_code.is_client_error());
+    assert!(sl.status_code.is_final());
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 4x9 Vendor Failure\r\n").is_err());
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 999 Vendor Failure\r\n").is_err());
+
+    let (_, sl) = SipResponseStatusLine::parse(b"SIP/2.0 199 Early Dialog Terminated\r\n").unwrap();
+    assert_eq!(sl.status_code, SipResponseStatusCode::EarlyDialogTerminated);
+    assert!(sl.status_code.is_provisional());
+    assert!(!sl.status_code.is_final());
+
+    assert_eq!(
+        SipResponseStatusCode::from_code(422)
+            .unwrap()
+            .reason_phrase(),
+        "Session Interval Too Small"
+    );
+    assert_eq!(
+        SipResponseStatusCode::from_code(429),
+        Some(SipResponseStatusCode::ProvideReferrerIdentity)
+    );
+    assert_eq!(SipResponseStatusCode::from_str("abc"), None);
+    assert_eq!(SipResponseStatusCode::from_str("999"), None);
+    assert_eq!(SipResponseStatusCode::from_str("099"), None);
+    assert_eq!(SipResponseStatusCode::from_code(700), None);
+    assert_eq!(
+        SipResponseStatusCode::from_code(100),
+        Some(SipResponseStatusCode::Trying)
+    );
+    assert!(SipResponseStatusCode::Accepted.is_success());
+    assert!(SipResponseStatusCode::AlternativeService.is_redirect());
+    assert!(SipResponseStatusCode::PreconditionFailure.is_server_error());
+    assert!(SipResponseStatusCode::Rejected.is_global_failure());
+
+    for code in 100..700 {
+        let status_code = SipResponseStatusCode::from_code(code).unwrap();
+        assert_eq!(status_code.code(), code);
+        assert_eq!(status_code.is_final(), code >= 200);
+    }
+}
+
+#[test]
+fn validate_response() {
+    let response_msg = "SIP/2.0 200 OK\r\n\
+    Via: SIP/2.0/UDP 192.168.178.69:60686;branch=z9hG4bKPj7IVefnk0j6Wn9oUM78ubmcURGDehvKEc\r\n\
+    From: <sip:12@192.168.178.26>;tag=XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b\r\n\
+    To: <sip:12@192.168.178.26>;tag=as68275e50\r\n\
+    Call-ID: p8gpcmxSdWwcM5xV89nm2LkEbcTPUdT1\r\n\
+    CSeq: 62833 REGISTER\r\n\
+    Content-Length: 0\r\n\r\n";
+    let (_, response) = SipResponse::parse(response_msg.as_bytes()).unwrap();
+    assert!(response.validate().is_empty());
+
+    let response_msg = "SIP/2.0 200 OK\r\n\
+    From: <sip:12@192.168.178.26>;tag=XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b\r\n\
+    To: <sip:12@192.168.178.26>;tag=as68275e50\r\n\
+    CSeq: 2147483648 REGISTER\r\n\
+    Content-Length: 0\r\n\r\n";
+    let (_, response) = SipResponse::parse(response_msg.as_bytes()).unwrap();
+    assert_eq!(
+        response.validate(),
+        vec![
+            SipViolation::MissingHeader(SipRFCHeader::CallID),
+            SipViolation::MissingHeader(SipRFCHeader::Via),
+            SipViolation::CSeqNumberTooLarge,
+        ]
+    );
+}
@@ -31,34 +31,57 @@ fn parse_request() {
           newvalue ;\r\n \
           secondparam ; q = 0.33\r\n\
     \r\n\
-    v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+    v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
         .as_bytes();
 
     let res = SipRequest::parse(invite_msg_buf);
-    let (_, parsed_req) = res.unwrap();
+    let (rest, parsed_req) = res.unwrap();
+    assert!(rest.is_empty());
+    assert!(parsed_req.validate().is_empty());
     let request_line = &parsed_req.rl;
     let headers = &parsed_req.headers;
     assert_eq!(request_line.method, SipMethod::INVITE);
-    assert_eq!(request_line.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(request_line.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(request_line.uri.hostport.host, "chair-dnrc.example.com");
+    assert_eq!(
+        request_line.uri.sip_uri().unwrap().scheme,
+        SipRequestUriScheme::SIP
+    );
+    assert_eq!(
+        request_line
+            .uri
+            .sip_uri()
+            .unwrap()
+            .user_info()
+            .unwrap()
+            .value,
+        "vivekg"
+    );
+    assert_eq!(
+        request_line.uri.sip_uri().unwrap().hostport.host,
+        "chair-dnrc.example.com"
+    );
     assert_eq!(request_line.sip_version, SipVersion(2, 0));
     assert_eq!(
-        request_line.uri.params().unwrap().get(&"unknownparam"),
-        Some(&None)
+        request_line
+            .uri
+            .sip_uri()
+            .unwrap()
+            .params()
+            .unwrap()
+            .get("unknownparam"),
+        Some(None)
     );
 
     let to_hdr = headers.get_rfc_s(SipRFCHeader::To).unwrap();
     assert_eq!(
-        to_hdr.params().unwrap().get(&"tag"),
-        Some(&Some("1918181833n"))
+        to_hdr.params().unwrap().get("tag"),
+        Some(Some("1918181833n"))
     );
     assert_eq!(to_hdr.value.vstr, "sip:vivekg@chair-dnrc.example.com");
 
@@ -69,7 +92,7 @@ fn parse_request() {
     );
     assert_eq!(
         from_hdr.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"J Rosenberg \\\""
+        &b"J Rosenberg \\\""[..]
     );
     assert_eq!(
         from_hdr.value.sip_uri().unwrap().user_info().unwrap().value,
@@ -79,10 +102,7 @@ fn parse_request() {
         from_hdr.value.sip_uri().unwrap().hostport.host,
         "example.com"
     );
-    assert_eq!(
-        from_hdr.params().unwrap().get(&"tag"),
-        Some(&Some("98asjd8"))
-    );
+    assert_eq!(from_hdr.params().unwrap().get("tag"), Some(Some("98asjd8")));
 
     let max_forwards = parsed_req
         .headers
@@ -96,11 +116,11 @@ fn parse_request() {
     assert_eq!(call_id.value.vstr, "wsinv.ndaksdj@192.0.2.1");
     assert_eq!(
         call_id.value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"wsinv.ndaksdj"
+        &b"wsinv.ndaksdj"[..]
     );
     assert_eq!(
         call_id.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"192.0.2.1"
+        &b"192.0.2.1"[..]
     );
 
     let content_length = &parsed_req
@@ -114,42 +134,42 @@ fn parse_request() {
     assert_eq!(cseq_header.params(), None);
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Number],
-        b"0009"
+        &b"0009"[..]
     );
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Method],
-        b"INVITE"
+        &b"INVITE"[..]
     );
 
     let via_hdrs = headers.get_rfc(SipRFCHeader::Via).unwrap();
     let first_via = &via_hdrs[0];
     assert_eq!(first_via.value.vstr, "SIP  /   2.0\r\n /UDP\r\n 192.0.2.2");
     assert_eq!(
-        first_via.params().unwrap().get(&"branch"),
-        Some(&Some("390skdjuw"))
+        first_via.params().unwrap().get("branch"),
+        Some(Some("390skdjuw"))
     );
 
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-        b"SIP"
+        &b"SIP"[..]
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-        b"2.0"
+        &b"2.0"[..]
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"UDP"
+        &b"UDP"[..]
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"192.0.2.2"
+        &b"192.0.2.2"[..]
     );
 
     let seond_via = &via_hdrs[1];
     assert_eq!(
         seond_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"TCP"
+        &b"TCP"[..]
     );
     assert_eq!(
         seond_via.value.vstr,
@@ -157,11 +177,11 @@ fn parse_request() {
     );
     assert_eq!(
         seond_via.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"spindle.example.com"
+        &b"spindle.example.com"[..]
     );
     assert_eq!(
-        seond_via.params().unwrap().get(&"branch"),
-        Some(&Some("z9hG4bK9ikj8"))
+        seond_via.params().unwrap().get("branch"),
+        Some(Some("z9hG4bK9ikj8"))
     );
 
     let subject_hdr = &headers.get_rfc_s(SipRFCHeader::Subject).unwrap();
@@ -189,9 +209,9 @@ fn parse_request() {
     let route_uri_params = &route_uri.params().unwrap();
     assert_eq!(route_uri.scheme, sipuri::RequestUriScheme::SIP);
     assert_eq!(route_uri.hostport.host, "services.example.com");
-    assert_eq!(route_uri_params.get(&"lr"), Some(&None));
-    assert_eq!(route_uri_params.get(&"unknownwith"), Some(&Some("value")));
-    assert_eq!(route_uri_params.get(&"unknown-no-value"), Some(&None));
+    assert_eq!(route_uri_params.get("lr"), Some(None));
+    assert_eq!(route_uri_params.get("unknownwith"), Some(Some("value")));
+    assert_eq!(route_uri_params.get("unknown-no-value"), Some(None));
     assert_eq!(route_uri_params.get(&"missing_param"), None);
 
     let contact = &headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
@@ -201,12 +221,12 @@ fn parse_request() {
     );
     assert_eq!(
         contact.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Quoted string \\\"\\\""
+        &b"Quoted string \\\"\\\""[..]
     );
     let contact_params = contact.params().unwrap();
-    assert_eq!(contact_params.get(&"newparam"), Some(&Some("newvalue")));
-    assert_eq!(contact_params.get(&"secondparam"), Some(&None));
-    assert_eq!(contact_params.get(&"q"), Some(&Some("0.33")));
+    assert_eq!(contact_params.get("newparam"), Some(Some("newvalue")));
+    assert_eq!(contact_params.get("secondparam"), Some(None));
+    assert_eq!(contact_params.get("q"), Some(Some("0.33")));
 
     let contact_uri = &contact.value.sip_uri().unwrap();
     assert_eq!(contact_uri.scheme, sipuri::RequestUriScheme::SIP);
@@ -215,15 +235,137 @@ fn parse_request() {
     assert_eq!(contact_uri.params(), None);
     /*********************************************************/
     assert_eq!(
-        parsed_req.body.unwrap(),
-        "v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+        parsed_req.body.as_deref().unwrap(),
+        "v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
             .as_bytes()
     );
 }
+
+#[test]
+fn validate_missing_required_headers() {
+    let msg = "INVITE sip:user@example.com SIP/2.0\r\n\
+    CSeq: 193942 INVITE\r\n\
+    Via: SIP/2.0/UDP 192.0.2.95;branch=z9hG4bKkdj.insuf\r\n\
+    Content-Type: application/sdp\r\n\
+    l: 5\r\n\
+    \r\n\
+    v=0\r\n"
+        .as_bytes();
+
+    let (_, request) = SipRequest::parse(msg).unwrap();
+    assert_eq!(
+        request.validate(),
+        vec![
+            SipViolation::MissingHeader(SipRFCHeader::To),
+            SipViolation::MissingHeader(SipRFCHeader::From),
+            SipViolation::MissingHeader(SipRFCHeader::CallID),
+            SipViolation::MissingHeader(SipRFCHeader::MaxForwards),
+        ]
+    );
+}
+
+#[test]
+fn validate_multiple_values_in_single_value_headers() {
+    let msg = "INVITE sip:user@company.com SIP/2.0\r\n\
+    Contact: <sip:caller@host25.example.net>\r\n\
+    Via: SIP/2.0/UDP 192.0.2.25;branch=z9hG4bKvscfd\r\n\
+    Max-Forwards: 70\r\n\
+    Call-ID: multi01.98asdh@192.0.2.1\r\n\
+    CSeq: 59 INVITE\r\n\
+    Call-ID: multi01.98asdh@192.0.2.2\r\n\
+    From: sip:caller@example.com;tag=3413415\r\n\
+    To: sip:user@example.com\r\n\
+    To: sip:other@example.net\r\n\
+    From: sip:caller@example.net;tag=2923420123\r\n\
+    l: 0\r\n\
+    \r\n"
+        .as_bytes();
+
+    // Duplicates of single valued headers are rejected only by the strict parser
+    assert!(SipRequest::parse_ext(msg, &SipParseOptions::strict()).is_err());
+
+    let (_, request) = SipRequest::parse(msg).unwrap();
+    assert_eq!(
+        request.validate(),
+        vec![
+            SipViolation::DuplicateHeader(SipRFCHeader::To),
+            SipViolation::DuplicateHeader(SipRFCHeader::From),
+            SipViolation::DuplicateHeader(SipRFCHeader::CallID),
+        ]
+    );
+}
+
+#[test]
+fn validate_large_values_in_scalar_fields() {
+    let msg = "REGISTER sip:example.com SIP/2.0\r\n\
+    Via: SIP/2.0/TCP host129.example.com;branch=z9hG4bKzzxdiwo34sw\r\n\
+    Max-Forwards: 300\r\n\
+    From: <sip:user@example.com>;tag=239232jh3\r\n\
+    To: <sip:user@example.com>\r\n\
+    Call-ID: scalar02.23o0pd9vanlq3wnrlnewofjas9\r\n\
+    CSeq: 36893488147419103232 REGISTER\r\n\
+    Contact: <sip:user@host129.example.com>;expires=280297596632815\r\n\
+    l: 0\r\n\
+    \r\n"
+        .as_bytes();
+
+    let (_, request) = SipRequest::parse(msg).unwrap();
+    assert_eq!(
+        request.validate(),
+        vec![
+            SipViolation::CSeqNumberTooLarge,
+            SipViolation::InvalidHeader(SipRFCHeader::MaxForwards),
+        ]
+    );
+}
+
+#[test]
+fn validate_request_method_mismatch() {
+    let msg = "OPTIONS sip:user@example.com SIP/2.0\r\n\
+    To: sip:j.user@example.com\r\n\
+    From: sip:caller@example.net;tag=34525\r\n\
+    Max-Forwards: 6\r\n\
+    Call-ID: mismatch01.dj0234sxdfl3\r\n\
+    CSeq: 8 INVITE\r\n\
+    Via: SIP/2.0/UDP host.example.com;branch=z9hG4bKkdjuw\r\n\
+    l: 0\r\n\
+    \r\n"
+        .as_bytes();
+
+    let (_, request) = SipRequest::parse(msg).unwrap();
+    assert_eq!(request.validate(), vec![SipViolation::CSeqMethodMismatch]);
+}
+
+#[test]
+fn validate_content_length_mismatch() {
+    let msg = "MESSAGE sip:user@example.com SIP/2.0\r\n\
+    To: sip:user@example.com\r\n\
+    From: sip:caller@example.net;tag=34525\r\n\
+    Max-Forwards: 70\r\n\
+    Call-ID: clmismatch.dj0234sxdfl3\r\n\
+    CSeq: 8 MESSAGE\r\n\
+    Via: SIP/2.0/UDP host.example.com;branch=z9hG4bKkdjuw\r\n\
+    Content-Length: 5\r\n\
+    \r\n\
+    Hello"
+        .as_bytes();
+
+    let (_, mut request) = SipRequest::parse(msg).unwrap();
+    assert!(request.validate().is_empty());
+
+    request.body = Some(b"Hello, Bob"[..].into());
+    assert_eq!(
+        request.validate(),
+        vec![SipViolation::ContentLengthMismatch {
+            content_length: 5,
+            body_length: 10
+        }]
+    );
+}
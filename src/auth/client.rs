@@ -0,0 +1,384 @@
+use super::nonce::Random;
+use super::ChallengeKind;
+use sipmsg::*;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Username and password for realm, for any realm if realm is `None`
+struct Account {
+    realm: Option<String>,
+    username: String,
+    password: String,
+}
+
+/// Challenge answered before, its nonce is reused for the next requests
+struct CachedChallenge {
+    kind: ChallengeKind,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: SipDigestAlgorithm,
+    qop: Vec<SipDigestQop>,
+    /// nonce-count of the last request
+    nc: u32,
+    /// Request with the nonce was accepted, so rejection of it means
+    /// the server forgot the nonce, not that the password is wrong
+    accepted: bool,
+}
+
+/// Answers 401 and 407 responses of user agent client.
+///
+/// `handle_response` makes the request to repeat with credentials for each challenge
+/// of response. Challenges are remembered per realm, `authorize` adds credentials to
+/// the next requests without waiting for a challenge.
+/// `nextnonce` of Authentication-Info replaces the remembered nonce.
+///
+/// ```rust
+/// use sipcore::auth::*;
+/// use sipcore::*;
+///
+/// let invite = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+///               Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+///               Max-Forwards: 70\r\n\
+///               To: Bob <sip:bob@biloxi.com>\r\n\
+///               From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+///               Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+///               CSeq: 314159 INVITE\r\n\
+///               Content-Length: 0\r\n\r\n";
+/// let (_, request) = SipRequest::parse(invite.as_bytes()).unwrap();
+///
+/// let mut store = MemoryStore::new();
+/// store.add_password("atlanta.com", "alice", "secret");
+/// let proxy = AuthServer::new(store, ChallengeKind::Proxy, "atlanta.com");
+/// let response = match proxy.authenticate(&request) {
+///     AuthResult::Challenge(response) => response.build().unwrap(),
+///     AuthResult::Authorized(_) => unreachable!(),
+/// };
+///
+/// let mut client = AuthClient::new().account("atlanta.com", "alice", "secret");
+/// let retry = client.handle_response(&request, &response).unwrap();
+/// assert_eq!(retry.headers.cseq().unwrap().seq, 314160);
+/// assert!(matches!(proxy.authenticate(&retry), AuthResult::Authorized(_)));
+/// ```
+pub struct AuthClient {
+    accounts: Vec<Account>,
+    algorithms: Vec<SipDigestAlgorithm>,
+    /// realm -> the last challenge
+    challenges: HashMap<String, CachedChallenge>,
+    random: Random,
+}
+
+impl Default for AuthClient {
+    fn default() -> AuthClient {
+        AuthClient::new()
+    }
+}
+
+impl AuthClient {
+    /// Client without accounts, all algorithms are supported, the strongest is preferred
+    pub fn new() -> AuthClient {
+        AuthClient {
+            accounts: Vec::new(),
+            algorithms: vec![
+                SipDigestAlgorithm::Sha512_256,
+                SipDigestAlgorithm::Sha512_256Sess,
+                SipDigestAlgorithm::Sha256,
+                SipDigestAlgorithm::Sha256Sess,
+                SipDigestAlgorithm::Md5,
+                SipDigestAlgorithm::Md5Sess,
+            ],
+            challenges: HashMap::new(),
+            random: Random::new(),
+        }
+    }
+
+    /// Account for `realm`, it replaces account added before for the same realm
+    pub fn account(mut self, realm: &str, username: &str, password: &str) -> AuthClient {
+        self.set_account(Some(realm), username, password);
+        self
+    }
+
+    /// Account for realms without own account
+    pub fn default_account(mut self, username: &str, password: &str) -> AuthClient {
+        self.set_account(None, username, password);
+        self
+    }
+
+    /// Supported algorithms in order of preference
+    pub fn algorithms(mut self, algorithms: &[SipDigestAlgorithm]) -> AuthClient {
+        self.algorithms = algorithms.to_vec();
+        self
+    }
+
+    fn set_account(&mut self, realm: Option<&str>, username: &str, password: &str) {
+        self.accounts.retain(|a| a.realm.as_deref() != realm);
+        self.accounts.push(Account {
+            realm: realm.map(str::to_string),
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        // Cached nonce may be used with the other user
+        match realm {
+            Some(realm) => {
+                self.challenges.remove(realm);
+            }
+            None => self.challenges.clear(),
+        }
+    }
+
+    fn find_account(&self, realm: &str) -> Option<&Account> {
+        self.accounts
+            .iter()
+            .find(|a| a.realm.as_deref() == Some(realm))
+            .or_else(|| self.accounts.iter().find(|a| a.realm.is_none()))
+    }
+
+    /// Handles response to `request`.
+    ///
+    /// For 401 and 407 returns `request` to send again with new CSeq, new Via branch
+    /// and credentials for every realm of WWW-Authenticate and Proxy-Authenticate headers.
+    /// A proxy that forks the request puts challenges of all branches in one response.
+    /// Returns `None` if there are no accounts for realms or no supported algorithms,
+    /// and if credentials with the nonce of the last challenge were rejected without
+    /// `stale=true`. Credentials with a nonce accepted before are repeated once
+    /// with the new nonce, server may forget nonces before they expire.
+    ///
+    /// For other responses remembers that nonces of credentials were accepted
+    /// and `nextnonce` of Authentication-Info, returns `None`.
+    pub fn handle_response<'a>(
+        &mut self,
+        request: &SipRequest<'a>,
+        response: &SipResponse,
+    ) -> Option<SipRequest<'a>> {
+        match response.sl.status_code {
+            SipResponseStatusCode::Unauthorized
+            | SipResponseStatusCode::ProxyAuthenticationRequired => self.retry(request, response),
+            _ => {
+                self.accept_nonces(request);
+                self.update_nonce(request, response);
+                None
+            }
+        }
+    }
+
+    /// Adds credentials for every remembered realm, credentials present in `request`
+    /// for these realms are replaced. Request of the new dialog may go through other proxies,
+    /// remembered realms should be cleared by `forget` if it's not desired.
+    pub fn authorize(&mut self, request: &mut SipRequest) {
+        let realms: Vec<String> = self.challenges.keys().cloned().collect();
+        for realm in realms {
+            self.set_credentials(request, &realm);
+        }
+    }
+
+    /// Forgets challenge of realm, credentials will be sent after the next challenge
+    pub fn forget(&mut self, realm: &str) {
+        self.challenges.remove(realm);
+    }
+
+    fn retry<'a>(
+        &mut self,
+        request: &SipRequest<'a>,
+        response: &SipResponse,
+    ) -> Option<SipRequest<'a>> {
+        // The most preferred supported challenge for each realm
+        let mut chosen: Vec<(ChallengeKind, SipDigestChallenge)> = Vec::new();
+        for kind in &[ChallengeKind::Www, ChallengeKind::Proxy] {
+            let headers = match response.headers.get_rfc(kind.challenge_header()) {
+                Some(headers) => headers,
+                None => continue,
+            };
+            for challenge in headers.iter().filter_map(SipDigestChallenge::from_header) {
+                let preference = match self.preference(challenge.algorithm) {
+                    Some(preference) => preference,
+                    None => continue,
+                };
+                match chosen.iter_mut().find(|(_, c)| c.realm == challenge.realm) {
+                    Some((_, c)) => {
+                        if self.preference(c.algorithm) > Some(preference) {
+                            *c = challenge;
+                        }
+                    }
+                    None => chosen.push((*kind, challenge)),
+                }
+            }
+        }
+        chosen.retain(|(_, c)| self.find_account(c.realm).is_some());
+        if chosen.is_empty() {
+            return None;
+        }
+        for (kind, challenge) in &chosen {
+            // Password is wrong, repeating won't help
+            if !challenge.stale && self.has_new_nonce(request, *kind, challenge.realm) {
+                return None;
+            }
+        }
+
+        let mut request = request.clone();
+        for (kind, challenge) in chosen {
+            self.challenges.insert(
+                challenge.realm.to_string(),
+                CachedChallenge {
+                    kind,
+                    nonce: challenge.nonce.to_string(),
+                    opaque: challenge.opaque.map(str::to_string),
+                    algorithm: challenge.algorithm,
+                    qop: challenge.qop.clone(),
+                    nc: 0,
+                    accepted: false,
+                },
+            );
+            self.set_credentials(&mut request, challenge.realm);
+        }
+        let cseq = request.headers.cseq()?;
+        request.headers.set(
+            SipHeader::new_owned(
+                SipRFCHeader::CSeq.as_str(),
+                &format!("{} {}", cseq.seq + 1, cseq.method.as_str()),
+            )
+            .ok()?,
+        );
+        self.new_branch(&mut request)?;
+        Some(request)
+    }
+
+    /// Credentials of `request` for `realm` use the remembered nonce,
+    /// which was not accepted yet
+    fn has_new_nonce(&self, request: &SipRequest, kind: ChallengeKind, realm: &str) -> bool {
+        let cached = match self.challenges.get(realm) {
+            Some(cached) if cached.kind == kind && !cached.accepted => cached,
+            _ => return false,
+        };
+        credentials(request, kind).any(|c| c.realm == realm && c.nonce == cached.nonce)
+    }
+
+    /// Request was not rejected, its nonces are valid
+    fn accept_nonces(&mut self, request: &SipRequest) {
+        for kind in &[ChallengeKind::Www, ChallengeKind::Proxy] {
+            for credentials in credentials(request, *kind) {
+                if let Some(cached) = self.challenges.get_mut(credentials.realm) {
+                    if cached.kind == *kind && cached.nonce == credentials.nonce {
+                        cached.accepted = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lower is better
+    fn preference(&self, algorithm: SipDigestAlgorithm) -> Option<usize> {
+        self.algorithms.iter().position(|a| *a == algorithm)
+    }
+
+    /// Replaces credentials for `realm` with credentials for remembered challenge
+    fn set_credentials(&mut self, request: &mut SipRequest, realm: &str) {
+        let (username, password) = match self.find_account(realm) {
+            Some(account) => (account.username.clone(), account.password.clone()),
+            None => return,
+        };
+        let cnonce = self.random.hex();
+        let cached = match self.challenges.get_mut(realm) {
+            Some(cached) => cached,
+            None => return,
+        };
+        cached.nc += 1;
+        let challenge = SipDigestChallenge {
+            realm,
+            nonce: &cached.nonce,
+            opaque: cached.opaque.as_deref(),
+            algorithm: cached.algorithm,
+            qop: cached.qop.clone(),
+            stale: false,
+        };
+        let credentials = challenge.credentials(request, &username, &password, &cnonce, cached.nc);
+        let header = match cached.kind {
+            ChallengeKind::Www => credentials.authorization(),
+            ChallengeKind::Proxy => credentials.proxy_authorization(),
+        };
+        if let Ok(header) = header {
+            remove_credentials(request, cached.kind, realm);
+            request.headers.push_back(header);
+        }
+    }
+
+    /// Authentication-Info is sent by user agent server only,
+    /// it belongs to credentials of Authorization header
+    fn update_nonce(&mut self, request: &SipRequest, response: &SipResponse) {
+        let next_nonce = response
+            .headers
+            .get_rfc(SipRFCHeader::AuthenticationInfo)
+            .into_iter()
+            .flatten()
+            .filter_map(|h| h.value.tags())
+            .find(|tags| {
+                matches!(tags.get(&SipHeaderTagType::AinfoType),
+                         Some(name) if name.eq_ignore_ascii_case(b"nextnonce"))
+            })
+            .and_then(|tags| tags.get(&SipHeaderTagType::AinfoValue))
+            .and_then(|nonce| std::str::from_utf8(nonce).ok());
+        let next_nonce = match next_nonce {
+            Some(next_nonce) => next_nonce,
+            None => return,
+        };
+        for credentials in credentials(request, ChallengeKind::Www) {
+            if let Some(cached) = self.challenges.get_mut(credentials.realm) {
+                if cached.kind == ChallengeKind::Www && cached.nonce == credentials.nonce {
+                    cached.nonce = next_nonce.to_string();
+                    cached.nc = 0;
+                    cached.accepted = true;
+                }
+            }
+        }
+    }
+
+    /// Retried request is a new transaction
+    /// [rfc3261 8.1.3.5](https://tools.ietf.org/html/rfc3261#section-8.1.3.5)
+    fn new_branch(&self, request: &mut SipRequest) -> Option<()> {
+        let via = request.headers.pop_front_rfc(SipRFCHeader::Via)?;
+        let mut value = via.value.vstr.to_string();
+        if let Some(params) = via.params() {
+            for name in params.keys() {
+                if name.eq_ignore_ascii_case("branch") {
+                    continue;
+                }
+                match params.get(name) {
+                    Some(Some(param_value)) => write!(value, ";{}={}", name, param_value).ok()?,
+                    _ => write!(value, ";{}", name).ok()?,
+                }
+            }
+        }
+        write!(value, ";branch=z9hG4bK{}", self.random.hex()).ok()?;
+        request
+            .headers
+            .push_front(SipHeader::new_owned(SipRFCHeader::Via.as_str(), &value).ok()?);
+        Some(())
+    }
+}
+
+fn credentials<'a>(
+    request: &'a SipRequest,
+    kind: ChallengeKind,
+) -> impl Iterator<Item = SipDigestCredentials<'a>> {
+    request
+        .headers
+        .get_rfc(kind.credentials_header())
+        .into_iter()
+        .flatten()
+        .filter_map(SipDigestCredentials::from_header)
+}
+
+fn remove_credentials(request: &mut SipRequest, kind: ChallengeKind, realm: &str) {
+    let headers = match request.headers.remove_rfc(kind.credentials_header()) {
+        Some(headers) => headers,
+        None => return,
+    };
+    for header in headers {
+        let other_realm = match SipDigestCredentials::from_header(&header) {
+            Some(credentials) => credentials.realm != realm,
+            None => true,
+        };
+        if other_realm {
+            request.headers.push_back(header);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Digest authentication of requests for registrars and proxies, and answers to challenges
+//! for user agent clients
+//! ([rfc3261 22](https://tools.ietf.org/html/rfc3261#section-22)).
+//!
+//! ```rust
+//! use sipcore::auth::{AuthResult, AuthServer, ChallengeKind, MemoryStore};
+//! use sipcore::*;
+//!
+//! let mut store = MemoryStore::new();
+//! store.add_password("biloxi.com", "bob", "zanzibar");
+//! let server = AuthServer::new(store, ChallengeKind::Www, "biloxi.com");
+//!
+//! let register = "REGISTER sip:biloxi.com SIP/2.0\r\n\
+//!                 Via: SIP/2.0/UDP bobspc.biloxi.com:5060;branch=z9hG4bKnashds7\r\n\
+//!                 Max-Forwards: 70\r\n\
+//!                 To: Bob <sip:bob@biloxi.com>\r\n\
+//!                 From: Bob <sip:bob@biloxi.com>;tag=456248\r\n\
+//!                 Call-ID: 843817637684230@998sdasdh09\r\n\
+//!                 CSeq: 1826 REGISTER\r\n\
+//!                 Content-Length: 0\r\n\r\n";
+//! let (_, mut request) = SipRequest::parse(register.as_bytes()).unwrap();
+//!
+//! let response = match server.authenticate(&request) {
+//!     AuthResult::Challenge(response) => response.build().unwrap(),
+//!     AuthResult::Authorized(_) => unreachable!(),
+//! };
+//! assert_eq!(response.sl.status_code, SipResponseStatusCode::Unauthorized);
+//!
+//! // Client
+//! // One challenge for each algorithm, SHA-256 is preferred
+//! let www_authenticate = &response.headers.get_rfc(SipRFCHeader::WWWAuthenticate).unwrap()[0];
+//! let challenge = SipDigestChallenge::from_header(www_authenticate).unwrap();
+//! let credentials = challenge.credentials(&request, "bob", "zanzibar", "0a4f113b", 1);
+//! request.headers.push_back(credentials.authorization().unwrap());
+//!
+//! match server.authenticate(&request) {
+//!     AuthResult::Authorized(user) => assert_eq!(user.username, "bob"),
+//!     AuthResult::Challenge(_) => unreachable!(),
+//! }
+//! ```
+mod client;
+mod nonce;
+mod store;
+
+pub use self::client::AuthClient;
+pub use self::store::{CredentialStore, FileStore, MemoryStore};
+
+use self::nonce::{NonceCheck, Nonces};
+use sipmsg::*;
+
+use std::time::Duration;
+
+/// Who asks for credentials
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChallengeKind {
+    /// User agent server or registrar: 401 with WWW-Authenticate,
+    /// credentials in Authorization
+    Www,
+    /// Proxy: 407 with Proxy-Authenticate, credentials in Proxy-Authorization
+    Proxy,
+}
+
+impl ChallengeKind {
+    pub fn status_code(&self) -> SipResponseStatusCode {
+        match self {
+            ChallengeKind::Www => SipResponseStatusCode::Unauthorized,
+            ChallengeKind::Proxy => SipResponseStatusCode::ProxyAuthenticationRequired,
+        }
+    }
+
+    pub fn challenge_header(&self) -> SipRFCHeader {
+        match self {
+            ChallengeKind::Www => SipRFCHeader::WWWAuthenticate,
+            ChallengeKind::Proxy => SipRFCHeader::ProxyAuthenticate,
+        }
+    }
+
+    pub fn credentials_header(&self) -> SipRFCHeader {
+        match self {
+            ChallengeKind::Www => SipRFCHeader::Authorization,
+            ChallengeKind::Proxy => SipRFCHeader::ProxyAuthorization,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuthorizedUser {
+    pub username: String,
+    pub realm: String,
+}
+
+pub enum AuthResult<'a> {
+    Authorized(AuthorizedUser),
+    /// 401 or 407 response to send, other headers can be added
+    Challenge(SipResponseBuilder<'a>),
+}
+
+/// Checks credentials of requests and makes challenges.
+/// Nonces are valid for one realm and `nonce_lifetime`,
+/// nonce-count must grow with each request.
+/// Expired nonces with valid credentials are answered with `stale=true`.
+pub struct AuthServer<S: CredentialStore> {
+    store: S,
+    kind: ChallengeKind,
+    realms: Vec<String>,
+    algorithms: Vec<SipDigestAlgorithm>,
+    qop: Vec<SipDigestQop>,
+    nonces: Nonces,
+}
+
+impl<S: CredentialStore> AuthServer<S> {
+    /// Server with SHA-256 and MD5 challenges, `qop="auth"` and nonce lifetime of 5 minutes
+    pub fn new(store: S, kind: ChallengeKind, realm: &str) -> AuthServer<S> {
+        AuthServer {
+            store,
+            kind,
+            realms: vec![realm.to_string()],
+            algorithms: vec![SipDigestAlgorithm::Sha256, SipDigestAlgorithm::Md5],
+            qop: vec![SipDigestQop::Auth],
+            nonces: Nonces::new(Duration::from_secs(300)),
+        }
+    }
+
+    /// Credentials of this realm are accepted too.
+    /// Challenge is made for realm that equals the host of Request-URI or From URI
+    /// (From URI is preferred by proxy), otherwise for the first realm.
+    pub fn add_realm(mut self, realm: &str) -> AuthServer<S> {
+        if !self.realms.iter().any(|r| r == realm) {
+            self.realms.push(realm.to_string());
+        }
+        self
+    }
+
+    /// Challenge contains one header for each algorithm in order of preference.
+    /// Credentials with other algorithms are rejected.
+    ///
+    /// # Panics
+    /// If `algorithms` is empty
+    pub fn algorithms(mut self, algorithms: &[SipDigestAlgorithm]) -> AuthServer<S> {
+        assert!(!algorithms.is_empty(), "at least one algorithm is required");
+        self.algorithms = algorithms.to_vec();
+        self
+    }
+
+    /// Empty `qop` allows credentials without qop ([rfc2069](https://tools.ietf.org/html/rfc2069)),
+    /// they are not protected from replay while nonce is valid
+    pub fn qop(mut self, qop: &[SipDigestQop]) -> AuthServer<S> {
+        self.qop = qop.to_vec();
+        self
+    }
+
+    pub fn nonce_lifetime(mut self, lifetime: Duration) -> AuthServer<S> {
+        self.nonces.set_lifetime(lifetime);
+        self
+    }
+
+    pub fn kind(&self) -> ChallengeKind {
+        self.kind
+    }
+
+    pub fn realms(&self) -> &[String] {
+        &self.realms
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Checks Authorization or Proxy-Authorization headers of `request`,
+    /// the first credentials for one of realms are used.
+    /// Returns challenge if credentials are absent, invalid or their nonce is not valid.
+    pub fn authenticate<'a>(&self, request: &SipRequest<'a>) -> AuthResult<'a> {
+        let credentials = request
+            .headers
+            .get_rfc(self.kind.credentials_header())
+            .into_iter()
+            .flatten()
+            .filter_map(SipDigestCredentials::from_header)
+            .find(|c| self.realms.iter().any(|r| r == c.realm));
+        let credentials = match credentials {
+            Some(credentials) => credentials,
+            None => return AuthResult::Challenge(self.challenge(request, None, false)),
+        };
+        let realm = credentials.realm;
+
+        if !self.credentials_allowed(request, &credentials) {
+            return AuthResult::Challenge(self.challenge(request, Some(realm), false));
+        }
+        let ha1 = match self
+            .store
+            .ha1(realm, credentials.username, credentials.algorithm)
+        {
+            Some(ha1) => ha1,
+            None => return AuthResult::Challenge(self.challenge(request, Some(realm), false)),
+        };
+        if !credentials.verify_ha1(request, &ha1) {
+            return AuthResult::Challenge(self.challenge(request, Some(realm), false));
+        }
+        match self.nonces.check(credentials.nonce, realm, credentials.nc) {
+            NonceCheck::Valid => AuthResult::Authorized(AuthorizedUser {
+                username: credentials.username.to_string(),
+                realm: realm.to_string(),
+            }),
+            // Digest is valid, so the client knows the password and only needs a new nonce
+            // (rfc7616 section 3.3)
+            NonceCheck::Stale | NonceCheck::Unknown => {
+                AuthResult::Challenge(self.challenge(request, Some(realm), true))
+            }
+            NonceCheck::Replayed => {
+                AuthResult::Challenge(self.challenge(request, Some(realm), false))
+            }
+        }
+    }
+
+    /// 401 or 407 response with new nonce for `realm` or realm chosen by request.
+    /// The same nonce is used in challenges for all algorithms.
+    pub fn challenge<'a>(
+        &self,
+        request: &SipRequest<'a>,
+        realm: Option<&str>,
+        stale: bool,
+    ) -> SipResponseBuilder<'a> {
+        let realm = realm.unwrap_or_else(|| self.default_realm(request));
+        let nonce = self.nonces.issue(realm);
+        let to_tag = self.nonces.random_hex();
+        let mut response = request.make_response(self.kind.status_code(), None, &to_tag);
+        for algorithm in &self.algorithms {
+            let mut challenge = SipDigestChallenge::new(realm, &nonce);
+            challenge.algorithm = *algorithm;
+            challenge.qop = self.qop.clone();
+            challenge.stale = stale;
+            let header = match self.kind {
+                ChallengeKind::Www => challenge.www_authenticate(),
+                ChallengeKind::Proxy => challenge.proxy_authenticate(),
+            };
+            // Realm is escaped, nonce is hex, so header is always valid
+            if let Ok(header) = header {
+                response = response.header(header);
+            }
+        }
+        response
+    }
+
+    /// Registrar authenticates users of its domain, proxy authenticates callers
+    fn default_realm(&self, request: &SipRequest) -> &str {
+        let request_host = request
+            .rl
+            .uri
+            .sip_uri()
+            .map(|u| u.hostport.host.to_string());
+        let from_host = request
+            .headers
+            .from_addr()
+            .and_then(|from| from.uri.sip_uri().map(|u| u.hostport.host.to_string()));
+        let hosts = match self.kind {
+            ChallengeKind::Www => [request_host, from_host],
+            ChallengeKind::Proxy => [from_host, request_host],
+        };
+        for host in hosts.iter().flatten() {
+            if let Some(realm) = self.realms.iter().find(|r| r.eq_ignore_ascii_case(host)) {
+                return realm;
+            }
+        }
+        &self.realms[0]
+    }
+
+    /// Algorithm and qop were offered, digest-uri is Request-URI
+    fn credentials_allowed(
+        &self,
+        request: &SipRequest,
+        credentials: &SipDigestCredentials,
+    ) -> bool {
+        if !self.algorithms.contains(&credentials.algorithm) {
+            return false;
+        }
+        match credentials.qop {
+            Some(qop) if !self.qop.contains(&qop) => return false,
+            None if !self.qop.is_empty() => return false,
+            _ => {}
+        }
+        match (
+            request.rl.uri.sip_uri(),
+            SipUri::parse(credentials.uri.as_bytes()),
+        ) {
+            (Some(request_uri), Ok((&[], uri))) => request_uri.equivalent(&uri),
+            _ => request.rl.uri.to_string() == credentials.uri,
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use sipmsg::SipDigestAlgorithm;
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Count of nonces whose nonce-count is remembered
+const USED_NONCES_CAPACITY: usize = 10_000;
+
+/// Result of checking nonce of credentials
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) enum NonceCheck {
+    Valid,
+    /// Nonce was issued for the realm, but its lifetime is over
+    /// or its nonce-count was forgotten
+    Stale,
+    /// Nonce was not issued by this server or issued for other realm
+    Unknown,
+    /// nonce-count is not greater than in the previous request
+    Replayed,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compares all bytes, so the time doesn't depend on the position of first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Generator of nonces, cnonces, tags and branches.
+/// Values are SHA-256 of a secret key and a counter, the key is read from `/dev/urandom`.
+/// If it can't be read, the key is made from random keys of `RandomState` and the time,
+/// such values are unique, but they are not unpredictable.
+pub(super) struct Random {
+    key: String,
+    counter: AtomicU64,
+}
+
+impl Random {
+    pub(super) fn new() -> Random {
+        Random {
+            key: Random::os_key().unwrap_or_else(Random::fallback_key),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    fn os_key() -> Option<String> {
+        let mut key = [0u8; 32];
+        File::open("/dev/urandom")
+            .and_then(|mut file| file.read_exact(&mut key))
+            .ok()?;
+        Some(to_hex(&key))
+    }
+
+    // `BuildHasher::hash_one` requires rust 1.71
+    #[allow(unknown_lints, clippy::manual_hash_one)]
+    fn fallback_key() -> String {
+        let random_state = RandomState::new();
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let hash = |part: u8| {
+            let mut hasher = random_state.build_hasher();
+            (time, part).hash(&mut hasher);
+            hasher.finish()
+        };
+        format!("{:016x}{:016x}", hash(0), hash(1))
+    }
+
+    /// 32 hex digits
+    pub(super) fn hex(&self) -> String {
+        let count = self.counter.fetch_add(1, Ordering::Relaxed).to_string();
+        let mut hex = SipDigestAlgorithm::Sha256.hash(&[self.key.as_bytes(), count.as_bytes()]);
+        hex.truncate(32);
+        hex
+    }
+}
+
+struct UsedNonce {
+    /// Milliseconds from the start of `Nonces`
+    time: u64,
+    last_nc: u32,
+}
+
+/// Nonces that authenticated requests with nonce-count
+struct UsedNonces {
+    nonces: HashMap<String, UsedNonce>,
+    /// Valid nonces issued before it, but absent in `nonces`, were forgotten
+    forgotten_before: u64,
+}
+
+/// Issuer of nonces. Nonce is `time salt mac`, where `mac` is a hash of the secret key,
+/// time, salt and realm, so nonces are checked without a table of issued nonces.
+/// Only nonces that authenticated requests are remembered to detect replays,
+/// the oldest of them are forgotten when there are too many.
+pub(super) struct Nonces {
+    lifetime: Duration,
+    random: Random,
+    /// Time of nonces is counted from it
+    start: Instant,
+    capacity: usize,
+    used: Mutex<UsedNonces>,
+}
+
+impl Nonces {
+    pub(super) fn new(lifetime: Duration) -> Nonces {
+        Nonces {
+            lifetime,
+            random: Random::new(),
+            start: Instant::now(),
+            capacity: USED_NONCES_CAPACITY,
+            used: Mutex::new(UsedNonces {
+                nonces: HashMap::new(),
+                forgotten_before: 0,
+            }),
+        }
+    }
+
+    pub(super) fn set_lifetime(&mut self, lifetime: Duration) {
+        self.lifetime = lifetime;
+    }
+
+    pub(super) fn random_hex(&self) -> String {
+        self.random.hex()
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    fn is_expired(&self, time: u64, now: u64) -> bool {
+        u128::from(now - time) >= self.lifetime.as_millis()
+    }
+
+    /// 32 hex digits
+    fn mac(&self, time: &str, salt: &str, realm: &str) -> String {
+        let key = self.random.key.as_bytes();
+        let mut mac = SipDigestAlgorithm::Sha256.hash(&[
+            key,
+            time.as_bytes(),
+            salt.as_bytes(),
+            realm.as_bytes(),
+            key,
+        ]);
+        mac.truncate(32);
+        mac
+    }
+
+    /// New nonce for `realm`, 64 hex digits
+    pub(super) fn issue(&self, realm: &str) -> String {
+        let time = format!("{:016x}", self.now());
+        let mut salt = self.random.hex();
+        salt.truncate(16);
+        let mac = self.mac(&time, &salt, realm);
+        format!("{}{}{}", time, salt, mac)
+    }
+
+    /// Checks nonce and remembers `nc` if nonce is valid.
+    /// Credentials without nonce-count (rfc2069) can't be checked for replay.
+    pub(super) fn check(&self, nonce: &str, realm: &str, nc: Option<u32>) -> NonceCheck {
+        if nonce.len() != 64 || !nonce.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return NonceCheck::Unknown;
+        }
+        let (time, salt, mac) = (&nonce[..16], &nonce[16..32], &nonce[32..]);
+        if !constant_time_eq(self.mac(time, salt, realm).as_bytes(), mac.as_bytes()) {
+            return NonceCheck::Unknown;
+        }
+        let now = self.now();
+        let time = match u64::from_str_radix(time, 16) {
+            Ok(time) if time <= now => time,
+            _ => return NonceCheck::Unknown,
+        };
+        if self.is_expired(time, now) {
+            return NonceCheck::Stale;
+        }
+        let nc = match nc {
+            Some(0) => return NonceCheck::Replayed,
+            Some(nc) => nc,
+            None => return NonceCheck::Valid,
+        };
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(used_nonce) = used.nonces.get_mut(nonce) {
+            if nc <= used_nonce.last_nc {
+                return NonceCheck::Replayed;
+            }
+            used_nonce.last_nc = nc;
+            return NonceCheck::Valid;
+        }
+        if time < used.forgotten_before {
+            return NonceCheck::Stale;
+        }
+        if used.nonces.len() >= self.capacity {
+            self.forget(&mut used, now);
+        }
+        used.nonces
+            .insert(nonce.to_string(), UsedNonce { time, last_nc: nc });
+        NonceCheck::Valid
+    }
+
+    /// Forgets expired nonces and, if it is not enough, the older half of nonces
+    fn forget(&self, used: &mut UsedNonces, now: u64) {
+        used.nonces
+            .retain(|_, used_nonce| !self.is_expired(used_nonce.time, now));
+        if used.nonces.len() <= self.capacity / 2 {
+            return;
+        }
+        let mut times: Vec<u64> = used.nonces.values().map(|n| n.time).collect();
+        times.sort_unstable();
+        let last_forgotten = times[(times.len() - 1) / 2];
+        used.nonces
+            .retain(|_, used_nonce| used_nonce.time > last_forgotten);
+        used.forgotten_before = used.forgotten_before.max(last_forgotten + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_test() {
+        let random = Random::new();
+        let hex = random.hex();
+        assert_eq!(hex.len(), 32);
+        assert!(hex.bytes().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(hex, random.hex());
+        assert_ne!(hex, Random::new().hex());
+    }
+
+    #[test]
+    fn nonce_test() {
+        let nonces = Nonces::new(Duration::from_secs(60));
+        let nonce = nonces.issue("biloxi.com");
+        assert_eq!(nonce.len(), 64);
+        assert_ne!(nonce, nonces.issue("biloxi.com"));
+
+        assert_eq!(
+            nonces.check(&nonce, "atlanta.com", Some(1)),
+            NonceCheck::Unknown
+        );
+        assert_eq!(
+            nonces.check("0123", "biloxi.com", Some(1)),
+            NonceCheck::Unknown
+        );
+        let mut forged = nonce.clone().into_bytes();
+        forged[0] = if forged[0] == b'f' { b'e' } else { b'f' };
+        assert_eq!(
+            nonces.check(&String::from_utf8(forged).unwrap(), "biloxi.com", Some(1)),
+            NonceCheck::Unknown
+        );
+        assert_eq!(
+            Nonces::new(Duration::from_secs(60)).check(&nonce, "biloxi.com", Some(1)),
+            NonceCheck::Unknown
+        );
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(0)),
+            NonceCheck::Replayed
+        );
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(1)),
+            NonceCheck::Valid
+        );
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(1)),
+            NonceCheck::Replayed
+        );
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(3)),
+            NonceCheck::Valid
+        );
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(2)),
+            NonceCheck::Replayed
+        );
+        assert_eq!(nonces.check(&nonce, "biloxi.com", None), NonceCheck::Valid);
+
+        let nonces = Nonces::new(Duration::from_secs(0));
+        let nonce = nonces.issue("biloxi.com");
+        assert_eq!(
+            nonces.check(&nonce, "biloxi.com", Some(1)),
+            NonceCheck::Stale
+        );
+    }
+
+    #[test]
+    fn used_nonces_capacity() {
+        let mut nonces = Nonces::new(Duration::from_secs(60));
+        nonces.capacity = 2;
+        let first = nonces.issue("biloxi.com");
+        std::thread::sleep(Duration::from_millis(2));
+        let second = nonces.issue("biloxi.com");
+        let third = nonces.issue("biloxi.com");
+        for nonce in &[&first, &second, &third] {
+            assert_eq!(
+                nonces.check(nonce, "biloxi.com", Some(1)),
+                NonceCheck::Valid
+            );
+        }
+        // The oldest nonce is forgotten, its replay can't be detected
+        assert_eq!(
+            nonces.check(&first, "biloxi.com", Some(2)),
+            NonceCheck::Stale
+        );
+        assert_eq!(
+            nonces.check(&third, "biloxi.com", Some(1)),
+            NonceCheck::Replayed
+        );
+        assert_eq!(nonces.used.lock().unwrap().nonces.len(), 2);
+    }
+}
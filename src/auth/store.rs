@@ -0,0 +1,245 @@
+use sipmsg::SipDigestAlgorithm;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Source of user secrets for `AuthServer`.
+/// Implementations must be safe to call from several threads if the server is shared.
+pub trait CredentialStore {
+    /// `H(username:realm:password)` computed with hash function of `algorithm`
+    /// (`SipDigestAlgorithm::ha1`), `None` if the user is unknown in the realm.
+    fn ha1(&self, realm: &str, username: &str, algorithm: SipDigestAlgorithm) -> Option<String>;
+}
+
+/// `-sess` variants have the same HA1 as the plain algorithm
+fn hash_function(algorithm: SipDigestAlgorithm) -> SipDigestAlgorithm {
+    match algorithm {
+        SipDigestAlgorithm::Md5 | SipDigestAlgorithm::Md5Sess => SipDigestAlgorithm::Md5,
+        SipDigestAlgorithm::Sha256 | SipDigestAlgorithm::Sha256Sess => SipDigestAlgorithm::Sha256,
+        SipDigestAlgorithm::Sha512_256 | SipDigestAlgorithm::Sha512_256Sess => {
+            SipDigestAlgorithm::Sha512_256
+        }
+    }
+}
+
+enum Secret {
+    Password(String),
+    /// HA1 for each hash function
+    Ha1(Vec<(SipDigestAlgorithm, String)>),
+}
+
+/// Users are kept in memory, passwords can be replaced with HA1 values.
+/// ```rust
+/// use sipcore::auth::{CredentialStore, MemoryStore};
+/// use sipcore::SipDigestAlgorithm;
+///
+/// let mut store = MemoryStore::new();
+/// store.add_password("biloxi.com", "bob", "zanzibar");
+/// assert_eq!(
+///     store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Md5Sess),
+///     Some(SipDigestAlgorithm::Md5.ha1("bob", "biloxi.com", "zanzibar"))
+/// );
+/// assert_eq!(store.ha1("atlanta.com", "bob", SipDigestAlgorithm::Md5), None);
+/// ```
+#[derive(Default)]
+pub struct MemoryStore {
+    /// (realm, username) -> secret
+    users: HashMap<(String, String), Secret>,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    /// Adds user or replaces the secret of user
+    pub fn add_password(&mut self, realm: &str, username: &str, password: &str) {
+        self.users.insert(
+            (realm.to_string(), username.to_string()),
+            Secret::Password(password.to_string()),
+        );
+    }
+
+    /// Adds HA1 of user for hash function of `algorithm`.
+    /// Several values can be added for different hash functions, the password is replaced.
+    pub fn add_ha1(
+        &mut self,
+        realm: &str,
+        username: &str,
+        algorithm: SipDigestAlgorithm,
+        ha1: &str,
+    ) {
+        let algorithm = hash_function(algorithm);
+        let key = (realm.to_string(), username.to_string());
+        let ha1 = ha1.to_ascii_lowercase();
+        match self.users.get_mut(&key) {
+            Some(Secret::Ha1(values)) => {
+                values.retain(|(a, _)| *a != algorithm);
+                values.push((algorithm, ha1));
+            }
+            _ => {
+                self.users.insert(key, Secret::Ha1(vec![(algorithm, ha1)]));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, realm: &str, username: &str) {
+        self.users
+            .remove(&(realm.to_string(), username.to_string()));
+    }
+}
+
+impl CredentialStore for MemoryStore {
+    fn ha1(&self, realm: &str, username: &str, algorithm: SipDigestAlgorithm) -> Option<String> {
+        match self.users.get(&(realm.to_string(), username.to_string()))? {
+            Secret::Password(password) => Some(algorithm.ha1(username, realm, password)),
+            Secret::Ha1(values) => {
+                let algorithm = hash_function(algorithm);
+                values
+                    .iter()
+                    .find(|(a, _)| *a == algorithm)
+                    .map(|(_, ha1)| ha1.clone())
+            }
+        }
+    }
+}
+
+/// Users are loaded from a file in the format of Apache `htdigest`,
+/// hash function can be added before HA1 (MD5 by default):
+/// ```text
+/// # username:realm:[algorithm:]HA1
+/// bob:biloxi.com:a0d7add5ca03306b8cf3d9ec1f7d8e0d
+/// bob:biloxi.com:SHA-256:1bd7e4da3d8a1f0c27b7b1c0b63e05f1cce10a01fdc0b0c3e7bdd2b6d1f2ba9d
+/// ```
+/// Passwords are not stored in the file. `reload` reads the file again.
+pub struct FileStore {
+    path: PathBuf,
+    users: RwLock<MemoryStore>,
+}
+
+impl FileStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<FileStore> {
+        let path = path.as_ref().to_path_buf();
+        let users = FileStore::load(&path)?;
+        Ok(FileStore {
+            path,
+            users: RwLock::new(users),
+        })
+    }
+
+    /// Replaces users with the content of file, users are kept if the file is invalid
+    pub fn reload(&self) -> io::Result<()> {
+        let users = FileStore::load(&self.path)?;
+        *self.users.write().unwrap_or_else(|e| e.into_inner()) = users;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> io::Result<MemoryStore> {
+        let content = fs::read_to_string(path)?;
+        let mut users = MemoryStore::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid_line = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: invalid line {}", path.display(), idx + 1),
+                )
+            };
+            let fields: Vec<&str> = line.split(':').collect();
+            let (username, realm, algorithm, ha1) = match fields[..] {
+                [username, realm, ha1] => (username, realm, SipDigestAlgorithm::Md5, ha1),
+                [username, realm, algorithm, ha1] => {
+                    let algorithm =
+                        SipDigestAlgorithm::from_str(algorithm).ok_or_else(invalid_line)?;
+                    (username, realm, algorithm, ha1)
+                }
+                _ => return Err(invalid_line()),
+            };
+            let hex_len = match hash_function(algorithm) {
+                SipDigestAlgorithm::Md5 => 32,
+                _ => 64,
+            };
+            if ha1.len() != hex_len || !ha1.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid_line());
+            }
+            users.add_ha1(realm, username, algorithm, ha1);
+        }
+        Ok(users)
+    }
+}
+
+impl CredentialStore for FileStore {
+    fn ha1(&self, realm: &str, username: &str, algorithm: SipDigestAlgorithm) -> Option<String> {
+        let users = self.users.read().unwrap_or_else(|e| e.into_inner());
+        users.ha1(realm, username, algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_test() {
+        let path = std::env::temp_dir().join(format!("sipcore-htdigest-{}", std::process::id()));
+        let md5 = SipDigestAlgorithm::Md5.ha1("bob", "biloxi.com", "zanzibar");
+        let sha256 = SipDigestAlgorithm::Sha256.ha1("bob", "biloxi.com", "zanzibar");
+        let alice = SipDigestAlgorithm::Md5.ha1("alice", "atlanta.com", "secret");
+        fs::write(
+            &path,
+            format!(
+                "# users\nbob:biloxi.com:{}\n\nbob:biloxi.com:SHA-256:{}\nalice:atlanta.com:{}\n",
+                md5,
+                sha256,
+                alice.to_uppercase()
+            ),
+        )
+        .unwrap();
+
+        let store = FileStore::open(&path).unwrap();
+        assert_eq!(
+            store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Md5),
+            Some(md5.clone())
+        );
+        assert_eq!(
+            store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Sha256Sess),
+            Some(sha256)
+        );
+        assert_eq!(
+            store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Sha512_256),
+            None
+        );
+        assert_eq!(
+            store.ha1("atlanta.com", "alice", SipDigestAlgorithm::Md5),
+            Some(alice)
+        );
+        assert_eq!(
+            store.ha1("atlanta.com", "bob", SipDigestAlgorithm::Md5),
+            None
+        );
+
+        // Invalid file doesn't change users
+        fs::write(&path, format!("bob:biloxi.com:SHA-256:{}\n", md5)).unwrap();
+        let e = store.reload().unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(e.to_string().ends_with("invalid line 1"));
+        assert_eq!(
+            store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Md5),
+            Some(md5)
+        );
+
+        fs::write(&path, "").unwrap();
+        store.reload().unwrap();
+        assert_eq!(
+            store.ha1("biloxi.com", "bob", SipDigestAlgorithm::Md5),
+            None
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1 +1,5 @@
 pub use sipmsg::*;
+
+pub use sdpmsg as sdp;
+
+pub mod auth;
@@ -0,0 +1,394 @@
+use sipcore::auth::*;
+use sipcore::*;
+
+use std::time::Duration;
+
+const INVITE: &str = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+Max-Forwards: 70\r\n\
+To: Bob <sip:bob@biloxi.com>\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+CSeq: 314159 INVITE\r\n\
+Contact: <sip:alice@pc33.atlanta.com>\r\n\
+Content-Length: 0\r\n\r\n";
+
+fn challenge_response(result: AuthResult) -> SipResponse {
+    match result {
+        AuthResult::Challenge(response) => response.build().unwrap(),
+        AuthResult::Authorized(user) => panic!("authorized {:?}", user),
+    }
+}
+
+fn authorize<'a>(
+    request: &SipRequest<'a>,
+    challenge: &SipHeader,
+    password: &str,
+    nc: u32,
+) -> SipRequest<'a> {
+    let challenge = SipDigestChallenge::from_header(challenge).unwrap();
+    let credentials = challenge.credentials(request, "alice", password, "0a4f113b", nc);
+    let mut request = request.clone();
+    request
+        .headers
+        .push_back(credentials.proxy_authorization().unwrap());
+    request
+}
+
+#[test]
+fn proxy_auth() {
+    let mut store = MemoryStore::new();
+    store.add_password("atlanta.com", "alice", "secret");
+    let server = AuthServer::new(store, ChallengeKind::Proxy, "biloxi.com")
+        .add_realm("atlanta.com")
+        .algorithms(&[SipDigestAlgorithm::Sha256, SipDigestAlgorithm::Md5Sess]);
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+
+    // Realm is chosen by From host
+    let response = challenge_response(server.authenticate(&request));
+    assert_eq!(
+        response.sl.status_code,
+        SipResponseStatusCode::ProxyAuthenticationRequired
+    );
+    assert!(response.headers.to_addr().unwrap().tag.is_some());
+    let challenges: Vec<SipDigestChallenge> = response
+        .headers
+        .get_rfc(SipRFCHeader::ProxyAuthenticate)
+        .unwrap()
+        .iter()
+        .map(|h| SipDigestChallenge::from_header(h).unwrap())
+        .collect();
+    assert_eq!(challenges.len(), 2);
+    assert_eq!(challenges[0].algorithm, SipDigestAlgorithm::Sha256);
+    assert_eq!(challenges[1].algorithm, SipDigestAlgorithm::Md5Sess);
+    assert_eq!(challenges[0].nonce, challenges[1].nonce);
+    assert_eq!(challenges[0].realm, "atlanta.com");
+    assert!(!challenges[0].stale);
+
+    // All challenges share nonce, each algorithm is checked with the new one
+    for idx in 0..challenges.len() {
+        let response = challenge_response(server.authenticate(&request));
+        let challenge = &response
+            .headers
+            .get_rfc(SipRFCHeader::ProxyAuthenticate)
+            .unwrap()[idx];
+        let authorized = authorize(&request, challenge, "secret", 1);
+        match server.authenticate(&authorized) {
+            AuthResult::Authorized(user) => {
+                assert_eq!(user.username, "alice");
+                assert_eq!(user.realm, "atlanta.com");
+            }
+            AuthResult::Challenge(_) => panic!("not authorized"),
+        }
+        // Replay
+        challenge_response(server.authenticate(&authorized));
+        let authorized = authorize(&request, challenge, "secret", 2);
+        assert!(matches!(
+            server.authenticate(&authorized),
+            AuthResult::Authorized(_)
+        ));
+
+        // Wrong password
+        let authorized = authorize(&request, challenge, "secret2", 3);
+        let response = challenge_response(server.authenticate(&authorized));
+        let challenge = &response
+            .headers
+            .get_rfc(SipRFCHeader::ProxyAuthenticate)
+            .unwrap()[0];
+        assert!(!SipDigestChallenge::from_header(challenge).unwrap().stale);
+
+        // Credentials are for other request
+        let mut authorized = authorize(&request, challenge, "secret", 1);
+        authorized.rl.uri = Uri::Sip(SipUri::new(
+            SipRequestUriScheme::SIP,
+            "carol.biloxi.com",
+            None,
+        ));
+        challenge_response(server.authenticate(&authorized));
+    }
+
+    // Credentials for the other realm are ignored
+    let credentials = SipDigestChallenge::new("chicago.com", "ea9c8e88df84f1cec4341ae6cbe5a359")
+        .credentials(&request, "alice", "secret", "0a4f113b", 1);
+    let mut authorized = request.clone();
+    authorized
+        .headers
+        .push_back(credentials.proxy_authorization().unwrap());
+    challenge_response(server.authenticate(&authorized));
+}
+
+#[test]
+fn unknown_nonce() {
+    let mut store = MemoryStore::new();
+    store.add_password("atlanta.com", "alice", "secret");
+    let server = AuthServer::new(store, ChallengeKind::Proxy, "atlanta.com");
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+
+    // Nonce of other server, digest is right
+    let challenge = SipDigestChallenge::new("atlanta.com", "dcd98b7102dd2f0e8b11d0f600bfb0c093");
+    let credentials = challenge.credentials(&request, "alice", "secret", "0a4f113b", 1);
+    let mut authorized = request.clone();
+    authorized
+        .headers
+        .push_back(credentials.proxy_authorization().unwrap());
+    let response = challenge_response(server.authenticate(&authorized));
+    let challenge = &response
+        .headers
+        .get_rfc(SipRFCHeader::ProxyAuthenticate)
+        .unwrap()[0];
+    assert!(SipDigestChallenge::from_header(challenge).unwrap().stale);
+
+    // Wrong password
+    let response =
+        challenge_response(server.authenticate(&authorize(&request, challenge, "wrong", 1)));
+    let challenge = &response
+        .headers
+        .get_rfc(SipRFCHeader::ProxyAuthenticate)
+        .unwrap()[0];
+    assert!(!SipDigestChallenge::from_header(challenge).unwrap().stale);
+}
+
+#[test]
+fn stale_nonce() {
+    let mut store = MemoryStore::new();
+    store.add_password("biloxi.com", "alice", "secret");
+    let server = AuthServer::new(store, ChallengeKind::Www, "biloxi.com")
+        .nonce_lifetime(Duration::from_secs(0));
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+
+    let response = challenge_response(server.authenticate(&request));
+    assert_eq!(response.sl.status_code, SipResponseStatusCode::Unauthorized);
+    let challenge = &response
+        .headers
+        .get_rfc(SipRFCHeader::WWWAuthenticate)
+        .unwrap()[0];
+    let challenge = SipDigestChallenge::from_header(challenge).unwrap();
+    assert_eq!(challenge.realm, "biloxi.com");
+    let credentials = challenge.credentials(&request, "alice", "secret", "0a4f113b", 1);
+    let mut authorized = request.clone();
+    authorized
+        .headers
+        .push_back(credentials.authorization().unwrap());
+
+    let response = challenge_response(server.authenticate(&authorized));
+    let challenge = &response
+        .headers
+        .get_rfc(SipRFCHeader::WWWAuthenticate)
+        .unwrap()[0];
+    assert!(SipDigestChallenge::from_header(challenge).unwrap().stale);
+
+    // Proxy-Authorization is not checked by server of user agent
+    let mut authorized = request.clone();
+    authorized
+        .headers
+        .push_back(credentials.proxy_authorization().unwrap());
+    let response = challenge_response(server.authenticate(&authorized));
+    assert!(response
+        .headers
+        .get_rfc(SipRFCHeader::ProxyAuthenticate)
+        .is_none());
+}
+
+/// Forking proxy collects challenges of branches
+fn forked_response<'a, S: CredentialStore, T: CredentialStore>(
+    request: &SipRequest<'a>,
+    uas: &AuthServer<S>,
+    proxy: &AuthServer<T>,
+) -> SipResponse<'a> {
+    let mut response = challenge_response(uas.authenticate(request));
+    let proxy_response = challenge_response(proxy.authenticate(request));
+    for header in proxy_response
+        .headers
+        .get_rfc(SipRFCHeader::ProxyAuthenticate)
+        .unwrap()
+    {
+        response.headers.push_back(header.clone());
+    }
+    response.sl.status_code = SipResponseStatusCode::ProxyAuthenticationRequired;
+    response
+}
+
+fn credentials_of<'a>(
+    request: &'a SipRequest,
+    kind: ChallengeKind,
+) -> Vec<SipDigestCredentials<'a>> {
+    request
+        .headers
+        .get_rfc(kind.credentials_header())
+        .unwrap()
+        .iter()
+        .map(|h| SipDigestCredentials::from_header(h).unwrap())
+        .collect()
+}
+
+#[test]
+fn client_retry() {
+    let mut store = MemoryStore::new();
+    store.add_password("atlanta.com", "alice", "secret");
+    store.add_password("biloxi.com", "alice", "secret2");
+    let proxy = AuthServer::new(store, ChallengeKind::Proxy, "atlanta.com");
+    let mut store = MemoryStore::new();
+    store.add_password("biloxi.com", "alice", "secret2");
+    let uas = AuthServer::new(store, ChallengeKind::Www, "biloxi.com")
+        .algorithms(&[SipDigestAlgorithm::Md5Sess, SipDigestAlgorithm::Sha512_256]);
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+
+    // No account for biloxi.com
+    let response = forked_response(&request, &uas, &proxy);
+    let mut client = AuthClient::new().account("atlanta.com", "alice", "secret");
+    let retry = client.handle_response(&request, &response).unwrap();
+    assert!(retry.headers.get_rfc(SipRFCHeader::Authorization).is_none());
+    assert!(matches!(
+        proxy.authenticate(&retry),
+        AuthResult::Authorized(_)
+    ));
+
+    let mut client = AuthClient::new()
+        .account("atlanta.com", "alice", "secret")
+        .default_account("alice", "secret2");
+    let response = forked_response(&request, &uas, &proxy);
+    let retry = client.handle_response(&request, &response).unwrap();
+    assert_eq!(retry.headers.cseq().unwrap().seq, 314160);
+    let branch = retry.headers.top_via().unwrap().branch.unwrap().to_string();
+    assert!(branch.starts_with("z9hG4bK"));
+    assert_ne!(branch, "z9hG4bK776asdhds");
+    assert_eq!(retry.headers.vias().count(), 1);
+    // The strongest algorithm
+    let credentials = credentials_of(&retry, ChallengeKind::Www);
+    assert_eq!(credentials.len(), 1);
+    assert_eq!(credentials[0].algorithm, SipDigestAlgorithm::Sha512_256);
+    let credentials = credentials_of(&retry, ChallengeKind::Proxy);
+    assert_eq!(credentials.len(), 1);
+    assert_eq!(credentials[0].algorithm, SipDigestAlgorithm::Sha256);
+    assert!(matches!(
+        proxy.authenticate(&retry),
+        AuthResult::Authorized(_)
+    ));
+    assert!(matches!(
+        uas.authenticate(&retry),
+        AuthResult::Authorized(_)
+    ));
+
+    // Cached credentials for the next request
+    let mut next = request.clone();
+    client.authorize(&mut next);
+    assert_eq!(credentials_of(&next, ChallengeKind::Www)[0].nc, Some(2));
+    assert_eq!(credentials_of(&next, ChallengeKind::Proxy)[0].nc, Some(2));
+    assert!(matches!(
+        proxy.authenticate(&next),
+        AuthResult::Authorized(_)
+    ));
+    assert!(matches!(uas.authenticate(&next), AuthResult::Authorized(_)));
+    // Authorize replaces credentials
+    client.authorize(&mut next);
+    assert_eq!(credentials_of(&next, ChallengeKind::Www).len(), 1);
+    assert_eq!(credentials_of(&next, ChallengeKind::Www)[0].nc, Some(3));
+
+    // Rejected credentials are not repeated
+    let response = challenge_response(uas.authenticate(&request));
+    let mut client = AuthClient::new()
+        .default_account("alice", "secret")
+        .algorithms(&[SipDigestAlgorithm::Md5Sess]);
+    let retry = client.handle_response(&request, &response).unwrap();
+    assert_eq!(
+        credentials_of(&retry, ChallengeKind::Www)[0].algorithm,
+        SipDigestAlgorithm::Md5Sess
+    );
+    let response = challenge_response(uas.authenticate(&retry));
+    assert!(client.handle_response(&retry, &response).is_none());
+
+    // Unsupported algorithm
+    let mut client = AuthClient::new()
+        .default_account("alice", "secret2")
+        .algorithms(&[SipDigestAlgorithm::Sha256]);
+    assert!(client.handle_response(&request, &response).is_none());
+}
+
+#[test]
+fn client_stale_and_next_nonce() {
+    let mut store = MemoryStore::new();
+    store.add_password("biloxi.com", "alice", "secret");
+    let uas = AuthServer::new(store, ChallengeKind::Www, "biloxi.com")
+        .nonce_lifetime(Duration::from_secs(0));
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+    let mut client = AuthClient::new().default_account("alice", "secret");
+
+    let response = challenge_response(uas.authenticate(&request));
+    let retry = client.handle_response(&request, &response).unwrap();
+    let response = challenge_response(uas.authenticate(&retry));
+    // stale=true, credentials are repeated with the new nonce
+    let retry2 = client.handle_response(&retry, &response).unwrap();
+    assert_eq!(retry2.headers.cseq().unwrap().seq, 314161);
+    assert_ne!(
+        credentials_of(&retry, ChallengeKind::Www)[0].nonce,
+        credentials_of(&retry2, ChallengeKind::Www)[0].nonce
+    );
+
+    let ok = "SIP/2.0 200 OK\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds\r\n\
+To: Bob <sip:bob@biloxi.com>;tag=a6c85cf\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+Call-ID: a84b4c76e66710@pc33.atlanta.com\r\n\
+CSeq: 314161 INVITE\r\n\
+Authentication-Info: qop=auth, rspauth=\"6629fae49393a05397450978507c4ef1\", \
+cnonce=\"0a4f113b\", nc=00000001, nextnonce=\"47364c23432d2e131a5fb210812c\"\r\n\
+Content-Length: 0\r\n\r\n";
+    let (_, ok) = SipResponse::parse(ok.as_bytes()).unwrap();
+    assert!(client.handle_response(&retry2, &ok).is_none());
+    let mut next = request.clone();
+    client.authorize(&mut next);
+    let credentials = credentials_of(&next, ChallengeKind::Www);
+    assert_eq!(credentials[0].nonce, "47364c23432d2e131a5fb210812c");
+    assert_eq!(credentials[0].nc, Some(1));
+}
+
+#[test]
+fn client_forgotten_nonce() {
+    let mut store = MemoryStore::new();
+    store.add_password("biloxi.com", "alice", "secret");
+    let uas = AuthServer::new(store, ChallengeKind::Www, "biloxi.com");
+    let (_, request) = SipRequest::parse(INVITE.as_bytes()).unwrap();
+    let mut client = AuthClient::new().default_account("alice", "secret");
+
+    let response = challenge_response(uas.authenticate(&request));
+    let retry = client.handle_response(&request, &response).unwrap();
+    assert!(matches!(
+        uas.authenticate(&retry),
+        AuthResult::Authorized(_)
+    ));
+    let ok = retry
+        .make_response(SipResponseStatusCode::OK, None, "a6c85cf")
+        .build()
+        .unwrap();
+    assert!(client.handle_response(&retry, &ok).is_none());
+
+    // Server forgot the accepted nonce and doesn't say it's stale, request is repeated once
+    let mut next = request.clone();
+    client.authorize(&mut next);
+    let response = uas.challenge(&next, None, false).build().unwrap();
+    let retry = client.handle_response(&next, &response).unwrap();
+    assert_ne!(
+        credentials_of(&next, ChallengeKind::Www)[0].nonce,
+        credentials_of(&retry, ChallengeKind::Www)[0].nonce
+    );
+    assert!(matches!(
+        uas.authenticate(&retry),
+        AuthResult::Authorized(_)
+    ));
+    let response = uas.challenge(&retry, None, false).build().unwrap();
+    assert!(client.handle_response(&retry, &response).is_none());
+
+    // Expired nonce
+    let mut store = MemoryStore::new();
+    store.add_password("biloxi.com", "alice", "secret");
+    let uas = AuthServer::new(store, ChallengeKind::Www, "biloxi.com")
+        .nonce_lifetime(Duration::from_millis(10));
+    let mut client = AuthClient::new().default_account("alice", "secret");
+    let response = challenge_response(uas.authenticate(&request));
+    client.handle_response(&request, &response).unwrap();
+    std::thread::sleep(Duration::from_millis(30));
+    let mut next = request.clone();
+    client.authorize(&mut next);
+    let response = challenge_response(uas.authenticate(&next));
+    assert!(client.handle_response(&next, &response).is_some());
+}